@@ -0,0 +1,87 @@
+//! Frame times of the month, week and day views over a large database, with
+//! the event cache and with the cache dropped before every frame (what each
+//! frame cost before it existed). Run with `cargo bench`; `BENCH_EVENTS`
+//! sets the number of events (20000 by default).
+
+use anyhow::Result;
+use calendar_app::app::{App, AppState};
+use calendar_app::models::config::Config;
+use calendar_app::models::event::Event;
+use calendar_app::storage::db::{create_event, open_db};
+use calendar_app::ui;
+use chrono::{Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use ratatui::{backend::TestBackend, Terminal};
+use rusqlite::Connection;
+use std::time::Instant;
+
+const FRAMES: u32 = 200;
+
+fn main() -> Result<()> {
+    let count: i64 = std::env::var("BENCH_EVENTS").ok().and_then(|n| n.parse().ok()).unwrap_or(20_000);
+    let path = std::env::temp_dir().join(format!("calendar-app-bench-{}.db", std::process::id()));
+    let conn = open_db(&path)?;
+    seed(&conn, count)?;
+
+    let mut config = Config::default();
+    config.calendar.timezone = Some("UTC".to_string());
+    let mut app = App::new(config, conn);
+    app.selected_date = NaiveDate::from_ymd_opt(2026, 6, 17).unwrap_or_default();
+    app.selected_time = NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default();
+    let mut terminal = Terminal::new(TestBackend::new(160, 50))?;
+
+    println!("{} events, {} frames per view", count, FRAMES);
+    for (name, state) in [("month", AppState::Month), ("week", AppState::Week), ("day", AppState::Day)] {
+        app.state = state;
+        let cached = frame_time(&mut terminal, &mut app, false)?;
+        let uncached = frame_time(&mut terminal, &mut app, true)?;
+        println!(
+            "{:<6} cached {:>9.3} ms/frame   uncached {:>9.3} ms/frame   {:>6.1}x",
+            name,
+            cached,
+            uncached,
+            uncached / cached
+        );
+    }
+
+    drop(app);
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+/// Average milliseconds per frame, refreshing the cache the way `run_app`
+/// does, or dropping it first when `invalidate` is set.
+fn frame_time(terminal: &mut Terminal<TestBackend>, app: &mut App, invalidate: bool) -> Result<f64> {
+    let started = Instant::now();
+    for _ in 0..FRAMES {
+        if invalidate {
+            app.event_cache.invalidate();
+        }
+        app.event_cache.refresh(&app.conn)?;
+        terminal.draw(|f| ui::draw(f, app))?;
+    }
+    Ok(started.elapsed().as_secs_f64() * 1000.0 / FRAMES as f64)
+}
+
+/// One-hour events through 2026, about `count / 365` a day, with a weekly
+/// series every thousand events.
+fn seed(conn: &Connection, count: i64) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    let year_start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    let per_day = (count / 365).max(1);
+    for i in 0..count {
+        let start = year_start + Duration::days(i / per_day) + Duration::minutes(8 * 60 + (i % per_day) * 10);
+        let event = Event {
+            title: format!("Event {}", i),
+            start_datetime: start,
+            end_datetime: start + Duration::hours(1),
+            timezone: Some("UTC".to_string()),
+            recurrence: if i % 1000 == 0 { "FREQ=WEEKLY".parse().ok() } else { None },
+            created_at: year_start,
+            updated_at: year_start,
+            ..Default::default()
+        };
+        create_event(&tx, &event)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
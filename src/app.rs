@@ -1,41 +1,243 @@
+use crate::history::History;
+use crate::models::attendee::{parse_attendees, Attendee};
+use crate::models::calendar::Calendar;
 use crate::models::config::Config;
-use chrono::{NaiveDate, NaiveTime};
+use crate::models::event::Event;
+use crate::models::keys::KeyBindings;
+use crate::models::recurrence::RecurrenceScope;
+use crate::models::tags::{complete_tag, TagFilter};
+use crate::models::theme::Theme;
+use crate::models::time_input::{format_input, parse_input};
+use crate::models::timezone;
+use crate::storage::cache::EventCache;
+use crate::storage::db::get_calendars;
+use crate::ui::columns::{covers_slot, pack};
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
 use rusqlite::Connection;
-use tui_textarea::TextArea;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tui_textarea::{CursorMove, TextArea};
 
 pub enum AppState {
     Year,
     Month,
     Week,
     Day,
+    Agenda,
 }
 
+/// Number of days the agenda view lists, starting at the selected date.
+pub const AGENDA_DAYS: i64 = 30;
+
 pub enum InteractionMode {
     Navigation,
     Selection,
     TimeSlot,
     EventForm,
+    RecurrenceScope,
+    Calendars,
+    Search,
+    Move,
+    QuickAdd,
+    TagFilter,
+}
+
+pub enum ScopeAction {
+    Edit,
+    Delete,
+    Move(Duration),
+}
+
+/// The event occurrence that edit, delete, move and duplicate act on.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Focus {
+    pub event_id: i64,
+    /// Start of the focused occurrence.
+    pub start: DateTime<Utc>,
+}
+
+/// An event being shifted with the arrow keys before the move is saved.
+pub struct MoveState {
+    pub event: Event,
+    pub offset: Duration,
+}
+
+pub struct ScopePromptState {
+    pub action: ScopeAction,
+    pub event: Event,
+    pub selected: usize,
+}
+
+pub struct SearchState {
+    pub query: String,
+    pub results: Vec<Event>,
+    pub selected: usize,
+}
+
+pub struct QuickAddState {
+    pub input: String,
+    /// The event `input` parses to, or why it does not parse.
+    pub preview: Result<Event, String>,
+}
+
+pub struct TagFilterState {
+    pub input: String,
+    /// Why `input` does not parse, shown until it is edited.
+    pub error: Option<String>,
+    /// Tags of all events, listed under the input.
+    pub known_tags: Vec<String>,
+}
+
+/// Fields of the event form, in Tab order.
+#[derive(Clone, Copy, PartialEq)]
+pub enum FormField {
+    Title,
+    Start,
+    End,
+    Description,
+    Location,
+    Tags,
+    Attendees,
+    Repeat,
+    Reminders,
+    AllDay,
+    Calendar,
+}
+
+impl FormField {
+    pub const ALL: [FormField; 11] = [
+        FormField::Title,
+        FormField::Start,
+        FormField::End,
+        FormField::Description,
+        FormField::Location,
+        FormField::Tags,
+        FormField::Attendees,
+        FormField::Repeat,
+        FormField::Reminders,
+        FormField::AllDay,
+        FormField::Calendar,
+    ];
+
+    pub fn next(self) -> FormField {
+        let index = FormField::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        FormField::ALL[(index + 1) % FormField::ALL.len()]
+    }
 }
 
 pub struct EventFormState<'a> {
     pub title: TextArea<'a>,
     pub description: TextArea<'a>,
     pub location: TextArea<'a>,
-    pub start_datetime: chrono::NaiveDateTime,
-    pub end_datetime: chrono::NaiveDateTime,
-    pub focused_field: usize,
+    /// Tags separated by commas or spaces; see `tags::parse_tags`.
+    pub tags: TextArea<'a>,
+    /// Tags of all events, offered as completions in the tags field.
+    pub known_tags: Vec<String>,
+    /// Attendees separated by commas; see `attendee::parse_attendees`.
+    pub attendees: TextArea<'a>,
+    pub recurrence: TextArea<'a>,
+    pub reminders: TextArea<'a>,
+    /// Local start, as typed; see `time_input`.
+    pub start: TextArea<'a>,
+    /// Local end, or the last day of an all-day event.
+    pub end: TextArea<'a>,
+    pub all_day: bool,
+    pub calendar_id: Option<i64>,
+    pub timezone: Option<String>,
+    pub focused_field: FormField,
+    pub recurrence_id: Option<DateTime<Utc>>,
+    pub recurrence_scope: Option<RecurrenceScope>,
+    pub error: Option<String>,
+}
+
+impl EventFormState<'_> {
+    /// The start and end fields, parsed.
+    pub fn times(&self, time_pattern: &str) -> (Result<NaiveDateTime>, Result<NaiveDateTime>) {
+        let parse = |area: &TextArea| parse_input(&area.lines().join(""), self.all_day, time_pattern);
+        (parse(&self.start), parse(&self.end))
+    }
+
+    /// Messages shown under the start and end fields while they are invalid.
+    pub fn time_errors(&self, time_pattern: &str) -> (Option<String>, Option<String>) {
+        match self.times(time_pattern) {
+            (Err(start), end) => (Some(start.to_string()), end.err().map(|e| e.to_string())),
+            (Ok(_), Err(end)) => (None, Some(end.to_string())),
+            (Ok(start), Ok(end)) if self.all_day && end < start => {
+                (None, Some("last day is before the first".to_string()))
+            }
+            (Ok(start), Ok(end)) if !self.all_day && end <= start => {
+                (None, Some("must be after the start".to_string()))
+            }
+            _ => (None, None),
+        }
+    }
+
+    /// Rest of the tag being typed in the tags field, completed from `known_tags`.
+    pub fn tag_suggestion(&self) -> Option<&str> {
+        complete_tag(&self.tags.lines().join(" "), &self.known_tags)
+    }
+
+    pub fn parsed_attendees(&self) -> Result<Vec<Attendee>> {
+        parse_attendees(&self.attendees.lines().join(","))
+    }
+
+    /// Replaces the start and end fields, e.g. after all-day was toggled.
+    pub fn set_times(&mut self, start: NaiveDateTime, end: NaiveDateTime, time_pattern: &str) {
+        self.start = time_field(start, self.all_day, time_pattern);
+        self.end = time_field(end, self.all_day, time_pattern);
+    }
+}
+
+/// A start or end field holding `value`, with the cursor after it.
+pub fn time_field<'a>(value: NaiveDateTime, all_day: bool, time_pattern: &str) -> TextArea<'a> {
+    let mut field = TextArea::from([format_input(value, all_day, time_pattern)]);
+    field.move_cursor(CursorMove::End);
+    field
 }
 
 pub struct App<'a> {
     pub state: AppState,
     pub mode: InteractionMode,
     pub config: Config,
+    pub timezone: Tz,
+    /// First day of the week in the month, week and year views.
+    pub week_start: Weekday,
+    /// Length of a day or week grid slot.
+    pub slot: Duration,
     pub conn: Connection,
     pub event_form_state: Option<EventFormState<'a>>,
+    pub scope_prompt_state: Option<ScopePromptState>,
+    pub search_state: Option<SearchState>,
+    pub quick_add_state: Option<QuickAddState>,
+    pub tag_filter_state: Option<TagFilterState>,
+    /// Only events whose tags match are shown, in every view.
+    pub tag_filter: TagFilter,
     pub selected_event_id: Option<i64>,
     pub selected_date: NaiveDate,
     pub selected_time: NaiveTime,
     pub selection_start: Option<NaiveTime>,
+    pub calendars: Vec<Calendar>,
+    /// Highlighted entry of the agenda view, an index into `agenda_entries`.
+    pub agenda_selected: usize,
+    pub focus: Option<Focus>,
+    pub move_state: Option<MoveState>,
+    /// Highlighted row of the calendar list popup.
+    pub selected_calendar: usize,
+    /// Status message for the bottom row, shared with the terminal notifier
+    /// for reminders; cleared on the next key.
+    pub banner: Rc<RefCell<Option<String>>>,
+    pub history: History,
+    /// Every read of events goes through this; writes must invalidate it.
+    pub event_cache: EventCache,
+    pub keys: KeyBindings,
+    pub theme: Theme,
+    /// Whether the key binding overlay is open; any key closes it.
+    pub show_help: bool,
+    /// Whether the focused event's details are shown: beside the week and day
+    /// grids, or in a popup that any key closes in the other views.
+    pub show_details: bool,
 }
 
 impl<'a> App<'a> {
@@ -45,19 +247,184 @@ impl<'a> App<'a> {
             "month" => AppState::Month,
             "week" => AppState::Week,
             "day" => AppState::Day,
+            "agenda" => AppState::Agenda,
             _ => AppState::Month,
         };
 
+        let timezone = config.timezone();
+        // load_config has already rejected invalid bindings, themes and grid settings.
+        let keys = config.key_bindings().unwrap_or_default();
+        let theme = config.theme().unwrap_or_default();
+        let week_start = config.ui.week_start().unwrap_or(Weekday::Mon);
+        let slot = config.calendar.slot_interval().unwrap_or(Duration::minutes(30));
+        let calendars = get_calendars(&conn).unwrap_or_default();
+        let now = chrono::Utc::now().with_timezone(&timezone).naive_local();
+
         App {
             state: default_view,
             mode: InteractionMode::Navigation,
             config,
+            timezone,
+            week_start,
+            slot,
             conn,
             event_form_state: None,
+            scope_prompt_state: None,
+            search_state: None,
+            quick_add_state: None,
+            tag_filter_state: None,
+            tag_filter: TagFilter::default(),
             selected_event_id: None,
-            selected_date: chrono::Local::now().naive_local().date(),
-            selected_time: chrono::Local::now().naive_local().time(),
+            selected_date: now.date(),
+            selected_time: slot_start(now.time(), slot),
             selection_start: None,
+            calendars,
+            agenda_selected: 0,
+            focus: None,
+            move_state: None,
+            selected_calendar: 0,
+            banner: Rc::new(RefCell::new(None)),
+            history: History::default(),
+            event_cache: EventCache::default(),
+            keys,
+            theme,
+            show_help: false,
+            show_details: false,
         }
     }
+
+    /// Whether keys are going into a text field rather than to bound actions.
+    pub fn is_typing(&self) -> bool {
+        matches!(
+            self.mode,
+            InteractionMode::EventForm | InteractionMode::Search | InteractionMode::QuickAdd | InteractionMode::TagFilter
+        )
+    }
+
+    /// UTC timestamps bounding the local days `first..=last`.
+    pub fn day_range(&self, first: NaiveDate, last: NaiveDate) -> (i64, i64) {
+        timezone::day_range(self.timezone, first, last)
+    }
+
+    /// Occurrences in `[start, end)` from calendars that are currently
+    /// visible, of events matching the tag filter.
+    pub fn events_in_range(&self, start: i64, end: i64) -> Vec<Event> {
+        let mut events =
+            self.event_cache.events_in_range(&self.conn, start, end, &self.tag_filter).unwrap_or_default();
+        events.retain(|event| {
+            self.calendar(event.calendar_id)
+                .is_none_or(|calendar| calendar.visible)
+        });
+        events
+    }
+
+    /// Agenda rows from the selected date on: every event paired with each
+    /// day it covers, in chronological order.
+    pub fn agenda_entries(&self) -> Vec<(NaiveDate, Event)> {
+        let last = self.selected_date + Duration::days(AGENDA_DAYS - 1);
+        let (start, end) = self.day_range(self.selected_date, last);
+        let events = self.events_in_range(start, end);
+        let mut entries = Vec::new();
+        for date in self.selected_date.iter_days().take(AGENDA_DAYS as usize) {
+            // All-day and multi-day events lead each day, then timed ones by start.
+            let (mut day_events, timed): (Vec<&Event>, Vec<&Event>) = events
+                .iter()
+                .filter(|e| e.covers_date(self.timezone, date))
+                .partition(|e| e.is_multi_day(self.timezone));
+            day_events.extend(timed);
+            entries.extend(day_events.into_iter().map(|e| (date, e.clone())));
+        }
+        entries
+    }
+
+    /// Timed events overlapping the selected slot, left to right as the day
+    /// and week views lay them out.
+    pub fn slot_events(&self) -> Vec<Event> {
+        let (start, end) = self.day_range(self.selected_date, self.selected_date);
+        let events: Vec<Event> = self
+            .events_in_range(start, end)
+            .into_iter()
+            .filter(|e| !e.is_multi_day(self.timezone))
+            .collect();
+        let placements = pack(&events);
+        let slot_instants = timezone::local_instants(self.timezone, self.selected_date.and_time(self.selected_time));
+        let mut slot_events: Vec<(usize, Event)> = events
+            .into_iter()
+            .zip(placements)
+            .filter(|(event, _)| covers_slot(event, &slot_instants, self.slot))
+            .map(|(event, placement)| (placement.column, event))
+            .collect();
+        slot_events.sort_by_key(|(column, _)| *column);
+        slot_events.into_iter().map(|(_, event)| event).collect()
+    }
+
+    pub fn focus_on(&mut self, event: &Event) {
+        self.focus = event.id.map(|event_id| Focus {
+            event_id,
+            start: event.start_datetime,
+        });
+    }
+
+    pub fn is_focused(&self, event: &Event) -> bool {
+        self.focus
+            .is_some_and(|focus| event.id == Some(focus.event_id) && event.start_datetime == focus.start)
+    }
+
+    /// Whether the view shows details in a panel beside its grid rather than in a popup.
+    pub fn has_details_panel(&self) -> bool {
+        matches!(self.state, AppState::Week | AppState::Day)
+    }
+
+    /// The focused occurrence, unless it has since been changed, deleted or hidden.
+    pub fn focused_event(&self) -> Option<Event> {
+        let start = self.focus?.start.timestamp();
+        self.events_in_range(start - 1, start + 1)
+            .into_iter()
+            .find(|event| self.is_focused(event))
+    }
+
+    pub fn calendar(&self, id: Option<i64>) -> Option<&Calendar> {
+        self.calendars.iter().find(|c| c.id == id)
+    }
+
+    pub fn reload_calendars(&mut self) {
+        if let Ok(calendars) = get_calendars(&self.conn) {
+            self.calendars = calendars;
+        }
+    }
+
+    pub fn to_local(&self, datetime: DateTime<Utc>) -> chrono::NaiveDateTime {
+        timezone::to_local(self.timezone, datetime)
+    }
+
+    /// First day of the week containing `date`.
+    pub fn week_first_day(&self, date: NaiveDate) -> NaiveDate {
+        date - Duration::days(date.weekday().days_since(self.week_start) as i64)
+    }
+
+    /// Days of the week in display order.
+    pub fn weekdays(&self) -> Vec<Weekday> {
+        std::iter::successors(Some(self.week_start), |day| Some(day.succ())).take(7).collect()
+    }
+
+    /// Start times of the grid slots from `start_hour` up to `end_hour`.
+    pub fn slot_times(&self, start_hour: u32, end_hour: u32) -> Vec<NaiveTime> {
+        let step = self.slot.num_minutes().max(1) as usize;
+        (start_hour * 60..end_hour.min(24) * 60)
+            .step_by(step)
+            .filter_map(|minute| NaiveTime::from_hms_opt(minute / 60, minute % 60, 0))
+            .collect()
+    }
+
+    /// Today's date in the display timezone.
+    pub fn today(&self) -> NaiveDate {
+        self.to_local(Utc::now()).date()
+    }
+}
+
+/// Start of the grid slot of length `slot` containing `time`.
+pub fn slot_start(time: NaiveTime, slot: Duration) -> NaiveTime {
+    let step = slot.num_minutes().max(1) as u32;
+    let minute = (time.hour() * 60 + time.minute()) / step * step;
+    NaiveTime::from_hms_opt(minute / 60, minute % 60, 0).unwrap_or(time)
 }
@@ -0,0 +1,210 @@
+//! An in-memory CalDAV server for tests: one account with one calendar,
+//! answering the requests `CalDavClient` sends.
+
+use super::{percent_decode, HttpRequest, HttpResponse, Transport};
+use anyhow::Result;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use url::Url;
+
+pub const BASE: &str = "https://dav.example.com/dav/";
+pub const PRINCIPAL: &str = "/dav/principals/ana/";
+pub const HOME: &str = "/dav/calendars/ana/";
+pub const CALENDAR: &str = "/dav/calendars/ana/work/";
+
+#[derive(Default)]
+pub struct ServerState {
+    /// Objects by decoded path, with their ETag and data.
+    pub objects: BTreeMap<String, (String, String)>,
+    pub ctag: u32,
+    etags: u32,
+    /// List `@` in hrefs as `%40`, as many servers do.
+    pub encode_hrefs: bool,
+    /// List hrefs as absolute URLs.
+    pub absolute_hrefs: bool,
+    /// Leave the ETag header out of PUT responses.
+    pub omit_put_etag: bool,
+}
+
+impl ServerState {
+    fn next_etag(&mut self) -> String {
+        self.etags += 1;
+        format!("\"{}\"", self.etags)
+    }
+
+    fn href(&self, path: &str) -> String {
+        let path = if self.encode_hrefs { path.replace('@', "%40") } else { path.to_string() };
+        if self.absolute_hrefs {
+            format!("https://dav.example.com{}", path)
+        } else {
+            path
+        }
+    }
+
+    /// Stores `data` at `CALENDAR` + `name` as a change made by another client.
+    pub fn put(&mut self, name: &str, data: &str) {
+        let etag = self.next_etag();
+        self.objects.insert(format!("{}{}", CALENDAR, name), (etag, data.to_string()));
+        self.ctag += 1;
+    }
+
+    /// Like `put`, but without changing the ctag, as if the change raced the sync.
+    pub fn put_quietly(&mut self, name: &str, data: &str) {
+        self.put(name, data);
+        self.ctag -= 1;
+    }
+
+    pub fn delete(&mut self, name: &str) {
+        self.objects.remove(&format!("{}{}", CALENDAR, name));
+        self.ctag += 1;
+    }
+
+    /// Data of the only object whose data contains `text`.
+    pub fn find(&self, text: &str) -> Option<&str> {
+        let mut found = self.objects.values().filter(|(_, data)| data.contains(text));
+        let (_, data) = found.next()?;
+        assert!(found.next().is_none(), "several objects contain '{}'", text);
+        Some(data)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct FakeServer(pub Rc<RefCell<ServerState>>);
+
+/// A calendar object with one event.
+pub fn vcalendar(uid: &str, summary: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:{}\r\nSUMMARY:{}\r\n\
+         DTSTART:20261020T090000Z\r\nDTEND:20261020T100000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+        uid, summary
+    )
+}
+
+fn multistatus(responses: &[String]) -> HttpResponse {
+    HttpResponse {
+        status: 207,
+        etag: None,
+        body: format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/" xmlns:i="http://apple.com/ns/ical/">{}</d:multistatus>"#,
+            responses.concat()
+        ),
+    }
+}
+
+fn response(href: &str, props: &str) -> String {
+    format!(
+        "<d:response><d:href>{}</d:href><d:propstat><d:prop>{}</d:prop>\
+         <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+        href, props
+    )
+}
+
+fn status(status: u16) -> HttpResponse {
+    HttpResponse {
+        status,
+        etag: None,
+        body: String::new(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+impl Transport for FakeServer {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        let mut state = self.0.borrow_mut();
+        let path = percent_decode(Url::parse(request.url)?.path());
+        let header = |name: &str| {
+            request
+                .headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+        let current = state.objects.get(&path).map(|(etag, _)| etag.clone());
+        Ok(match (request.method, path.as_str(), header("Depth")) {
+            ("PROPFIND", "/dav/", _) => multistatus(&[response(
+                "/dav/",
+                &format!("<d:current-user-principal><d:href>{}</d:href></d:current-user-principal>", PRINCIPAL),
+            )]),
+            ("PROPFIND", PRINCIPAL, _) => multistatus(&[response(
+                PRINCIPAL,
+                &format!("<c:calendar-home-set><d:href>{}</d:href></c:calendar-home-set>", HOME),
+            )]),
+            ("PROPFIND", HOME, _) => multistatus(&[
+                response(HOME, "<d:resourcetype><d:collection/></d:resourcetype>"),
+                response(
+                    &state.href(CALENDAR),
+                    &format!(
+                        "<d:resourcetype><d:collection/><c:calendar/></d:resourcetype>\
+                         <d:displayname>Work</d:displayname><cs:getctag>{}</cs:getctag>\
+                         <i:calendar-color>#FF0000FF</i:calendar-color>",
+                        state.ctag
+                    ),
+                ),
+            ]),
+            ("PROPFIND", CALENDAR, Some("0")) => {
+                multistatus(&[response(CALENDAR, &format!("<cs:getctag>{}</cs:getctag>", state.ctag))])
+            }
+            ("PROPFIND", CALENDAR, _) => {
+                let mut responses = vec![response(CALENDAR, "<d:resourcetype><d:collection/></d:resourcetype>")];
+                responses.extend(state.objects.iter().map(|(path, (etag, _))| {
+                    response(&state.href(path), &format!("<d:getetag>{}</d:getetag><d:resourcetype/>", etag))
+                }));
+                multistatus(&responses)
+            }
+            ("PROPFIND", _, _) => match current {
+                Some(etag) => multistatus(&[response(&state.href(&path), &format!("<d:getetag>{}</d:getetag>", etag))]),
+                None => status(404),
+            },
+            ("REPORT", CALENDAR, _) => multistatus(
+                &state
+                    .objects
+                    .iter()
+                    .map(|(path, (etag, data))| {
+                        response(
+                            &state.href(path),
+                            &format!(
+                                "<d:getetag>{}</d:getetag><c:calendar-data>{}</c:calendar-data>",
+                                etag,
+                                escape(data)
+                            ),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            ("PUT", _, _) => {
+                let allowed = match (header("If-Match"), header("If-None-Match")) {
+                    (Some(etag), _) => current.as_deref() == Some(etag),
+                    (None, Some("*")) => current.is_none(),
+                    _ => true,
+                };
+                if !allowed {
+                    return Ok(status(412));
+                }
+                let etag = state.next_etag();
+                let data = request.body.clone().unwrap_or_default();
+                state.objects.insert(path, (etag.clone(), data));
+                state.ctag += 1;
+                HttpResponse {
+                    status: 201,
+                    etag: (!state.omit_put_etag).then_some(etag),
+                    body: String::new(),
+                }
+            }
+            ("DELETE", _, _) => match (current, header("If-Match")) {
+                (None, _) => status(404),
+                (Some(current), Some(etag)) if current != etag => status(412),
+                _ => {
+                    state.objects.remove(&path);
+                    state.ctag += 1;
+                    status(204)
+                }
+            },
+            _ => status(405),
+        })
+    }
+}
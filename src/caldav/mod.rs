@@ -0,0 +1,473 @@
+#[cfg(test)]
+mod fake;
+pub mod sync;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use roxmltree::{Document, Node};
+use std::time::Duration;
+use url::Url;
+
+const DAV: &str = "DAV:";
+const CALDAV: &str = "urn:ietf:params:xml:ns:caldav";
+const CALENDARSERVER: &str = "http://calendarserver.org/ns/";
+const APPLE_ICAL: &str = "http://apple.com/ns/ical/";
+
+pub struct HttpRequest<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    pub headers: Vec<(&'a str, String)>,
+    pub body: Option<String>,
+}
+
+pub struct HttpResponse {
+    pub status: u16,
+    pub etag: Option<String>,
+    pub body: String,
+}
+
+/// Sends raw HTTP requests. Implemented over the network by `UreqTransport`;
+/// tests can substitute a stand-in server.
+pub trait Transport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse>;
+}
+
+pub struct UreqTransport {
+    agent: ureq::Agent,
+    authorization: Option<String>,
+}
+
+impl UreqTransport {
+    pub fn new(username: Option<&str>, password: Option<&str>) -> Self {
+        let authorization = username.map(|username| {
+            let credentials = format!("{}:{}", username, password.unwrap_or(""));
+            format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(credentials))
+        });
+        UreqTransport {
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build(),
+            authorization,
+        }
+    }
+}
+
+impl Transport for UreqTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        let mut call = self.agent.request(request.method, request.url);
+        if let Some(authorization) = &self.authorization {
+            call = call.set("Authorization", authorization);
+        }
+        for (name, value) in &request.headers {
+            call = call.set(name, value);
+        }
+        let result = match &request.body {
+            Some(body) => call.send_string(body),
+            None => call.call(),
+        };
+        let response = match result {
+            Ok(response) => response,
+            // Error statuses are reported to the caller, which knows which ones matter.
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(err) => return Err(anyhow!("{} {} failed: {}", request.method, request.url, err)),
+        };
+        Ok(HttpResponse {
+            status: response.status(),
+            etag: response.header("ETag").map(str::to_string),
+            body: response.into_string()?,
+        })
+    }
+}
+
+pub struct RemoteCalendar {
+    pub href: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub ctag: Option<String>,
+}
+
+/// A calendar object resource: one `.ics` file on the server.
+pub struct RemoteObject {
+    pub href: String,
+    pub etag: Option<String>,
+    /// The iCalendar text, when the request asked for it.
+    pub data: Option<String>,
+}
+
+pub enum PutOutcome {
+    Stored { etag: Option<String> },
+    /// The resource changed on the server since `etag` was recorded.
+    Conflict,
+}
+
+pub struct CalDavClient<T: Transport> {
+    transport: T,
+    base: Url,
+}
+
+impl<T: Transport> CalDavClient<T> {
+    pub fn new(transport: T, url: &str) -> Result<Self> {
+        let base = Url::parse(url).with_context(|| format!("Invalid CalDAV URL '{}'", url))?;
+        Ok(CalDavClient { transport, base })
+    }
+
+    fn url(&self, href: &str) -> Result<String> {
+        Ok(self.base.join(href)?.to_string())
+    }
+
+    /// What identifies `href` on this server: its percent-decoded path. Servers
+    /// may answer with absolute URLs or encode characters differently from the
+    /// href a resource was created at, so hrefs are compared by this.
+    pub fn resource_key(&self, href: &str) -> String {
+        match self.base.join(href) {
+            Ok(url) => percent_decode(url.path()),
+            Err(_) => percent_decode(href),
+        }
+    }
+
+    fn multistatus(&self, method: &str, href: &str, depth: &str, body: &str) -> Result<String> {
+        let url = self.url(href)?;
+        let response = self.transport.send(&HttpRequest {
+            method,
+            url: &url,
+            headers: vec![
+                ("Depth", depth.to_string()),
+                ("Content-Type", "application/xml; charset=utf-8".to_string()),
+            ],
+            body: Some(body.to_string()),
+        })?;
+        if response.status != 207 {
+            bail!("{} {} returned HTTP {}", method, url, response.status);
+        }
+        Ok(response.body)
+    }
+
+    /// Finds the calendars of the configured account, following
+    /// current-user-principal and calendar-home-set when the server offers them.
+    pub fn discover_calendars(&self) -> Result<Vec<RemoteCalendar>> {
+        let start = self.base.path().to_string();
+        let body = self.multistatus(
+            "PROPFIND",
+            &start,
+            "0",
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:current-user-principal/><c:calendar-home-set/></d:prop>
+</d:propfind>"#,
+        )?;
+        let doc = Document::parse(&body)?;
+        let principal = first_href(&doc, DAV, "current-user-principal").unwrap_or_else(|| start.clone());
+        let mut home = first_href(&doc, CALDAV, "calendar-home-set");
+        if home.is_none() && principal != start {
+            let body = self.multistatus(
+                "PROPFIND",
+                &principal,
+                "0",
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><c:calendar-home-set/></d:prop>
+</d:propfind>"#,
+            )?;
+            home = first_href(&Document::parse(&body)?, CALDAV, "calendar-home-set");
+        }
+        let home = home.unwrap_or(principal);
+
+        let body = self.multistatus(
+            "PROPFIND",
+            &home,
+            "1",
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/" xmlns:i="http://apple.com/ns/ical/">
+  <d:prop><d:resourcetype/><d:displayname/><cs:getctag/><i:calendar-color/></d:prop>
+</d:propfind>"#,
+        )?;
+        let doc = Document::parse(&body)?;
+        let mut calendars = Vec::new();
+        for response in responses(&doc) {
+            let is_calendar = find(response, DAV, "resourcetype").is_some_and(|t| find(t, CALDAV, "calendar").is_some());
+            let Some(href) = child_text(response, DAV, "href") else {
+                continue;
+            };
+            if !is_calendar {
+                continue;
+            }
+            let name = prop_text(response, DAV, "displayname")
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| href.trim_end_matches('/').rsplit('/').next().unwrap_or("Calendar").to_string());
+            // Apple clients write #RRGGBBAA; the alpha channel is dropped.
+            let color = prop_text(response, APPLE_ICAL, "calendar-color")
+                .filter(|c| c.starts_with('#') && c.len() >= 7)
+                .map(|c| c[..7].to_string());
+            calendars.push(RemoteCalendar {
+                href,
+                name,
+                color,
+                ctag: prop_text(response, CALENDARSERVER, "getctag"),
+            });
+        }
+        Ok(calendars)
+    }
+
+    pub fn ctag(&self, calendar_href: &str) -> Result<Option<String>> {
+        let body = self.multistatus(
+            "PROPFIND",
+            calendar_href,
+            "0",
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+  <d:prop><cs:getctag/></d:prop>
+</d:propfind>"#,
+        )?;
+        let doc = Document::parse(&body)?;
+        let ctag = responses(&doc).find_map(|r| prop_text(r, CALENDARSERVER, "getctag"));
+        Ok(ctag)
+    }
+
+    /// Every object in the calendar with its ETag, but no data.
+    pub fn list_objects(&self, calendar_href: &str) -> Result<Vec<RemoteObject>> {
+        let body = self.multistatus(
+            "PROPFIND",
+            calendar_href,
+            "1",
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/><d:resourcetype/></d:prop></d:propfind>"#,
+        )?;
+        self.parse_objects(&body, calendar_href)
+    }
+
+    /// VEVENT objects with an instance overlapping `[start, end)`, with data.
+    /// Times are UTC in `YYYYMMDDTHHMMSSZ` form.
+    pub fn query_events(&self, calendar_href: &str, start: &str, end: &str) -> Result<Vec<RemoteObject>> {
+        let body = self.multistatus(
+            "REPORT",
+            calendar_href,
+            "1",
+            &format!(
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/><c:calendar-data/></d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VEVENT"><c:time-range start="{}" end="{}"/></c:comp-filter>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#,
+                start, end
+            ),
+        )?;
+        self.parse_objects(&body, calendar_href)
+    }
+
+    fn parse_objects(&self, body: &str, calendar_href: &str) -> Result<Vec<RemoteObject>> {
+        let doc = Document::parse(body)?;
+        let collection = self.resource_key(calendar_href);
+        let mut objects = Vec::new();
+        for response in responses(&doc) {
+            let Some(href) = child_text(response, DAV, "href") else {
+                continue;
+            };
+            // Depth 1 listings include the collection itself.
+            let is_collection = find(response, DAV, "collection").is_some();
+            if is_collection || self.resource_key(&href).trim_end_matches('/') == collection.trim_end_matches('/') {
+                continue;
+            }
+            objects.push(RemoteObject {
+                href,
+                etag: prop_text(response, DAV, "getetag"),
+                data: prop_text(response, CALDAV, "calendar-data"),
+            });
+        }
+        Ok(objects)
+    }
+
+    /// ETag of the object at `href`, if the server reports one.
+    pub fn etag(&self, href: &str) -> Result<Option<String>> {
+        let body = self.multistatus(
+            "PROPFIND",
+            href,
+            "0",
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/></d:prop></d:propfind>"#,
+        )?;
+        let doc = Document::parse(&body)?;
+        let etag = responses(&doc).find_map(|r| prop_text(r, DAV, "getetag"));
+        Ok(etag)
+    }
+
+    /// Uploads `data` to `href`. With an `etag` the write only succeeds if the
+    /// server copy is unchanged; without one it only creates a new resource.
+    /// Servers that leave the ETag out of the response are asked for it, as
+    /// the next write needs it.
+    pub fn put(&self, href: &str, data: &str, etag: Option<&str>) -> Result<PutOutcome> {
+        let url = self.url(href)?;
+        let precondition = match etag {
+            Some(etag) => ("If-Match", etag.to_string()),
+            None => ("If-None-Match", "*".to_string()),
+        };
+        let response = self.transport.send(&HttpRequest {
+            method: "PUT",
+            url: &url,
+            headers: vec![
+                ("Content-Type", "text/calendar; charset=utf-8".to_string()),
+                precondition,
+            ],
+            body: Some(data.to_string()),
+        })?;
+        match response.status {
+            200..=299 => {
+                let etag = match response.etag {
+                    Some(etag) => Some(etag),
+                    None => self.etag(href)?,
+                };
+                Ok(PutOutcome::Stored { etag })
+            }
+            412 => Ok(PutOutcome::Conflict),
+            status => bail!("PUT {} returned HTTP {}", url, status),
+        }
+    }
+
+    /// Deletes `href` if it still has `etag`. Returns false on a conflict;
+    /// a resource that is already gone counts as deleted.
+    pub fn delete(&self, href: &str, etag: Option<&str>) -> Result<bool> {
+        let url = self.url(href)?;
+        let headers = etag.map(|etag| vec![("If-Match", etag.to_string())]).unwrap_or_default();
+        let response = self.transport.send(&HttpRequest {
+            method: "DELETE",
+            url: &url,
+            headers,
+            body: None,
+        })?;
+        match response.status {
+            200..=299 | 404 => Ok(true),
+            412 => Ok(false),
+            status => bail!("DELETE {} returned HTTP {}", url, status),
+        }
+    }
+}
+
+fn responses<'a, 'input>(doc: &'a Document<'input>) -> impl Iterator<Item = Node<'a, 'input>> {
+    doc.descendants().filter(|n| n.has_tag_name((DAV, "response")))
+}
+
+fn find<'a, 'input>(node: Node<'a, 'input>, ns: &str, name: &str) -> Option<Node<'a, 'input>> {
+    node.descendants().find(|n| n.has_tag_name((ns, name)))
+}
+
+fn child_text(node: Node, ns: &str, name: &str) -> Option<String> {
+    node.children()
+        .find(|n| n.has_tag_name((ns, name)))
+        .and_then(|n| n.text())
+        .map(|t| t.trim().to_string())
+}
+
+/// Text of property `name` in a response's successful propstat.
+fn prop_text(response: Node, ns: &str, name: &str) -> Option<String> {
+    response
+        .children()
+        .filter(|n| n.has_tag_name((DAV, "propstat")))
+        .filter(|propstat| {
+            child_text(*propstat, DAV, "status").is_none_or(|status| status.contains(" 200"))
+        })
+        .find_map(|propstat| find(propstat, ns, name))
+        .and_then(|n| n.text())
+        .map(|t| t.trim().to_string())
+}
+
+fn first_href(doc: &Document, ns: &str, property: &str) -> Option<String> {
+    responses(doc)
+        .find_map(|r| find(r, ns, property))
+        .and_then(|p| child_text(p, DAV, "href"))
+}
+
+/// Decodes `%XX` escapes; malformed ones are kept as they are.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|h| bytes[i] == b'%' && h.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|h| std::str::from_utf8(h).ok());
+        match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Encodes `value` for use as one path segment, keeping only unreserved characters.
+pub fn percent_encode_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake::{vcalendar, FakeServer, BASE, CALENDAR};
+    use super::*;
+
+    #[test]
+    fn discovers_calendars_through_the_principal() {
+        let server = FakeServer::default();
+        server.0.borrow_mut().ctag = 7;
+        let client = CalDavClient::new(server, BASE).unwrap();
+        let calendars = client.discover_calendars().unwrap();
+        assert_eq!(calendars.len(), 1);
+        assert_eq!(calendars[0].href, CALENDAR);
+        assert_eq!(calendars[0].name, "Work");
+        assert_eq!(calendars[0].color.as_deref(), Some("#FF0000"));
+        assert_eq!(calendars[0].ctag.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn compares_hrefs_by_decoded_path() {
+        let client = CalDavClient::new(FakeServer::default(), BASE).unwrap();
+        let key = client.resource_key("/dav/calendars/ana/work/1@calendar-app.ics");
+        assert_eq!(client.resource_key("/dav/calendars/ana/work/1%40calendar-app.ics"), key);
+        assert_eq!(client.resource_key("https://dav.example.com/dav/calendars/ana/work/1%40calendar-app.ics"), key);
+        assert_ne!(client.resource_key("/dav/calendars/ana/work/2@calendar-app.ics"), key);
+        assert_eq!(percent_decode("a%2Fb%zz%4"), "a/b%zz%4");
+        assert_eq!(percent_encode_segment("1@calendar-app/x y"), "1%40calendar-app%2Fx%20y");
+    }
+
+    #[test]
+    fn lists_encoded_and_absolute_hrefs_without_the_collection() {
+        let server = FakeServer::default();
+        {
+            let mut state = server.0.borrow_mut();
+            state.encode_hrefs = true;
+            state.absolute_hrefs = true;
+            state.put("a@example.com.ics", &vcalendar("a@example.com", "A"));
+        }
+        let client = CalDavClient::new(server, BASE).unwrap();
+        let objects = client.list_objects(CALENDAR).unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].href, "https://dav.example.com/dav/calendars/ana/work/a%40example.com.ics");
+        assert_eq!(client.resource_key(&objects[0].href), format!("{}a@example.com.ics", CALENDAR));
+        assert_eq!(objects[0].etag.as_deref(), Some("\"1\""));
+    }
+
+    #[test]
+    fn asks_for_the_etag_when_a_put_response_has_none() {
+        let server = FakeServer::default();
+        server.0.borrow_mut().omit_put_etag = true;
+        let client = CalDavClient::new(server, BASE).unwrap();
+        let href = format!("{}new.ics", CALENDAR);
+        match client.put(&href, &vcalendar("new", "New"), None).unwrap() {
+            PutOutcome::Stored { etag } => assert_eq!(etag.as_deref(), Some("\"1\"")),
+            PutOutcome::Conflict => panic!("unexpected conflict"),
+        }
+        assert!(matches!(client.put(&href, &vcalendar("new", "New"), None).unwrap(), PutOutcome::Conflict));
+    }
+}
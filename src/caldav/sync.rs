@@ -0,0 +1,555 @@
+use crate::{
+    caldav::{percent_encode_segment, CalDavClient, PutOutcome, RemoteCalendar, RemoteObject, Transport},
+    ics::{export, import::parse_event, parse_components},
+    models::{calendar::Calendar, event::Event},
+    storage::{
+        db::{
+            create_calendar, create_event, delete_event, get_calendars, get_event, get_event_by_uid, set_event_uid,
+            update_event,
+        },
+        sync_state::{
+            delete_resource, get_calendar_links, get_resources, insert_resource, link_calendar, mark_resource_synced,
+            set_calendar_ctag, unlink_calendar, unsynced_event_ids, CalendarLink, Resource,
+        },
+    },
+};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct SyncReport {
+    pub calendars: usize,
+    pub pulled: usize,
+    pub deleted_locally: usize,
+    pub pushed: usize,
+    pub deleted_remotely: usize,
+    /// Changes made on both sides; the server copy is kept.
+    pub conflicts: usize,
+}
+
+/// Two-way sync of every calendar on the server with its local mirror.
+/// Remote changes are pulled first, so when both sides changed an event the
+/// server wins and the conflict is counted. Floating times from the server
+/// are read in `floating`.
+pub fn sync<T: Transport>(
+    conn: &Connection,
+    client: &CalDavClient<T>,
+    floating: Tz,
+    past_days: i64,
+    future_days: i64,
+) -> Result<SyncReport> {
+    let mut report = SyncReport::default();
+    let remote = client.discover_calendars()?;
+    let links = link_calendars(conn, client, &remote)?;
+    let now = Utc::now();
+    let window = (now - Duration::days(past_days), now + Duration::days(future_days));
+
+    for link in links {
+        let remote_ctag = remote
+            .iter()
+            .find(|c| client.resource_key(&c.href) == client.resource_key(&link.href))
+            .and_then(|c| c.ctag.clone());
+        sync_calendar(conn, client, &link, remote_ctag, window, floating, &mut report)?;
+        report.calendars += 1;
+    }
+    Ok(report)
+}
+
+/// Creates local calendars for new remote ones and unlinks those removed
+/// from the server.
+fn link_calendars<T: Transport>(
+    conn: &Connection,
+    client: &CalDavClient<T>,
+    remote: &[RemoteCalendar],
+) -> Result<Vec<CalendarLink>> {
+    let same = |a: &str, b: &str| client.resource_key(a) == client.resource_key(b);
+    for link in get_calendar_links(conn)? {
+        if !remote.iter().any(|c| same(&c.href, &link.href)) {
+            unlink_calendar(conn, link.calendar_id)?;
+        }
+    }
+    let links = get_calendar_links(conn)?;
+    for calendar in remote.iter().filter(|c| !links.iter().any(|l| same(&l.href, &c.href))) {
+        let existing = get_calendars(conn)?;
+        let mut name = calendar.name.clone();
+        let mut suffix = 2;
+        while existing.iter().any(|c| c.name.eq_ignore_ascii_case(&name)) {
+            name = format!("{} {}", calendar.name, suffix);
+            suffix += 1;
+        }
+        let id = create_calendar(
+            conn,
+            &Calendar {
+                id: None,
+                name,
+                color: calendar.color.clone(),
+                visible: true,
+            },
+        )?;
+        link_calendar(conn, id, &calendar.href)?;
+    }
+    get_calendar_links(conn)
+}
+
+fn sync_calendar<T: Transport>(
+    conn: &Connection,
+    client: &CalDavClient<T>,
+    link: &CalendarLink,
+    remote_ctag: Option<String>,
+    window: (DateTime<Utc>, DateTime<Utc>),
+    floating: Tz,
+    report: &mut SyncReport,
+) -> Result<()> {
+    // An unchanged ctag means nothing on the server changed since the last pull.
+    if remote_ctag.is_none() || remote_ctag != link.ctag {
+        pull(conn, client, link, window, floating, report)?;
+        set_calendar_ctag(conn, link.calendar_id, remote_ctag.as_deref())?;
+    }
+    let conflicts = report.conflicts;
+    // Our writes change the ctag; remember the new one so they are not pulled
+    // back, unless a conflict needs the server copies pulled.
+    if push(conn, client, link, report)? && report.conflicts == conflicts {
+        let ctag = client.ctag(&link.href)?;
+        set_calendar_ctag(conn, link.calendar_id, ctag.as_deref())?;
+    }
+    Ok(())
+}
+
+fn pull<T: Transport>(
+    conn: &Connection,
+    client: &CalDavClient<T>,
+    link: &CalendarLink,
+    window: (DateTime<Utc>, DateTime<Utc>),
+    floating: Tz,
+    report: &mut SyncReport,
+) -> Result<()> {
+    let listing: HashMap<String, Option<String>> = client
+        .list_objects(&link.href)?
+        .into_iter()
+        .map(|object| (client.resource_key(&object.href), object.etag))
+        .collect();
+
+    // Objects gone from the server are deleted here too, unless edited locally
+    // since the last sync: those are uploaded again as new events.
+    let mut removed: Vec<&str> = Vec::new();
+    let resources = get_resources(conn, link.calendar_id)?;
+    for resource in &resources {
+        if !listing.contains_key(&client.resource_key(&resource.href)) && !removed.contains(&resource.href.as_str()) {
+            removed.push(&resource.href);
+        }
+    }
+    for href in removed {
+        let edited = resources.iter().find(|r| r.href == href && !r.is_override && r.dirty && r.event_id.is_some());
+        if let Some(resource) = edited {
+            delete_resource(conn, resource.id)?;
+            report.conflicts += 1;
+        }
+        let stale = resources.iter().filter(|r| r.href == href && Some(r.id) != edited.map(|e| e.id));
+        if forget_object(conn, stale)? {
+            report.deleted_locally += 1;
+        }
+    }
+
+    let format = |datetime: DateTime<Utc>| datetime.format("%Y%m%dT%H%M%SZ").to_string();
+    let changed = client.query_events(&link.href, &format(window.0), &format(window.1))?;
+    for object in changed {
+        let resources = get_resources(conn, link.calendar_id)?;
+        let key = client.resource_key(&object.href);
+        let master = resources.iter().find(|r| client.resource_key(&r.href) == key && !r.is_override);
+        if master.is_some_and(|m| m.etag.is_some() && m.etag == object.etag) {
+            continue;
+        }
+        if master.is_some_and(|m| m.dirty) {
+            report.conflicts += 1;
+        }
+        // Keep the href the resource was stored at, however the server spelled it this time.
+        let object = match master {
+            Some(master) => RemoteObject {
+                href: master.href.clone(),
+                ..object
+            },
+            None => object,
+        };
+        if apply_object(conn, link.calendar_id, &object, &resources, floating)? {
+            report.pulled += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Deletes `resources` and their local events. Returns whether any event was deleted.
+fn forget_object<'r>(conn: &Connection, resources: impl Iterator<Item = &'r Resource>) -> Result<bool> {
+    let mut deleted = false;
+    for resource in resources {
+        delete_resource(conn, resource.id)?;
+        if let Some(event_id) = resource.event_id {
+            delete_event(conn, event_id)?;
+            deleted = true;
+        }
+    }
+    Ok(deleted)
+}
+
+/// Stores the server copy of `object`: its master VEVENT updates or creates the
+/// local event, and modified occurrences are replaced by standalone events.
+fn apply_object(
+    conn: &Connection,
+    calendar_id: i64,
+    object: &RemoteObject,
+    resources: &[Resource],
+    floating: Tz,
+) -> Result<bool> {
+    let Some(data) = &object.data else {
+        return Ok(false);
+    };
+    let mut events: Vec<Event> = parse_components(data)
+        .into_iter()
+        .filter_map(|component| component.ok())
+        .filter(|component| component.name == "VEVENT")
+        .filter_map(|component| parse_event(&component, floating).ok())
+        .collect();
+    let Some(master_index) = events.iter().position(|e| e.recurrence_id.is_none()) else {
+        return Ok(false);
+    };
+    let mut master = events.remove(master_index);
+    master.calendar_id = Some(calendar_id);
+    master.exception_dates.extend(events.iter().filter_map(|e| e.recurrence_id));
+
+    let tx = conn.unchecked_transaction()?;
+    let existing = resources.iter().find(|r| r.href == object.href && !r.is_override);
+    let known_id = existing.and_then(|r| r.event_id).or_else(|| {
+        // An event imported from a file earlier is adopted rather than duplicated.
+        let uid = master.uid.as_deref()?;
+        get_event_by_uid(&tx, uid).ok().flatten().and_then(|e| e.id)
+    });
+    match known_id.and_then(|id| get_event(&tx, id).ok().flatten()) {
+        Some(local) => {
+            master.id = local.id;
+            // Reminders are local-only; keep them across server updates.
+            master.reminders = local.reminders;
+            update_event(&tx, &master)?;
+        }
+        None => master.id = Some(create_event(&tx, &master)?),
+    }
+    let master_id = master.id.unwrap_or_default();
+    match existing {
+        Some(resource) => mark_resource_synced(&tx, resource.id, object.etag.as_deref())?,
+        None => {
+            for stale in resources.iter().filter(|r| r.event_id == Some(master_id)) {
+                delete_resource(&tx, stale.id)?;
+            }
+            insert_resource(&tx, calendar_id, &object.href, master_id, object.etag.as_deref(), false)?;
+        }
+    }
+
+    for resource in resources.iter().filter(|r| r.href == object.href && r.is_override) {
+        delete_resource(&tx, resource.id)?;
+        if let Some(event_id) = resource.event_id {
+            delete_event(&tx, event_id)?;
+        }
+    }
+    for occurrence in events {
+        let Some(recurrence_id) = occurrence.recurrence_id else {
+            continue;
+        };
+        let uid = format!(
+            "{}#{}",
+            master.uid.as_deref().unwrap_or_default(),
+            recurrence_id.format("%Y%m%dT%H%M%SZ")
+        );
+        let id = create_event(
+            &tx,
+            &Event {
+                uid: Some(uid),
+                calendar_id: Some(calendar_id),
+                recurrence: None,
+                recurrence_id: None,
+                ..occurrence
+            },
+        )?;
+        insert_resource(&tx, calendar_id, &object.href, id, object.etag.as_deref(), true)?;
+    }
+    tx.commit()?;
+    Ok(true)
+}
+
+/// Uploads local changes. Returns whether anything was written to the server.
+fn push<T: Transport>(
+    conn: &Connection,
+    client: &CalDavClient<T>,
+    link: &CalendarLink,
+    report: &mut SyncReport,
+) -> Result<bool> {
+    let mut wrote = false;
+    let resources = get_resources(conn, link.calendar_id)?;
+    for resource in resources.iter().filter(|r| !r.is_override) {
+        let event = match resource.event_id {
+            Some(id) => get_event(conn, id)?,
+            None => None,
+        };
+        match event {
+            // Deleted locally, or moved to another calendar.
+            None => {
+                delete_remote(conn, client, &resources, resource, report)?;
+                wrote = true;
+            }
+            Some(event) if event.calendar_id != Some(link.calendar_id) => {
+                delete_remote(conn, client, &resources, resource, report)?;
+                wrote = true;
+            }
+            Some(event) if resource.dirty => {
+                match client.put(&resource.href, &export::export_events(&[event]), resource.etag.as_deref())? {
+                    PutOutcome::Stored { etag } => {
+                        mark_resource_synced(conn, resource.id, etag.as_deref())?;
+                        report.pushed += 1;
+                    }
+                    PutOutcome::Conflict => {
+                        // Pull the server copy on the next sync.
+                        report.conflicts += 1;
+                        set_calendar_ctag(conn, link.calendar_id, None)?;
+                    }
+                }
+                wrote = true;
+            }
+            Some(_) => {}
+        }
+    }
+
+    for id in unsynced_event_ids(conn, link.calendar_id)? {
+        let Some(mut event) = get_event(conn, id)? else {
+            continue;
+        };
+        if event.uid.is_none() {
+            let uid = export::event_uid(&event);
+            set_event_uid(conn, id, &uid)?;
+            event.uid = Some(uid);
+        }
+        let uid = event.uid.as_deref().unwrap_or_default();
+        let href = format!("{}/{}.ics", link.href.trim_end_matches('/'), percent_encode_segment(uid));
+        match client.put(&href, &export::export_events(&[event]), None)? {
+            PutOutcome::Stored { etag } => {
+                insert_resource(conn, link.calendar_id, &href, id, etag.as_deref(), false)?;
+                report.pushed += 1;
+            }
+            PutOutcome::Conflict => report.conflicts += 1,
+        }
+        wrote = true;
+    }
+    Ok(wrote)
+}
+
+fn delete_remote<T: Transport>(
+    conn: &Connection,
+    client: &CalDavClient<T>,
+    resources: &[Resource],
+    resource: &Resource,
+    report: &mut SyncReport,
+) -> Result<()> {
+    if client.delete(&resource.href, resource.etag.as_deref())? {
+        forget_object(conn, resources.iter().filter(|r| r.href == resource.href))?;
+        report.deleted_remotely += 1;
+    } else {
+        // Changed on the server meanwhile: drop the delete and pull the server copy.
+        delete_resource(conn, resource.id)?;
+        set_calendar_ctag(conn, resource.calendar_id, None)?;
+        report.conflicts += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caldav::fake::{vcalendar, FakeServer, BASE, CALENDAR};
+    use crate::storage::db::{get_all_events, open_memory_db};
+    use chrono::TimeZone;
+
+    struct Setup {
+        conn: Connection,
+        server: FakeServer,
+        client: CalDavClient<FakeServer>,
+    }
+
+    impl Setup {
+        fn new() -> Self {
+            let server = FakeServer::default();
+            let client = CalDavClient::new(server.clone(), BASE).unwrap();
+            Setup {
+                conn: open_memory_db(),
+                server,
+                client,
+            }
+        }
+
+        fn sync(&self) -> SyncReport {
+            sync(&self.conn, &self.client, Tz::UTC, 30, 365).unwrap()
+        }
+
+        fn calendar_id(&self) -> i64 {
+            get_calendar_links(&self.conn).unwrap()[0].calendar_id
+        }
+
+        fn titles(&self) -> Vec<String> {
+            let mut titles: Vec<String> = get_all_events(&self.conn)
+                .unwrap()
+                .into_iter()
+                .filter(|e| e.calendar_id == Some(self.calendar_id()))
+                .map(|e| e.title)
+                .collect();
+            titles.sort();
+            titles
+        }
+
+        fn event(&self, title: &str) -> Event {
+            get_all_events(&self.conn).unwrap().into_iter().find(|e| e.title == title).unwrap()
+        }
+
+        /// Creates a local event in the synced calendar.
+        fn create(&self, title: &str) -> i64 {
+            let start = Utc.with_ymd_and_hms(2026, 10, 21, 9, 0, 0).unwrap();
+            let event = Event {
+                title: title.to_string(),
+                calendar_id: Some(self.calendar_id()),
+                start_datetime: start,
+                end_datetime: start + Duration::hours(1),
+                ..Event::default()
+            };
+            create_event(&self.conn, &event).unwrap()
+        }
+
+        fn rename(&self, title: &str, new_title: &str) {
+            let event = Event {
+                title: new_title.to_string(),
+                ..self.event(title)
+            };
+            update_event(&self.conn, &event).unwrap();
+        }
+    }
+
+    #[test]
+    fn pulls_created_updated_and_deleted_objects() {
+        let setup = Setup::new();
+        setup.server.0.borrow_mut().put("a.ics", &vcalendar("a", "Planning"));
+        let report = setup.sync();
+        assert_eq!((report.calendars, report.pulled), (1, 1));
+        assert_eq!(setup.titles(), vec!["Planning"]);
+
+        setup.server.0.borrow_mut().put("a.ics", &vcalendar("a", "Planning (moved)"));
+        assert_eq!(setup.sync().pulled, 1);
+        assert_eq!(setup.titles(), vec!["Planning (moved)"]);
+
+        // Nothing changed: the ctag is the same and nothing is fetched.
+        assert_eq!(setup.sync().pulled, 0);
+
+        setup.server.0.borrow_mut().delete("a.ics");
+        assert_eq!(setup.sync().deleted_locally, 1);
+        assert!(setup.titles().is_empty());
+    }
+
+    #[test]
+    fn pushes_new_and_edited_events() {
+        let setup = Setup::new();
+        setup.sync();
+        let id = setup.create("Review");
+        let report = setup.sync();
+        assert_eq!(report.pushed, 1);
+        let data = setup.server.0.borrow().find("SUMMARY:Review").unwrap().to_string();
+        assert!(data.contains(&format!("UID:{}@calendar-app", id)));
+        let path = format!("{}{}@calendar-app.ics", CALENDAR, id);
+        assert!(setup.server.0.borrow().objects.contains_key(&path));
+
+        setup.rename("Review", "Design review");
+        let report = setup.sync();
+        assert_eq!((report.pushed, report.conflicts), (1, 0));
+        assert!(setup.server.0.borrow().find("SUMMARY:Design review").is_some());
+        assert_eq!(setup.server.0.borrow().objects.len(), 1);
+    }
+
+    #[test]
+    fn keeps_pushed_events_when_the_server_lists_encoded_hrefs() {
+        let setup = Setup::new();
+        {
+            let mut state = setup.server.0.borrow_mut();
+            state.encode_hrefs = true;
+            state.absolute_hrefs = true;
+        }
+        setup.sync();
+        setup.create("Review");
+        assert_eq!(setup.sync().pushed, 1);
+
+        // Another client's change makes the next sync list and pull everything.
+        setup.server.0.borrow_mut().put("other.ics", &vcalendar("other", "Other"));
+        let report = setup.sync();
+        assert_eq!((report.deleted_locally, report.pulled), (0, 1));
+        assert_eq!(setup.titles(), vec!["Other", "Review"]);
+
+        setup.rename("Review", "Design review");
+        let report = setup.sync();
+        assert_eq!((report.pushed, report.conflicts), (1, 0));
+        assert_eq!(setup.server.0.borrow().objects.len(), 2);
+    }
+
+    #[test]
+    fn edits_after_a_put_without_etag_are_not_lost() {
+        let setup = Setup::new();
+        setup.server.0.borrow_mut().omit_put_etag = true;
+        setup.sync();
+        setup.create("Review");
+        setup.sync();
+
+        setup.rename("Review", "Design review");
+        let report = setup.sync();
+        assert_eq!((report.pushed, report.conflicts), (1, 0));
+        assert!(setup.server.0.borrow().find("SUMMARY:Design review").is_some());
+        assert_eq!(setup.titles(), vec!["Design review"]);
+    }
+
+    #[test]
+    fn keeps_the_server_copy_when_a_put_conflicts() {
+        let setup = Setup::new();
+        setup.server.0.borrow_mut().put("a.ics", &vcalendar("a", "Planning"));
+        setup.sync();
+
+        // Changed on the server after our pull; the ctag does not give it away.
+        setup.server.0.borrow_mut().put_quietly("a.ics", &vcalendar("a", "Planning (server)"));
+        setup.rename("Planning", "Planning (local)");
+        let report = setup.sync();
+        assert_eq!((report.pushed, report.conflicts), (0, 1));
+        assert!(setup.server.0.borrow().find("SUMMARY:Planning (server)").is_some());
+
+        assert_eq!(setup.sync().pulled, 1);
+        assert_eq!(setup.titles(), vec!["Planning (server)"]);
+    }
+
+    #[test]
+    fn deletes_remotely_what_was_deleted_locally() {
+        let setup = Setup::new();
+        setup.server.0.borrow_mut().put("a.ics", &vcalendar("a", "Planning"));
+        setup.server.0.borrow_mut().put("b.ics", &vcalendar("b", "Retro"));
+        setup.sync();
+
+        delete_event(&setup.conn, setup.event("Planning").id.unwrap()).unwrap();
+        let report = setup.sync();
+        assert_eq!(report.deleted_remotely, 1);
+        assert!(setup.server.0.borrow().find("Planning").is_none());
+        assert!(setup.server.0.borrow().find("Retro").is_some());
+        assert!(get_resources(&setup.conn, setup.calendar_id()).unwrap().iter().all(|r| r.event_id.is_some()));
+    }
+
+    #[test]
+    fn uploads_again_an_edited_event_deleted_on_the_server() {
+        let setup = Setup::new();
+        setup.server.0.borrow_mut().put("a.ics", &vcalendar("a", "Planning"));
+        setup.sync();
+
+        setup.server.0.borrow_mut().delete("a.ics");
+        setup.rename("Planning", "Planning (local)");
+        let report = setup.sync();
+        assert_eq!((report.deleted_locally, report.conflicts, report.pushed), (0, 1, 1));
+        assert_eq!(setup.titles(), vec!["Planning (local)"]);
+        assert!(setup.server.0.borrow().find("SUMMARY:Planning (local)").is_some());
+    }
+}
@@ -0,0 +1,721 @@
+use crate::{
+    caldav::{sync::sync, CalDavClient, UreqTransport},
+    ics,
+    models::{
+        attendee::{parse_attendees, Attendee},
+        calendar::Calendar,
+        config::Config,
+        event::Event,
+        quick_add::{describe, parse_quick_add},
+        recurrence::Recurrence,
+        reminder::parse_reminders,
+        tags::{format_tags, parse_tags, TagFilter},
+        timezone::{day_range, local_to_utc, to_local},
+    },
+    storage::db::{
+        create_calendar, create_event, delete_calendar, delete_event, get_all_events, get_calendar_by_name,
+        get_calendars, get_event, get_events_in_range, search_events, update_calendar, update_event,
+    },
+};
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use clap::{Parser, Subcommand};
+use ratatui::style::Color;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Terminal calendar. Launches the TUI when no command is given.
+#[derive(Parser)]
+#[command(name = "calendar-app", version)]
+pub struct Cli {
+    /// Print machine-readable JSON instead of plain text
+    #[arg(long, global = true)]
+    pub json: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Create an event
+    Add {
+        #[arg(long)]
+        title: String,
+        /// Start, as "YYYY-MM-DD HH:MM" ("YYYY-MM-DD" with --all-day)
+        #[arg(long)]
+        start: String,
+        /// End, as "YYYY-MM-DD HH:MM"; defaults to start plus --duration.
+        /// With --all-day, the last day as "YYYY-MM-DD"
+        #[arg(long)]
+        end: Option<String>,
+        /// Create an all-day event spanning whole dates
+        #[arg(long)]
+        all_day: bool,
+        /// Length in minutes when --end is omitted
+        #[arg(long, default_value_t = 30)]
+        duration: i64,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        location: Option<String>,
+        /// Recurrence rule, e.g. "FREQ=WEEKLY;BYDAY=MO"
+        #[arg(long)]
+        repeat: Option<String>,
+        /// Reminders before the start, e.g. "10m,1d"
+        #[arg(long)]
+        remind: Option<String>,
+        /// Tags separated by commas or spaces, e.g. "work, travel"
+        #[arg(long)]
+        tags: Option<String>,
+        /// Attendees separated by commas, e.g. "Ana <ana@example.com> organizer, bob@example.com optional"
+        #[arg(long)]
+        attendees: Option<String>,
+        /// Calendar name; defaults to the first calendar
+        #[arg(long)]
+        calendar: Option<String>,
+    },
+    /// Create an event from a phrase such as "Lunch with Ana tomorrow 12:30 at Cafe Nero"
+    Quick {
+        #[arg(required = true, num_args = 1..)]
+        text: Vec<String>,
+        /// Day that "today", "tomorrow" and weekdays count from, as "YYYY-MM-DD"
+        #[arg(long)]
+        date: Option<String>,
+        /// Calendar name; defaults to the first calendar
+        #[arg(long)]
+        calendar: Option<String>,
+        /// Only show how the phrase is understood, without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List events between two dates
+    List {
+        /// First day, as "YYYY-MM-DD"; defaults to today
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day (inclusive), as "YYYY-MM-DD"; defaults to 30 days after --from
+        #[arg(long)]
+        to: Option<String>,
+        /// Only list events of this calendar
+        #[arg(long)]
+        calendar: Option<String>,
+        /// Only list events whose tags match, e.g. "work and not 1:1"
+        #[arg(long, allow_hyphen_values = true)]
+        filter: Option<String>,
+    },
+    /// Show upcoming events grouped by day
+    Agenda {
+        #[arg(long, default_value_t = 7)]
+        days: i64,
+        /// Only show events whose tags match, e.g. "work and not 1:1"
+        #[arg(long, allow_hyphen_values = true)]
+        filter: Option<String>,
+    },
+    /// Change fields of an existing event
+    Edit {
+        id: i64,
+        #[arg(long)]
+        title: Option<String>,
+        /// New start, keeping the length; "YYYY-MM-DD" for all-day events
+        #[arg(long)]
+        start: Option<String>,
+        /// New end; the last day as "YYYY-MM-DD" for all-day events
+        #[arg(long)]
+        end: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        location: Option<String>,
+        /// Recurrence rule; pass an empty string to stop repeating
+        #[arg(long)]
+        repeat: Option<String>,
+        /// Reminders before the start; pass an empty string to remove them
+        #[arg(long)]
+        remind: Option<String>,
+        /// Tags separated by commas or spaces; pass an empty string to remove them
+        #[arg(long)]
+        tags: Option<String>,
+        /// Attendees separated by commas, replacing the current ones; pass an empty string to remove them
+        #[arg(long)]
+        attendees: Option<String>,
+        /// Move the event to another calendar
+        #[arg(long)]
+        calendar: Option<String>,
+    },
+    /// Find events by words in their title, description or location
+    Search {
+        query: String,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Delete an event (and all its occurrences)
+    Delete { id: i64 },
+    /// Import events from an iCalendar file
+    Import {
+        path: PathBuf,
+        /// Calendar to import into; defaults to the first calendar
+        #[arg(long)]
+        calendar: Option<String>,
+    },
+    /// Export events to an iCalendar file
+    Export {
+        path: PathBuf,
+        /// First day, as "YYYY-MM-DD"; exports everything when omitted
+        #[arg(long, requires = "to")]
+        from: Option<String>,
+        /// Last day (inclusive), as "YYYY-MM-DD"
+        #[arg(long, requires = "from")]
+        to: Option<String>,
+    },
+    /// List and manage calendars
+    Calendars {
+        #[command(subcommand)]
+        action: Option<CalendarCommand>,
+    },
+    /// Two-way sync with the CalDAV server configured under [caldav]
+    Sync,
+}
+
+#[derive(Subcommand)]
+pub enum CalendarCommand {
+    /// Create a calendar
+    Add {
+        name: String,
+        /// Color name ("green"), "#rrggbb" or a 0-255 palette index; the
+        /// theme's event color when omitted
+        #[arg(long)]
+        color: Option<String>,
+    },
+    /// Change a calendar's color; pass an empty string to use the theme's event color
+    Color { name: String, color: String },
+    /// Show a calendar's events in the TUI
+    Show { name: String },
+    /// Hide a calendar's events from the TUI
+    Hide { name: String },
+    /// Delete a calendar and all its events
+    Delete { name: String },
+}
+
+#[derive(Serialize)]
+struct CalendarOutput {
+    id: Option<i64>,
+    name: String,
+    color: Option<String>,
+    visible: bool,
+}
+
+#[derive(Serialize)]
+struct EventOutput {
+    id: Option<i64>,
+    uid: Option<String>,
+    calendar_id: Option<i64>,
+    title: String,
+    description: Option<String>,
+    location: Option<String>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    all_day: bool,
+    timezone: Option<String>,
+    recurrence: Option<String>,
+    recurrence_id: Option<DateTime<Utc>>,
+    /// Minutes before the start
+    reminders: Vec<i64>,
+    tags: Vec<String>,
+    attendees: Vec<AttendeeOutput>,
+}
+
+#[derive(Serialize)]
+struct AttendeeOutput {
+    name: Option<String>,
+    email: String,
+    role: &'static str,
+    status: &'static str,
+}
+
+impl From<&Attendee> for AttendeeOutput {
+    fn from(attendee: &Attendee) -> Self {
+        AttendeeOutput {
+            name: attendee.name.clone(),
+            email: attendee.email.clone(),
+            role: attendee.role.name(),
+            status: attendee.status.name(),
+        }
+    }
+}
+
+impl From<&Event> for EventOutput {
+    fn from(event: &Event) -> Self {
+        EventOutput {
+            id: event.id,
+            uid: event.uid.clone(),
+            calendar_id: event.calendar_id,
+            title: event.title.clone(),
+            description: event.description.clone(),
+            location: event.location.clone(),
+            start: event.start_datetime,
+            end: event.end_datetime,
+            all_day: event.all_day,
+            timezone: event.timezone.clone(),
+            recurrence: event.recurrence.as_ref().map(|r| r.to_string()),
+            recurrence_id: event.recurrence_id,
+            reminders: event.reminders.clone(),
+            tags: event.tags.clone(),
+            attendees: event.attendees.iter().map(AttendeeOutput::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SyncOutput {
+    calendars: usize,
+    pulled: usize,
+    deleted_locally: usize,
+    pushed: usize,
+    deleted_remotely: usize,
+    conflicts: usize,
+}
+
+#[derive(Serialize)]
+struct ImportOutput {
+    imported: usize,
+    skipped: usize,
+    malformed: usize,
+}
+
+pub fn run(command: Command, json: bool, config: &Config, conn: &Connection) -> Result<()> {
+    let tz = config.timezone();
+    match command {
+        Command::Add {
+            title,
+            start,
+            end,
+            all_day,
+            duration,
+            description,
+            location,
+            repeat,
+            remind,
+            tags,
+            attendees,
+            calendar,
+        } => {
+            let (start, end) = if all_day {
+                let first = parse_date(&start)?;
+                let last = end.as_deref().map(parse_date).transpose()?.unwrap_or(first);
+                if last < first {
+                    bail!("End must not be before start");
+                }
+                Event::all_day_bounds(first, last)
+            } else {
+                let start = parse_datetime(tz, &start)?;
+                let end = match end {
+                    Some(end) => parse_datetime(tz, &end)?,
+                    None => start + Duration::minutes(duration),
+                };
+                if end < start {
+                    bail!("End must not be before start");
+                }
+                (start, end)
+            };
+            let event = Event {
+                title,
+                description,
+                location,
+                start_datetime: start,
+                end_datetime: end,
+                all_day,
+                timezone: (!all_day).then(|| tz.name().to_string()),
+                recurrence: parse_recurrence(repeat.as_deref())?,
+                reminders: parse_reminders(remind.as_deref().unwrap_or(""))?,
+                tags: parse_tags(tags.as_deref().unwrap_or("")),
+                attendees: parse_attendees(attendees.as_deref().unwrap_or(""))?,
+                calendar_id: calendar.as_deref().map(|name| find_calendar(conn, name)).transpose()?.and_then(|c| c.id),
+                ..Default::default()
+            };
+            let id = create_event(conn, &event)?;
+            let event = get_event(conn, id)?.ok_or_else(|| anyhow!("Event {} not found", id))?;
+            print_events(&[event], json, config)
+        }
+        Command::Quick { text, date, calendar, dry_run } => {
+            let base = match date {
+                Some(date) => parse_date(&date)?,
+                None => Utc::now().with_timezone(&tz).date_naive(),
+            };
+            let mut event = parse_quick_add(&text.join(" "), base, tz)?;
+            event.calendar_id = calendar.as_deref().map(|name| find_calendar(conn, name)).transpose()?.and_then(|c| c.id);
+            if dry_run {
+                if json {
+                    return print_events(&[event], json, config);
+                }
+                println!("{}\n{}", event.title, describe(&event, tz, config.ui.time_pattern()));
+                return Ok(());
+            }
+            let id = create_event(conn, &event)?;
+            let event = get_event(conn, id)?.ok_or_else(|| anyhow!("Event {} not found", id))?;
+            print_events(&[event], json, config)
+        }
+        Command::List { from, to, calendar, filter } => {
+            let from = match from {
+                Some(from) => parse_date(&from)?,
+                None => Utc::now().with_timezone(&tz).date_naive(),
+            };
+            let to = match to {
+                Some(to) => parse_date(&to)?,
+                None => from + Duration::days(30),
+            };
+            let mut events = events_between(conn, tz, from, to, &parse_filter(filter.as_deref())?)?;
+            if let Some(name) = calendar {
+                let calendar_id = find_calendar(conn, &name)?.id;
+                events.retain(|e| e.calendar_id == calendar_id);
+            }
+            print_events(&events, json, config)
+        }
+        Command::Agenda { days, filter } => {
+            let from = Utc::now().with_timezone(&tz).date_naive();
+            let filter = parse_filter(filter.as_deref())?;
+            let events = events_between(conn, tz, from, from + Duration::days(days.max(1) - 1), &filter)?;
+            if json {
+                return print_events(&events, json, config);
+            }
+            // Multi-day events are listed under every day they cover.
+            let mut day = from;
+            while day < from + Duration::days(days.max(1)) {
+                let day_events: Vec<&Event> = events.iter().filter(|e| e.covers_date(tz, day)).collect();
+                if !day_events.is_empty() {
+                    println!("{}", day.format("%A, %B %-d %Y"));
+                    for event in day_events {
+                        println!("  {}", format_event_line(event, config));
+                    }
+                }
+                day += Duration::days(1);
+            }
+            Ok(())
+        }
+        Command::Edit {
+            id,
+            title,
+            start,
+            end,
+            description,
+            location,
+            repeat,
+            remind,
+            tags,
+            attendees,
+            calendar,
+        } => {
+            let mut event = get_event(conn, id)?.ok_or_else(|| anyhow!("Event {} not found", id))?;
+            if let Some(title) = title {
+                event.title = title;
+            }
+            if event.all_day {
+                let (first, last) = event.date_span(tz);
+                let new_first = start.as_deref().map(parse_date).transpose()?.unwrap_or(first);
+                let new_last = match end.as_deref() {
+                    Some(end) => parse_date(end)?,
+                    None => new_first + (last - first),
+                };
+                if new_last < new_first {
+                    bail!("End must not be before start");
+                }
+                (event.start_datetime, event.end_datetime) = Event::all_day_bounds(new_first, new_last);
+            } else {
+                if let Some(start) = start {
+                    let duration = event.end_datetime - event.start_datetime;
+                    event.start_datetime = parse_datetime(tz, &start)?;
+                    event.end_datetime = event.start_datetime + duration;
+                }
+                if let Some(end) = end {
+                    event.end_datetime = parse_datetime(tz, &end)?;
+                }
+                if event.end_datetime < event.start_datetime {
+                    bail!("End must not be before start");
+                }
+            }
+            if description.is_some() {
+                event.description = description;
+            }
+            if location.is_some() {
+                event.location = location;
+            }
+            if repeat.is_some() {
+                event.recurrence = parse_recurrence(repeat.as_deref())?;
+            }
+            if let Some(remind) = remind {
+                event.reminders = parse_reminders(&remind)?;
+            }
+            if let Some(tags) = tags {
+                event.tags = parse_tags(&tags);
+            }
+            if let Some(attendees) = attendees {
+                event.attendees = parse_attendees(&attendees)?;
+            }
+            if let Some(name) = calendar {
+                event.calendar_id = find_calendar(conn, &name)?.id;
+            }
+            update_event(conn, &event)?;
+            let event = get_event(conn, id)?.ok_or_else(|| anyhow!("Event {} not found", id))?;
+            print_events(&[event], json, config)
+        }
+        Command::Search { query, limit } => print_events(&search_events(conn, &query, limit)?, json, config),
+        Command::Delete { id } => {
+            get_event(conn, id)?.ok_or_else(|| anyhow!("Event {} not found", id))?;
+            delete_event(conn, id)?;
+            if json {
+                println!("{}", serde_json::json!({ "deleted": id }));
+            } else {
+                println!("Deleted event {}", id);
+            }
+            Ok(())
+        }
+        Command::Import { path, calendar } => {
+            let calendar_id = calendar.as_deref().map(|name| find_calendar(conn, name)).transpose()?.and_then(|c| c.id);
+            let report = ics::import::import_file(conn, &path, calendar_id, config.timezone())?;
+            if json {
+                let output = ImportOutput {
+                    imported: report.imported,
+                    skipped: report.skipped,
+                    malformed: report.malformed,
+                };
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!(
+                    "Imported {}, skipped {} (already present), malformed {}",
+                    report.imported, report.skipped, report.malformed
+                );
+            }
+            Ok(())
+        }
+        Command::Export { path, from, to } => {
+            let events = match (from, to) {
+                (Some(from), Some(to)) => events_between(conn, tz, parse_date(&from)?, parse_date(&to)?, &TagFilter::default())?,
+                _ => get_all_events(conn)?,
+            };
+            std::fs::write(&path, ics::export::export_events(&events))?;
+            if json {
+                println!("{}", serde_json::json!({ "exported": events.len(), "path": path }));
+            } else {
+                println!("Exported {} events to {}", events.len(), path.display());
+            }
+            Ok(())
+        }
+        Command::Calendars { action } => {
+            match action {
+                None => {}
+                Some(CalendarCommand::Add { name, color }) => {
+                    let color = parse_color(color.as_deref().unwrap_or(""))?;
+                    create_calendar(
+                        conn,
+                        &Calendar {
+                            id: None,
+                            name,
+                            color,
+                            visible: true,
+                        },
+                    )?;
+                }
+                Some(CalendarCommand::Color { name, color }) => {
+                    let color = parse_color(&color)?;
+                    let calendar = find_calendar(conn, &name)?;
+                    update_calendar(conn, &Calendar { color, ..calendar })?;
+                }
+                Some(CalendarCommand::Show { name }) => {
+                    let calendar = find_calendar(conn, &name)?;
+                    update_calendar(conn, &Calendar { visible: true, ..calendar })?;
+                }
+                Some(CalendarCommand::Hide { name }) => {
+                    let calendar = find_calendar(conn, &name)?;
+                    update_calendar(conn, &Calendar { visible: false, ..calendar })?;
+                }
+                Some(CalendarCommand::Delete { name }) => {
+                    let calendar = find_calendar(conn, &name)?;
+                    delete_calendar(conn, calendar.id.unwrap_or_default())?;
+                }
+            }
+            print_calendars(&get_calendars(conn)?, json)
+        }
+        Command::Sync => {
+            let caldav = config
+                .caldav
+                .as_ref()
+                .ok_or_else(|| anyhow!("No [caldav] section in the config file"))?;
+            let password = caldav.password.clone().or_else(|| std::env::var("CALDAV_PASSWORD").ok());
+            let transport = UreqTransport::new(caldav.username.as_deref(), password.as_deref());
+            let client = CalDavClient::new(transport, &caldav.url)?;
+            let report = sync(conn, &client, config.timezone(), caldav.past_days, caldav.future_days)?;
+            if json {
+                let output = SyncOutput {
+                    calendars: report.calendars,
+                    pulled: report.pulled,
+                    deleted_locally: report.deleted_locally,
+                    pushed: report.pushed,
+                    deleted_remotely: report.deleted_remotely,
+                    conflicts: report.conflicts,
+                };
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!(
+                    "Synced {} calendars: pulled {}, pushed {}, deleted {} locally and {} remotely",
+                    report.calendars, report.pulled, report.pushed, report.deleted_locally, report.deleted_remotely
+                );
+                if report.conflicts > 0 {
+                    println!("{} conflicts; the server copy was kept", report.conflicts);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn find_calendar(conn: &Connection, name: &str) -> Result<Calendar> {
+    get_calendar_by_name(conn, name)?.ok_or_else(|| anyhow!("Calendar '{}' not found", name))
+}
+
+/// Checks a calendar color; empty means none.
+fn parse_color(value: &str) -> Result<Option<String>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse::<Color>()
+        .map_err(|_| anyhow!("Invalid color '{}', expected a name, #rrggbb or 0-255", value))?;
+    Ok(Some(value.to_string()))
+}
+
+fn print_calendars(calendars: &[Calendar], json: bool) -> Result<()> {
+    if json {
+        let output: Vec<CalendarOutput> = calendars
+            .iter()
+            .map(|c| CalendarOutput {
+                id: c.id,
+                name: c.name.clone(),
+                color: c.color.clone(),
+                visible: c.visible,
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        for calendar in calendars {
+            let visibility = if calendar.visible { "" } else { "  (hidden)" };
+            println!("{:<20} {}{}", calendar.name, calendar.color.as_deref().unwrap_or("(theme)"), visibility);
+        }
+    }
+    Ok(())
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| anyhow!("Invalid date '{}', expected YYYY-MM-DD", value))
+}
+
+/// Parses a wall-clock time in `tz`.
+fn parse_datetime(tz: Tz, value: &str) -> Result<DateTime<Utc>> {
+    let naive = ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .ok_or_else(|| anyhow!("Invalid date-time '{}', expected YYYY-MM-DD HH:MM", value))?;
+    local_to_utc(tz, naive).ok_or_else(|| anyhow!("'{}' does not exist in {} (daylight saving gap)", value, tz))
+}
+
+fn parse_recurrence(value: Option<&str>) -> Result<Option<Recurrence>> {
+    match value.map(str::trim) {
+        Some(rule) if !rule.is_empty() => Ok(Some(rule.parse()?)),
+        _ => Ok(None),
+    }
+}
+
+fn parse_filter(value: Option<&str>) -> Result<TagFilter> {
+    value.map(str::parse).transpose().map(Option::unwrap_or_default)
+}
+
+/// Occurrences between the start of `from` and the end of `to`, as local
+/// days in `tz`, of the events matching `filter`.
+fn events_between(conn: &Connection, tz: Tz, from: NaiveDate, to: NaiveDate, filter: &TagFilter) -> Result<Vec<Event>> {
+    let (start, end) = day_range(tz, from, to);
+    get_events_in_range(conn, start, end, filter)
+}
+
+fn format_event_line(event: &Event, config: &Config) -> String {
+    let tz = config.timezone();
+    let time_format = config.ui.time_pattern();
+    let mut line = if event.all_day {
+        format!("{:<11}  {}", "all day", event.title)
+    } else {
+        format!(
+            "{}-{}  {}",
+            to_local(tz, event.start_datetime).format(time_format),
+            to_local(tz, event.end_datetime).format(time_format),
+            event.title
+        )
+    };
+    if let Some(location) = event.location.as_deref().filter(|l| !l.is_empty()) {
+        line.push_str(&format!(" @ {}", location));
+    }
+    if !event.tags.is_empty() {
+        line.push_str(&format!("  ({})", format_tags(&event.tags)));
+    }
+    if let Some(id) = event.id {
+        line.push_str(&format!("  [#{}]", id));
+    }
+    line
+}
+
+fn print_events(events: &[Event], json: bool, config: &Config) -> Result<()> {
+    if json {
+        let output: Vec<EventOutput> = events.iter().map(EventOutput::from).collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        for event in events {
+            let (day, _) = event.date_span(config.timezone());
+            println!("{}  {}", day.format("%Y-%m-%d"), format_event_line(event, config));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::open_memory_db;
+
+    fn run_args(conn: &Connection, config: &Config, args: &[&str]) -> Result<()> {
+        let cli = Cli::try_parse_from(["calendar-app"].iter().chain(args))?;
+        run(cli.command.expect("a command"), true, config, conn)
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    #[test]
+    fn edits_all_day_events_by_date() {
+        let conn = open_memory_db();
+        let mut config = Config::default();
+        config.calendar.timezone = Some("Europe/Warsaw".to_string());
+        let add = ["add", "--title", "Trip", "--all-day", "--start", "2024-05-01", "--end", "2024-05-02"];
+        run_args(&conn, &config, &add).unwrap();
+        let id = get_all_events(&conn).unwrap()[0].id.unwrap().to_string();
+
+        run_args(&conn, &config, &["edit", &id, "--end", "2024-05-03"]).unwrap();
+        let event = get_event(&conn, id.parse().unwrap()).unwrap().unwrap();
+        assert_eq!(
+            (event.start_datetime, event.end_datetime),
+            Event::all_day_bounds(date("2024-05-01"), date("2024-05-03"))
+        );
+
+        // A new start keeps the number of days.
+        run_args(&conn, &config, &["edit", &id, "--start", "2024-06-10"]).unwrap();
+        let event = get_event(&conn, id.parse().unwrap()).unwrap().unwrap();
+        assert_eq!(
+            (event.start_datetime, event.end_datetime),
+            Event::all_day_bounds(date("2024-06-10"), date("2024-06-12"))
+        );
+        assert!(event.covers_date(Tz::America__New_York, date("2024-06-12")));
+        assert!(!event.covers_date(Tz::Asia__Tokyo, date("2024-06-13")));
+
+        assert!(run_args(&conn, &config, &["edit", &id, "--start", "2024-06-10 09:00"]).is_err());
+        assert!(run_args(&conn, &config, &["edit", &id, "--end", "2024-06-09"]).is_err());
+    }
+}
@@ -0,0 +1,266 @@
+use crate::models::event::Event;
+use crate::storage::db::{delete_event, get_event, restore_event, update_event};
+use anyhow::Result;
+use rusqlite::Connection;
+
+/// Operations kept for undo; older ones are dropped.
+const HISTORY_LIMIT: usize = 100;
+
+/// One event's stored state before and after an operation. `None` means the
+/// event did not exist.
+struct Change {
+    id: i64,
+    before: Option<Event>,
+    after: Option<Event>,
+}
+
+struct Operation {
+    description: String,
+    changes: Vec<Change>,
+}
+
+/// Undo and redo stacks of event operations for the current session.
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Operation>,
+    redo: Vec<Operation>,
+}
+
+impl History {
+    /// Runs `operation` in a transaction and records it for undo. `touched`
+    /// lists the existing events it may change or delete; `operation` returns
+    /// the ids of the events it created, which are passed on.
+    pub fn perform<F>(
+        &mut self,
+        conn: &Connection,
+        description: String,
+        touched: &[i64],
+        operation: F,
+    ) -> Result<Vec<i64>>
+    where
+        F: FnOnce(&Connection) -> Result<Vec<i64>>,
+    {
+        let tx = conn.unchecked_transaction()?;
+        let mut before = Vec::new();
+        for &id in touched {
+            before.push((id, get_event(&tx, id)?));
+        }
+        let created = operation(&tx)?;
+        let mut changes = Vec::new();
+        for (id, before) in before {
+            changes.push(Change {
+                id,
+                before,
+                after: get_event(&tx, id)?,
+            });
+        }
+        for &id in &created {
+            changes.push(Change {
+                id,
+                before: None,
+                after: get_event(&tx, id)?,
+            });
+        }
+        tx.commit()?;
+
+        self.undo.push(Operation { description, changes });
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        Ok(created)
+    }
+
+    /// Reverts the latest operation, returning its description.
+    pub fn undo(&mut self, conn: &Connection) -> Result<Option<String>> {
+        let Some(operation) = self.undo.pop() else {
+            return Ok(None);
+        };
+        let tx = conn.unchecked_transaction()?;
+        for change in operation.changes.iter().rev() {
+            restore(&tx, change.id, change.before.as_ref())?;
+        }
+        tx.commit()?;
+        let description = operation.description.clone();
+        self.redo.push(operation);
+        Ok(Some(description))
+    }
+
+    /// Re-applies the latest undone operation, returning its description.
+    pub fn redo(&mut self, conn: &Connection) -> Result<Option<String>> {
+        let Some(operation) = self.redo.pop() else {
+            return Ok(None);
+        };
+        let tx = conn.unchecked_transaction()?;
+        for change in &operation.changes {
+            restore(&tx, change.id, change.after.as_ref())?;
+        }
+        tx.commit()?;
+        let description = operation.description.clone();
+        self.undo.push(operation);
+        Ok(Some(description))
+    }
+}
+
+/// Puts event `id` back into `state`, re-creating it under the same id if it
+/// was deleted.
+fn restore(conn: &Connection, id: i64, state: Option<&Event>) -> Result<()> {
+    let exists = get_event(conn, id)?.is_some();
+    match state {
+        None if exists => delete_event(conn, id),
+        None => Ok(()),
+        Some(event) if exists => update_event(conn, event),
+        Some(event) => restore_event(conn, event).map(|_| ()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::attendee::Attendee;
+    use crate::models::recurrence::RecurrenceScope;
+    use crate::storage::db::{create_event, get_all_events, open_memory_db, update_occurrences};
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+    }
+
+    fn add_event(conn: &Connection, title: &str) -> i64 {
+        create_event(
+            conn,
+            &Event {
+                title: title.to_string(),
+                start_datetime: at(2, 9),
+                end_datetime: at(2, 10),
+                reminders: vec![15],
+                tags: vec!["work".to_string()],
+                attendees: vec![Attendee {
+                    name: None,
+                    email: "ana@example.com".to_string(),
+                    role: Default::default(),
+                    status: Default::default(),
+                }],
+                ..Event::default()
+            },
+        )
+        .unwrap()
+    }
+
+    fn rename(history: &mut History, conn: &Connection, id: i64, title: &str) {
+        history
+            .perform(conn, format!("Rename to {}", title), &[id], |conn| {
+                let mut event = get_event(conn, id)?.unwrap();
+                event.title = title.to_string();
+                update_event(conn, &event)?;
+                Ok(Vec::new())
+            })
+            .unwrap();
+    }
+
+    fn title(conn: &Connection, id: i64) -> Option<String> {
+        get_event(conn, id).unwrap().map(|event| event.title)
+    }
+
+    #[test]
+    fn undoes_a_delete_under_the_original_id() {
+        let conn = open_memory_db();
+        add_event(&conn, "Other");
+        let id = add_event(&conn, "Review");
+        let original = get_event(&conn, id).unwrap().unwrap();
+        let mut history = History::default();
+
+        history
+            .perform(&conn, "Delete Review".to_string(), &[id], |conn| {
+                delete_event(conn, id)?;
+                Ok(Vec::new())
+            })
+            .unwrap();
+        assert!(get_event(&conn, id).unwrap().is_none());
+
+        assert_eq!(history.undo(&conn).unwrap().as_deref(), Some("Delete Review"));
+        let restored = get_event(&conn, id).unwrap().unwrap();
+        assert_eq!(restored.title, "Review");
+        assert_eq!(restored.reminders, original.reminders);
+        assert_eq!(restored.tags, original.tags);
+        assert_eq!(restored.attendees, original.attendees);
+
+        assert_eq!(history.redo(&conn).unwrap().as_deref(), Some("Delete Review"));
+        assert!(get_event(&conn, id).unwrap().is_none());
+        assert_eq!(history.redo(&conn).unwrap(), None);
+    }
+
+    #[test]
+    fn a_new_operation_clears_what_could_be_redone() {
+        let conn = open_memory_db();
+        let id = add_event(&conn, "A");
+        let mut history = History::default();
+        rename(&mut history, &conn, id, "B");
+        rename(&mut history, &conn, id, "C");
+
+        history.undo(&conn).unwrap();
+        assert_eq!(title(&conn, id).as_deref(), Some("B"));
+        rename(&mut history, &conn, id, "D");
+        assert_eq!(history.redo(&conn).unwrap(), None);
+
+        history.undo(&conn).unwrap();
+        history.undo(&conn).unwrap();
+        assert_eq!(title(&conn, id).as_deref(), Some("A"));
+        assert_eq!(history.undo(&conn).unwrap(), None);
+    }
+
+    #[test]
+    fn forgets_the_oldest_operations_past_the_limit() {
+        let conn = open_memory_db();
+        let id = add_event(&conn, "0");
+        let mut history = History::default();
+        for n in 1..=HISTORY_LIMIT + 1 {
+            rename(&mut history, &conn, id, &n.to_string());
+        }
+
+        for _ in 0..HISTORY_LIMIT {
+            assert!(history.undo(&conn).unwrap().is_some());
+        }
+        assert_eq!(history.undo(&conn).unwrap(), None);
+        // The first rename fell off the stack, so it stays.
+        assert_eq!(title(&conn, id).as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn undoes_an_occurrence_split_off_a_series() {
+        let conn = open_memory_db();
+        let id = add_event(&conn, "Standup");
+        let mut series = get_event(&conn, id).unwrap().unwrap();
+        series.recurrence = Some("FREQ=DAILY".parse().unwrap());
+        update_event(&conn, &series).unwrap();
+        let mut history = History::default();
+
+        let edited = Event {
+            title: "Moved".to_string(),
+            start_datetime: at(4, 11),
+            end_datetime: at(4, 12),
+            recurrence_id: Some(at(4, 9)),
+            ..series.clone()
+        };
+        let created = history
+            .perform(&conn, "Move Standup".to_string(), &[id], |conn| {
+                Ok(update_occurrences(conn, &edited, at(4, 9), RecurrenceScope::ThisOccurrence)?
+                    .into_iter()
+                    .collect())
+            })
+            .unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(get_event(&conn, id).unwrap().unwrap().exception_dates, [at(4, 9)]);
+
+        history.undo(&conn).unwrap();
+        let restored = get_event(&conn, id).unwrap().unwrap();
+        assert!(restored.exception_dates.is_empty());
+        assert_eq!(restored.recurrence, series.recurrence);
+        assert_eq!(get_all_events(&conn).unwrap().len(), 1);
+        assert!(get_event(&conn, created[0]).unwrap().is_none());
+
+        history.redo(&conn).unwrap();
+        assert_eq!(get_event(&conn, created[0]).unwrap().unwrap().start_datetime, at(4, 11));
+        assert_eq!(get_event(&conn, id).unwrap().unwrap().exception_dates, [at(4, 9)]);
+    }
+}
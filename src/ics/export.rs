@@ -0,0 +1,318 @@
+use crate::models::attendee::{Attendee, Role};
+use crate::models::event::Event;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+/// Content lines longer than this many octets are folded (RFC 5545 §3.1).
+const MAX_LINE_OCTETS: usize = 75;
+
+pub fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Splits `line` into CRLF-terminated chunks of at most 75 octets, never
+/// breaking inside a UTF-8 sequence.
+pub fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut octets = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts towards the limit.
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// A parameter value, quoted when it contains characters that end a
+/// parameter (RFC 5545 §3.2). Double quotes cannot be escaped and are dropped.
+fn param_value(value: &str) -> String {
+    let value = value.replace('"', "");
+    if value.contains([':', ';', ',']) {
+        format!("\"{}\"", value)
+    } else {
+        value
+    }
+}
+
+/// ORGANIZER or ATTENDEE line for `attendee`. The organizer is not listed
+/// again as an attendee; its PARTSTAT goes on the ORGANIZER line instead.
+fn attendee_line(attendee: &Attendee) -> String {
+    let name = attendee
+        .name
+        .as_deref()
+        .map(|name| format!(";CN={}", param_value(name)))
+        .unwrap_or_default();
+    let status = attendee.status.ics_name();
+    if attendee.role == Role::Organizer {
+        format!("ORGANIZER{};PARTSTAT={}:mailto:{}", name, status, attendee.email)
+    } else {
+        format!("ATTENDEE{};ROLE={};PARTSTAT={}:mailto:{}", name, attendee.role.ics_name(), status, attendee.email)
+    }
+}
+
+fn format_utc(datetime: DateTime<Utc>) -> String {
+    datetime.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// A DTSTART/DTEND/EXDATE line of `event`. All-day events use DATE values,
+/// events with a zone its local time and TZID, so that their recurrences keep
+/// the wall-clock time across DST changes, and the others UTC.
+fn time_property(event: &Event, name: &str, datetimes: &[DateTime<Utc>]) -> String {
+    let zone = event
+        .timezone
+        .as_deref()
+        .and_then(|name| name.parse::<Tz>().ok())
+        .filter(|zone| *zone != Tz::UTC);
+    let (params, values): (String, Vec<String>) = match zone {
+        _ if event.all_day => (
+            ";VALUE=DATE".to_string(),
+            datetimes.iter().map(|d| d.format("%Y%m%d").to_string()).collect(),
+        ),
+        Some(zone) => (
+            format!(";TZID={}", zone.name()),
+            datetimes
+                .iter()
+                .map(|d| d.with_timezone(&zone).format("%Y%m%dT%H%M%S").to_string())
+                .collect(),
+        ),
+        None => (String::new(), datetimes.iter().map(|d| format_utc(*d)).collect()),
+    };
+    format!("{}{}:{}", name, params, values.join(","))
+}
+
+/// UID for `event`, falling back to one derived from its row id.
+pub fn event_uid(event: &Event) -> String {
+    let uid = event
+        .uid
+        .clone()
+        .unwrap_or_else(|| format!("{}@calendar-app", event.id.unwrap_or_default()));
+    // Occurrences exported on their own need a UID distinct from their series.
+    match event.recurrence_id {
+        Some(recurrence_id) => format!("{}-{}", uid, format_utc(recurrence_id)),
+        None => uid,
+    }
+}
+
+fn write_event(out: &mut String, event: &Event, now: DateTime<Utc>) {
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", escape_text(&event_uid(event))),
+        format!("DTSTAMP:{}", format_utc(now)),
+        format!("CREATED:{}", format_utc(event.created_at)),
+        format!("LAST-MODIFIED:{}", format_utc(event.updated_at)),
+        time_property(event, "DTSTART", &[event.start_datetime]),
+        time_property(event, "DTEND", &[event.end_datetime]),
+        format!("SUMMARY:{}", escape_text(&event.title)),
+    ];
+    if let Some(description) = event.description.as_deref().filter(|d| !d.is_empty()) {
+        lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    if let Some(location) = event.location.as_deref().filter(|l| !l.is_empty()) {
+        lines.push(format!("LOCATION:{}", escape_text(location)));
+    }
+    if !event.tags.is_empty() {
+        let categories: Vec<String> = event.tags.iter().map(|tag| escape_text(tag)).collect();
+        lines.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+    lines.extend(event.attendees.iter().map(attendee_line));
+    // Expanded occurrences are written as plain events; only series carry rules.
+    if event.recurrence_id.is_none() {
+        if let Some(recurrence) = &event.recurrence {
+            lines.push(format!("RRULE:{}", recurrence));
+            if !event.exception_dates.is_empty() {
+                lines.push(time_property(event, "EXDATE", &event.exception_dates));
+            }
+        }
+    }
+    lines.push("END:VEVENT".to_string());
+
+    for line in lines {
+        out.push_str(&fold_line(&line));
+    }
+}
+
+/// Serialises `events` into a complete VCALENDAR document.
+pub fn export_events(events: &[Event]) -> String {
+    let now = Utc::now();
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//calendar-app//calendar-app//EN",
+        "CALSCALE:GREGORIAN",
+    ] {
+        out.push_str(&fold_line(line));
+    }
+    for event in events {
+        write_event(&mut out, event, now);
+    }
+    out.push_str(&fold_line("END:VCALENDAR"));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ics::import::import_str;
+    use crate::models::tags::TagFilter;
+    use crate::storage::db::{get_events_in_range, open_memory_db};
+    use chrono::{TimeZone, Timelike};
+
+    fn utc(month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn keeps_the_local_time_of_a_series_across_dst() {
+        // Weekly at 09:00 in Warsaw: 07:00 UTC in summer time, 08:00 UTC after 25 October.
+        let series = Event {
+            uid: Some("standup@example.com".to_string()),
+            title: "Standup".to_string(),
+            start_datetime: utc(10, 19, 7),
+            end_datetime: utc(10, 19, 8),
+            timezone: Some("Europe/Warsaw".to_string()),
+            recurrence: Some("FREQ=WEEKLY".parse().unwrap()),
+            exception_dates: vec![utc(11, 2, 8)],
+            ..Event::default()
+        };
+        let ics = export_events(&[series]);
+        assert!(ics.contains("DTSTART;TZID=Europe/Warsaw:20261019T090000\r\n"));
+        assert!(ics.contains("DTEND;TZID=Europe/Warsaw:20261019T100000\r\n"));
+        assert!(ics.contains("EXDATE;TZID=Europe/Warsaw:20261102T090000\r\n"));
+
+        let conn = open_memory_db();
+        import_str(&conn, &ics, None, Tz::UTC).unwrap();
+        let events =
+            get_events_in_range(&conn, utc(10, 19, 0).timestamp(), utc(11, 10, 0).timestamp(), &TagFilter::default())
+                .unwrap();
+        assert_eq!(events[0].timezone.as_deref(), Some("Europe/Warsaw"));
+        let starts: Vec<_> = events.iter().map(|e| e.start_datetime).collect();
+        assert_eq!(starts, [utc(10, 19, 7), utc(10, 26, 8), utc(11, 9, 8)]);
+        assert!(events.iter().all(|e| e.local_start().hour() == 9));
+    }
+
+    #[test]
+    fn writes_events_without_a_zone_in_utc() {
+        let event = Event {
+            title: "Call".to_string(),
+            start_datetime: utc(10, 19, 7),
+            end_datetime: utc(10, 19, 8),
+            timezone: Some("UTC".to_string()),
+            ..Event::default()
+        };
+        let ics = export_events(&[event]);
+        assert!(ics.contains("DTSTART:20261019T070000Z\r\n"));
+        assert!(ics.contains("DTEND:20261019T080000Z\r\n"));
+    }
+
+    #[test]
+    fn escapes_text_values() {
+        assert_eq!(escape_text("a\\b;c,d\r\ne"), "a\\\\b\\;c\\,d\\ne");
+        assert_eq!(crate::ics::unescape_text(&escape_text("a\\b;c,d\ne")), "a\\b;c,d\ne");
+    }
+
+    #[test]
+    fn folds_long_lines_between_characters() {
+        let line = format!("DESCRIPTION:{}", "ż".repeat(50));
+        let folded = fold_line(&line);
+        let physical: Vec<&str> = folded.strip_suffix("\r\n").unwrap().split("\r\n").collect();
+        assert!(physical.len() > 1);
+        assert!(physical.iter().all(|l| l.len() <= MAX_LINE_OCTETS));
+        assert!(physical[1..].iter().all(|l| l.starts_with(' ')));
+        // The first line stops short of 75 octets rather than split a two-byte 'ż'.
+        assert_eq!(physical[0].len(), 74);
+        assert_eq!(crate::ics::unfold(&folded), [line]);
+
+        assert_eq!(fold_line("SUMMARY:short"), "SUMMARY:short\r\n");
+    }
+
+    #[test]
+    fn round_trips_text_through_export_and_import() {
+        let event = Event {
+            uid: Some("review@example.com".to_string()),
+            title: "Review; part 1, draft".to_string(),
+            description: Some(format!("Bring:\n- notes, \\slides\\\n{}", "Zażółć gęślą jaźń. ".repeat(6))),
+            location: Some("Café \"Kraków\", 2nd floor; room 4".to_string()),
+            start_datetime: utc(10, 19, 7),
+            end_datetime: utc(10, 19, 8),
+            tags: vec!["planning".to_string(), "work".to_string()],
+            ..Event::default()
+        };
+        let conn = open_memory_db();
+        let report = import_str(&conn, &export_events(std::slice::from_ref(&event)), None, Tz::UTC).unwrap();
+        assert_eq!(report.imported, 1);
+        let imported = crate::storage::db::get_event_by_uid(&conn, "review@example.com").unwrap().unwrap();
+        assert_eq!(imported.title, event.title);
+        assert_eq!(imported.description, event.description);
+        assert_eq!(imported.location, event.location);
+        assert_eq!(imported.tags, event.tags);
+        assert_eq!((imported.start_datetime, imported.end_datetime), (event.start_datetime, event.end_datetime));
+    }
+
+    #[test]
+    fn round_trips_the_organizer_and_attendees() {
+        use crate::models::attendee::Status;
+        let attendees = vec![
+            Attendee {
+                name: Some("Kowalski, Jan".to_string()),
+                email: "jan@example.com".to_string(),
+                role: Role::Organizer,
+                status: Status::Accepted,
+            },
+            Attendee {
+                name: None,
+                email: "bob@example.com".to_string(),
+                role: Role::Optional,
+                status: Status::Tentative,
+            },
+            Attendee {
+                name: Some("Ana".to_string()),
+                email: "ana@example.com".to_string(),
+                role: Role::Chair,
+                status: Status::Declined,
+            },
+        ];
+        let event = Event {
+            uid: Some("meeting@example.com".to_string()),
+            title: "Meeting".to_string(),
+            start_datetime: utc(10, 19, 7),
+            end_datetime: utc(10, 19, 8),
+            attendees: attendees.clone(),
+            ..Event::default()
+        };
+        let ics = export_events(&[event]);
+        let people: Vec<String> = crate::ics::unfold(&ics)
+            .into_iter()
+            .filter(|line| line.starts_with("ORGANIZER") || line.starts_with("ATTENDEE"))
+            .collect();
+        assert_eq!(
+            people,
+            [
+                "ORGANIZER;CN=\"Kowalski, Jan\";PARTSTAT=ACCEPTED:mailto:jan@example.com",
+                "ATTENDEE;ROLE=OPT-PARTICIPANT;PARTSTAT=TENTATIVE:mailto:bob@example.com",
+                "ATTENDEE;CN=Ana;ROLE=CHAIR;PARTSTAT=DECLINED:mailto:ana@example.com",
+            ]
+        );
+
+        let conn = open_memory_db();
+        import_str(&conn, &ics, None, Tz::UTC).unwrap();
+        let imported = crate::storage::db::get_event_by_uid(&conn, "meeting@example.com").unwrap().unwrap();
+        assert_eq!(imported.attendees, attendees);
+    }
+}
@@ -0,0 +1,355 @@
+use crate::{
+    ics::{
+        parse_components, parse_datetime_value, parse_duration, parse_property_datetime, unescape_text, Component,
+        Property,
+    },
+    models::{
+        attendee::{Attendee, Role, Status},
+        event::Event,
+        recurrence::Recurrence,
+        tags::parse_tags,
+    },
+    storage::db::{add_exception_date, create_event, get_event_by_uid},
+};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use rusqlite::Connection;
+use std::fs;
+use std::path::Path;
+
+#[derive(Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub skipped: usize,
+    pub malformed: usize,
+}
+
+pub fn import_file(conn: &Connection, path: &Path, calendar_id: Option<i64>, floating: Tz) -> Result<ImportReport> {
+    let input = fs::read_to_string(path)?;
+    import_str(conn, &input, calendar_id, floating)
+}
+
+/// Imports every VEVENT in `input` into `calendar_id` (the default calendar
+/// when `None`), skipping UIDs that are already stored. Floating times are
+/// read in `floating`.
+pub fn import_str(conn: &Connection, input: &str, calendar_id: Option<i64>, floating: Tz) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut masters = Vec::new();
+    let mut overrides = Vec::new();
+
+    for component in parse_components(input) {
+        match component {
+            Ok(component) if component.name == "VEVENT" => match parse_event(&component, floating) {
+                Ok(event) if event.recurrence_id.is_some() => overrides.push(Event { calendar_id, ..event }),
+                Ok(event) => masters.push(Event { calendar_id, ..event }),
+                Err(_) => report.malformed += 1,
+            },
+            Ok(_) => {}
+            Err(_) => report.malformed += 1,
+        }
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for event in masters {
+        let uid = event.uid.clone().unwrap_or_default();
+        if get_event_by_uid(&tx, &uid)?.is_some() {
+            report.skipped += 1;
+            continue;
+        }
+        create_event(&tx, &event)?;
+        report.imported += 1;
+    }
+
+    // Modified occurrences become standalone events, excluded from their series.
+    for event in overrides {
+        let (Some(uid), Some(recurrence_id)) = (event.uid.as_deref(), event.recurrence_id) else {
+            report.malformed += 1;
+            continue;
+        };
+        let override_uid = format!("{}#{}", uid, recurrence_id.format("%Y%m%dT%H%M%SZ"));
+        if get_event_by_uid(&tx, &override_uid)?.is_some() {
+            report.skipped += 1;
+            continue;
+        }
+        if let Some(series_id) = get_event_by_uid(&tx, uid)?.and_then(|series| series.id) {
+            add_exception_date(&tx, series_id, recurrence_id)?;
+        }
+        create_event(
+            &tx,
+            &Event {
+                uid: Some(override_uid),
+                recurrence: None,
+                recurrence_id: None,
+                ..event
+            },
+        )?;
+        report.imported += 1;
+    }
+    tx.commit()?;
+
+    Ok(report)
+}
+
+fn text_property(component: &Component, name: &str) -> Option<String> {
+    component
+        .property(name)
+        .map(|p| unescape_text(&p.value))
+        .filter(|value| !value.is_empty())
+}
+
+/// Parses a VEVENT. Overrides of single occurrences keep their RECURRENCE-ID.
+/// Floating times are wall-clock times in `floating`, which the event keeps
+/// as its zone so that recurrences stay at the same local time.
+pub fn parse_event(component: &Component, floating: Tz) -> Result<Event> {
+    let dtstart_property = component
+        .property("DTSTART")
+        .ok_or_else(|| anyhow!("VEVENT without DTSTART"))?;
+    let dtstart = parse_property_datetime(dtstart_property, floating)?;
+    let timezone = if dtstart_property.value.trim().ends_with('Z') {
+        None
+    } else {
+        Some(
+            dtstart_property
+                .param("TZID")
+                .map(|tzid| tzid.trim_start_matches('/'))
+                .filter(|tzid| tzid.parse::<Tz>().is_ok())
+                .map_or_else(|| floating.name().to_string(), str::to_string),
+        )
+    };
+    let start = dtstart.datetime;
+    let end = if let Some(dtend) = component.property("DTEND") {
+        parse_property_datetime(dtend, floating)?.datetime
+    } else if let Some(duration) = component.property("DURATION") {
+        start + parse_duration(&duration.value)?
+    } else if dtstart.is_date {
+        start + Duration::days(1)
+    } else {
+        start
+    };
+    if end < start {
+        return Err(anyhow!("DTEND before DTSTART"));
+    }
+
+    let recurrence = component
+        .property("RRULE")
+        .map(|p| p.value.parse::<Recurrence>())
+        .transpose()?;
+    let mut exception_dates = Vec::new();
+    for exdate in component.properties("EXDATE") {
+        for value in exdate.value.split(',') {
+            exception_dates.push(parse_datetime_value(value, exdate.param("TZID"), floating)?.datetime);
+        }
+    }
+    let recurrence_id = component
+        .property("RECURRENCE-ID")
+        .map(|property| parse_property_datetime(property, floating))
+        .transpose()?
+        .map(|r| r.datetime);
+
+    let title = text_property(component, "SUMMARY").unwrap_or_default();
+    let uid = text_property(component, "UID").unwrap_or_else(|| synthetic_uid(&title, start));
+
+    Ok(Event {
+        id: None,
+        uid: Some(uid),
+        calendar_id: None,
+        title,
+        description: text_property(component, "DESCRIPTION"),
+        start_datetime: start,
+        end_datetime: end,
+        all_day: dtstart.is_date,
+        timezone: timezone.filter(|_| !dtstart.is_date),
+        location: text_property(component, "LOCATION"),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        recurrence,
+        exception_dates,
+        reminders: Vec::new(),
+        tags: categories(component),
+        attendees: attendees(component),
+        recurrence_id,
+    })
+}
+
+/// People from the ORGANIZER and ATTENDEE properties. An organizer who is
+/// also listed as an attendee keeps that entry's participation status.
+fn attendees(component: &Component) -> Vec<Attendee> {
+    let person = |property: &Property, role: Role| {
+        let value = property.value.trim();
+        let email = match value.get(..7) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("mailto:") => &value[7..],
+            _ => value,
+        };
+        (!email.is_empty()).then(|| Attendee {
+            name: property.param("CN").map(str::to_string).filter(|name| !name.is_empty()),
+            email: email.to_string(),
+            role,
+            status: Status::from_ics(property.param("PARTSTAT")),
+        })
+    };
+
+    let mut attendees: Vec<Attendee> =
+        component.property("ORGANIZER").and_then(|p| person(p, Role::Organizer)).into_iter().collect();
+    for property in component.properties("ATTENDEE") {
+        let Some(attendee) = person(property, Role::from_ics(property.param("ROLE"))) else {
+            continue;
+        };
+        match attendees.iter_mut().find(|a| a.email.eq_ignore_ascii_case(&attendee.email)) {
+            Some(existing) => {
+                existing.status = attendee.status;
+                existing.name = existing.name.take().or(attendee.name);
+            }
+            None => attendees.push(attendee),
+        }
+    }
+    attendees
+}
+
+/// Tags from the CATEGORIES properties, one per category. Spaces inside a
+/// category become dashes so it stays a single tag.
+fn categories(component: &Component) -> Vec<String> {
+    let mut tags = Vec::new();
+    for property in component.properties("CATEGORIES") {
+        let mut category = String::new();
+        let mut escaped = false;
+        // Commas separate categories unless escaped.
+        for c in property.value.chars().chain([',']) {
+            if c == ',' && !escaped {
+                let words: Vec<&str> = category.split_whitespace().collect();
+                tags.extend(parse_tags(&unescape_text(&words.join("-"))));
+                category.clear();
+            } else {
+                category.push(c);
+            }
+            escaped = c == '\\' && !escaped;
+        }
+    }
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Stable UID for events that lack one, so re-importing them stays idempotent.
+fn synthetic_uid(title: &str, start: DateTime<Utc>) -> String {
+    // FNV-1a: simple and stable across builds, unlike `DefaultHasher`.
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in title.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{}-{:016x}@calendar-app", start.timestamp(), hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{get_all_events, open_memory_db};
+    use chrono::TimeZone;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+SUMMARY:Stand\r
+ up ☕\r
+DESCRIPTION:Agenda:\\n1. News\\, notes\\; \\\\ backslash\r
+LOCATION:Room 4\r
+DTSTART;TZID=Europe/Warsaw:20261019T090000\r
+DTEND;TZID=Europe/Warsaw:20261019T093000\r
+RRULE:FREQ=WEEKLY\r
+CATEGORIES:work,Team Sync\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+RECURRENCE-ID;TZID=Europe/Warsaw:20261026T090000\r
+SUMMARY:Standup (moved)\r
+DTSTART;TZID=Europe/Warsaw:20261026T110000\r
+DTEND;TZID=Europe/Warsaw:20261026T113000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Holiday\r
+DTSTART;VALUE=DATE:20261101\r
+DTEND;VALUE=DATE:20261103\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:broken@example.com\r
+SUMMARY:No start\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn imports_events_overrides_and_all_day_dates() {
+        let conn = open_memory_db();
+        let report = import_str(&conn, CALENDAR, None, Tz::UTC).unwrap();
+        assert_eq!((report.imported, report.skipped, report.malformed), (3, 0, 1));
+
+        let series = get_event_by_uid(&conn, "standup@example.com").unwrap().unwrap();
+        assert_eq!(series.title, "Standup ☕");
+        assert_eq!(series.description.as_deref(), Some("Agenda:\n1. News, notes; \\ backslash"));
+        assert_eq!(series.location.as_deref(), Some("Room 4"));
+        assert_eq!((series.start_datetime, series.end_datetime), (utc(10, 19, 7, 0), utc(10, 19, 7, 30)));
+        assert_eq!(series.timezone.as_deref(), Some("Europe/Warsaw"));
+        assert_eq!(series.tags, ["team-sync", "work"]);
+        // The moved occurrence is excluded from the series and stored on its own.
+        assert_eq!(series.exception_dates, [utc(10, 26, 8, 0)]);
+        let moved = get_event_by_uid(&conn, "standup@example.com#20261026T080000Z").unwrap().unwrap();
+        assert_eq!((moved.title.as_str(), moved.start_datetime), ("Standup (moved)", utc(10, 26, 10, 0)));
+        assert!(moved.recurrence.is_none());
+
+        let holiday = get_all_events(&conn).unwrap().into_iter().find(|e| e.title == "Holiday").unwrap();
+        assert!(holiday.all_day && holiday.timezone.is_none());
+        assert_eq!((holiday.start_datetime, holiday.end_datetime), (utc(11, 1, 0, 0), utc(11, 3, 0, 0)));
+        assert!(holiday.uid.unwrap().ends_with("@calendar-app"));
+    }
+
+    #[test]
+    fn importing_the_same_file_again_changes_nothing() {
+        let conn = open_memory_db();
+        import_str(&conn, CALENDAR, None, Tz::UTC).unwrap();
+        let before = get_all_events(&conn).unwrap();
+
+        let report = import_str(&conn, CALENDAR, None, Tz::UTC).unwrap();
+        assert_eq!((report.imported, report.skipped, report.malformed), (0, 3, 1));
+        let after = get_all_events(&conn).unwrap();
+        assert_eq!(after.len(), 3);
+        for (before, after) in before.iter().zip(&after) {
+            assert_eq!((&before.id, &before.uid, &before.title), (&after.id, &after.uid, &after.title));
+            assert_eq!(before.updated_at, after.updated_at);
+            assert_eq!(before.exception_dates, after.exception_dates);
+        }
+    }
+
+    #[test]
+    fn lists_an_organizer_who_is_also_an_attendee_once() {
+        let input = "BEGIN:VEVENT\r\nUID:sync@example.com\r\nDTSTART:20261019T070000Z\r\n\
+                     ORGANIZER;CN=Ana:MAILTO:Ana@Example.com\r\n\
+                     ATTENDEE;ROLE=CHAIR;PARTSTAT=ACCEPTED:mailto:ana@example.com\r\n\
+                     ATTENDEE;CN=Bob:mailto:bob@example.com\r\nEND:VEVENT\r\n";
+        let conn = open_memory_db();
+        import_str(&conn, input, None, Tz::UTC).unwrap();
+        let event = get_event_by_uid(&conn, "sync@example.com").unwrap().unwrap();
+        assert_eq!(
+            event.attendees,
+            [
+                Attendee {
+                    name: Some("Ana".to_string()),
+                    email: "Ana@Example.com".to_string(),
+                    role: Role::Organizer,
+                    status: Status::Accepted,
+                },
+                Attendee {
+                    name: Some("Bob".to_string()),
+                    email: "bob@example.com".to_string(),
+                    role: Role::Required,
+                    status: Status::NeedsAction,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,252 @@
+pub mod export;
+pub mod import;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// A single content line, e.g. `DTSTART;TZID=Europe/Warsaw:20240102T090000`.
+pub struct Property {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A VEVENT (or other component) with its properties, in file order.
+pub struct Component {
+    pub name: String,
+    pub properties: Vec<Property>,
+}
+
+impl Component {
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    pub fn properties<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Property> {
+        self.properties.iter().filter(move |p| p.name == name)
+    }
+}
+
+/// Joins folded lines (RFC 5545 §3.1) back into logical content lines.
+pub fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in input.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(continuation) = raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(continuation);
+                continue;
+            }
+        }
+        if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+pub fn parse_property(line: &str) -> Result<Property> {
+    let mut in_quotes = false;
+    let mut colon = None;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => {
+                colon = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let colon = colon.ok_or_else(|| anyhow!("Missing ':' in '{}'", line))?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut parts = split_unquoted(head, ';').into_iter();
+    let name = parts.next().unwrap_or_default().to_ascii_uppercase();
+    if name.is_empty() {
+        bail!("Missing property name in '{}'", line);
+    }
+    let params = parts
+        .filter_map(|param| {
+            param
+                .split_once('=')
+                .map(|(k, v)| (k.to_ascii_uppercase(), v.trim_matches('"').to_string()))
+        })
+        .collect();
+
+    Ok(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+fn split_unquoted(s: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in s.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            c if c == separator && !in_quotes => parts.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+/// Splits a calendar file into its components, flattening nesting.
+pub fn parse_components(input: &str) -> Vec<Result<Component>> {
+    let mut components = Vec::new();
+    let mut stack: Vec<Result<Component>> = Vec::new();
+
+    for line in unfold(input) {
+        let property = match parse_property(&line) {
+            Ok(property) => property,
+            Err(err) => {
+                if let Some(current) = stack.last_mut() {
+                    if current.is_ok() {
+                        *current = Err(err);
+                    }
+                }
+                continue;
+            }
+        };
+        match property.name.as_str() {
+            "BEGIN" => stack.push(Ok(Component {
+                name: property.value.to_ascii_uppercase(),
+                properties: Vec::new(),
+            })),
+            "END" => {
+                if let Some(component) = stack.pop() {
+                    components.push(component);
+                }
+            }
+            _ => {
+                if let Some(Ok(current)) = stack.last_mut() {
+                    current.properties.push(property);
+                }
+            }
+        }
+    }
+    components.extend(stack);
+    components
+}
+
+pub fn unescape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => result.push('\n'),
+                Some(other) => result.push(other),
+                None => {}
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// A DTSTART/DTEND style value resolved to UTC. `is_date` marks VALUE=DATE.
+pub struct IcsDateTime {
+    pub datetime: DateTime<Utc>,
+    pub is_date: bool,
+}
+
+/// Parses a DATE or DATE-TIME value. Floating times (neither `Z` nor a known
+/// TZID) are wall-clock times in `floating`, the zone events are shown in.
+pub fn parse_datetime_value(value: &str, tzid: Option<&str>, floating: Tz) -> Result<IcsDateTime> {
+    let value = value.trim();
+    if value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| anyhow!("Invalid date '{}'", value))?;
+        return Ok(IcsDateTime {
+            datetime: Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default()),
+            is_date: true,
+        });
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .map_err(|_| anyhow!("Invalid date-time '{}'", value))?;
+        return Ok(IcsDateTime {
+            datetime: Utc.from_utc_datetime(&naive),
+            is_date: false,
+        });
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .map_err(|_| anyhow!("Invalid date-time '{}'", value))?;
+    let tz = tzid
+        .and_then(|tzid| tzid.trim_start_matches('/').parse::<Tz>().ok())
+        .unwrap_or(floating);
+    let datetime = tz
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| anyhow!("Nonexistent local time '{}' in {}", value, tz))?
+        .with_timezone(&Utc);
+    Ok(IcsDateTime {
+        datetime,
+        is_date: false,
+    })
+}
+
+pub fn parse_property_datetime(property: &Property, floating: Tz) -> Result<IcsDateTime> {
+    let mut parsed = parse_datetime_value(&property.value, property.param("TZID"), floating)?;
+    if property.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) {
+        parsed.is_date = true;
+    }
+    Ok(parsed)
+}
+
+/// Parses a DURATION value such as `PT1H30M`, `P1D` or `-P1W`.
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let (negative, rest) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let rest = rest.strip_prefix('P').ok_or_else(|| anyhow!("Invalid duration '{}'", value))?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = number.parse().map_err(|_| anyhow!("Invalid duration '{}'", value))?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => bail!("Invalid duration '{}'", value),
+                };
+            }
+        }
+    }
+    if !number.is_empty() {
+        bail!("Invalid duration '{}'", value);
+    }
+    Ok(if negative { -total } else { total })
+}
@@ -1,20 +1,54 @@
 use crate::{
-    app::{App, AppState, EventFormState, InteractionMode},
-    models::event::Event,
-    storage::db::{create_event, delete_event, get_events_in_range, update_event},
+    app::{
+        slot_start, time_field, App, AppState, EventFormState, Focus, FormField, InteractionMode, MoveState,
+        QuickAddState, ScopeAction, ScopePromptState, SearchState, TagFilterState,
+    },
+    models::{
+        attendee::format_attendees,
+        event::Event,
+        keys::Action,
+        quick_add::parse_quick_add,
+        recurrence::{Recurrence, RecurrenceScope},
+        reminder::{format_reminders, parse_reminders},
+        tags::{format_tags, parse_tags, TagFilter},
+        timezone::{local_to_utc, local_to_utc_lenient},
+    },
+    storage::db::{
+        create_event, delete_occurrences, get_events_in_range, get_tags, search_events, update_calendar, update_event,
+        update_occurrences,
+    },
 };
-use chrono::{Datelike, Duration, TimeZone, Utc};
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use crossterm::event::{KeyCode, KeyEvent};
 use tui_textarea::TextArea;
 
 pub fn handle_input<'a>(key: KeyEvent, app: &mut App<'a>) {
-    if key.code == KeyCode::Tab {
+    app.banner.borrow_mut().take();
+    if app.show_help {
+        app.show_help = false;
+        return;
+    }
+    if app.show_details && !app.has_details_panel() {
+        app.show_details = false;
+        return;
+    }
+    if !app.is_typing() && app.keys.is(&key, Action::Help) {
+        app.show_help = true;
+        return;
+    }
+    if !app.is_typing() && app.keys.is(&key, Action::NextView) {
         app.state = match app.state {
             AppState::Year => AppState::Month,
             AppState::Month => AppState::Week,
             AppState::Week => AppState::Day,
-            AppState::Day => AppState::Year,
+            AppState::Day => AppState::Agenda,
+            AppState::Agenda => AppState::Year,
         };
+        app.show_details &= app.has_details_panel();
+        return;
+    }
+    if matches!(app.mode, InteractionMode::Navigation | InteractionMode::Selection) && handle_event_keys(key, app) {
         return;
     }
     match app.mode {
@@ -22,52 +56,344 @@ pub fn handle_input<'a>(key: KeyEvent, app: &mut App<'a>) {
         InteractionMode::Selection => handle_selection_input(key, app),
         InteractionMode::TimeSlot => handle_timeslot_input(key, app),
         InteractionMode::EventForm => handle_event_form_input(key, app),
+        InteractionMode::RecurrenceScope => handle_recurrence_scope_input(key, app),
+        InteractionMode::Calendars => handle_calendars_input(key, app),
+        InteractionMode::Search => handle_search_input(key, app),
+        InteractionMode::Move => handle_move_input(key, app),
+        InteractionMode::QuickAdd => handle_quick_add_input(key, app),
+        InteractionMode::TagFilter => handle_tag_filter_input(key, app),
+    }
+}
+
+/// Keys acting on events that work the same in every view. Returns whether
+/// `key` was one of them.
+fn handle_event_keys(key: KeyEvent, app: &mut App) -> bool {
+    let Some(action) = app.keys.action(&key) else {
+        return false;
+    };
+    match action {
+        Action::Undo => undo(app, false),
+        Action::Redo => undo(app, true),
+        Action::NextEvent => focus_adjacent(app, true),
+        Action::PreviousEvent => focus_adjacent(app, false),
+        Action::CycleSlot if matches!(app.state, AppState::Week | AppState::Day) => {
+            // Cycles through the events overlapping the selected slot.
+            let slot_events = app.slot_events();
+            let next = slot_events
+                .iter()
+                .position(|e| app.is_focused(e))
+                .map_or(0, |index| (index + 1) % slot_events.len().max(1));
+            if let Some(event) = slot_events.get(next) {
+                app.focus_on(event);
+            }
+        }
+        Action::Details if app.show_details => app.show_details = false,
+        Action::Details | Action::Edit | Action::Delete | Action::Move | Action::Duplicate => {
+            let Some(event) = target_event(app) else {
+                if action == Action::Details {
+                    if app.has_details_panel() {
+                        app.show_details = true;
+                    } else {
+                        *app.banner.borrow_mut() = Some("No event focused".to_string());
+                    }
+                }
+                return true;
+            };
+            match action {
+                Action::Details => {
+                    app.focus_on(&event);
+                    app.show_details = true;
+                }
+                Action::Edit => start_scoped_action(app, ScopeAction::Edit, event),
+                Action::Delete => start_scoped_action(app, ScopeAction::Delete, event),
+                Action::Move => {
+                    app.focus_on(&event);
+                    app.move_state = Some(MoveState {
+                        event,
+                        offset: Duration::zero(),
+                    });
+                    app.mode = InteractionMode::Move;
+                }
+                _ => duplicate_event(app, event),
+            }
+        }
+        _ => return false,
     }
+    true
+}
+
+/// The event details, edit, delete, move and duplicate act on: the focused one or, in
+/// selection mode, the selected agenda entry or an event in the selected slot.
+fn target_event(app: &App) -> Option<Event> {
+    if let Some(event) = app.focused_event() {
+        return Some(event);
+    }
+    if !matches!(app.mode, InteractionMode::Selection) {
+        return None;
+    }
+    if matches!(app.state, AppState::Agenda) {
+        return app.agenda_entries().into_iter().nth(app.agenda_selected).map(|(_, event)| event);
+    }
+    // Timed events in the slot win over the all-day banner of the selected date.
+    // All-day events are stored as UTC midnights, so they are matched by date
+    // rather than by overlap with the slot.
+    let slot_events = app.slot_events();
+    if !slot_events.is_empty() {
+        return slot_events.into_iter().next();
+    }
+    let (start, end) = app.day_range(app.selected_date, app.selected_date);
+    app.events_in_range(start, end)
+        .into_iter()
+        .find(|e| e.is_multi_day(app.timezone) && e.covers_date(app.timezone, app.selected_date))
+}
+
+/// Focuses the event after (or before) the focused one, or the selected slot
+/// when nothing is focused, looking up to a year ahead (or back).
+fn focus_adjacent(app: &mut App, forward: bool) {
+    let (anchor, anchor_id) = match app.focus {
+        Some(focus) => (focus.start, Some(focus.event_id)),
+        None => (
+            local_to_utc_lenient(app.timezone, app.selected_date.and_time(app.selected_time)),
+            None,
+        ),
+    };
+    let (start, end) = if forward {
+        (anchor, anchor + Duration::days(366))
+    } else {
+        (anchor - Duration::days(366), anchor + Duration::seconds(1))
+    };
+    // Occurrences are ordered by start, ties broken by event id.
+    let current = (anchor, anchor_id.unwrap_or(if forward { i64::MIN } else { i64::MAX }));
+    let key = |event: &Event| (event.start_datetime, event.id.unwrap_or_default());
+    let events = app.events_in_range(start.timestamp(), end.timestamp());
+    let target = if forward {
+        events.into_iter().filter(|e| key(e) > current || (anchor_id.is_none() && key(e) == current)).min_by_key(key)
+    } else {
+        events.into_iter().filter(|e| key(e) < current).max_by_key(key)
+    };
+    let Some(event) = target else {
+        *app.banner.borrow_mut() = Some(format!("No {} event", if forward { "later" } else { "earlier" }));
+        return;
+    };
+    app.focus_on(&event);
+    reveal_event(app, &event);
+}
+
+/// Scrolls the current view to a newly focused event.
+fn reveal_event(app: &mut App, event: &Event) {
+    if matches!(app.state, AppState::Agenda) {
+        if let Some(index) = app.agenda_entries().iter().position(|(_, e)| app.is_focused(e)) {
+            app.agenda_selected = index;
+            return;
+        }
+    }
+    let local = if event.all_day {
+        event.start_datetime.naive_utc()
+    } else {
+        app.to_local(event.start_datetime)
+    };
+    if event.all_day {
+        app.selected_date = local.date();
+    } else {
+        select_slot(app, local);
+    }
+    app.agenda_selected = 0;
+}
+
+/// Creates a copy of `event` at the same time; an occurrence of a recurring
+/// series is copied as a single event.
+fn duplicate_event(app: &mut App, event: Event) {
+    let copy = Event {
+        id: None,
+        uid: None,
+        recurrence: if event.recurrence_id.is_some() { None } else { event.recurrence.clone() },
+        recurrence_id: None,
+        exception_dates: if event.recurrence_id.is_some() { Vec::new() } else { event.exception_dates.clone() },
+        ..event
+    };
+    let description = format!("duplicate '{}'", copy.title);
+    let result = app.history.perform(&app.conn, description, &[], |conn| Ok(vec![create_event(conn, &copy)?]));
+    app.event_cache.invalidate();
+    match result {
+        Ok(created) => {
+            app.focus = created.first().map(|&event_id| Focus {
+                event_id,
+                start: copy.start_datetime,
+            });
+            *app.banner.borrow_mut() = Some(format!("Duplicated '{}'", copy.title));
+        }
+        Err(err) => *app.banner.borrow_mut() = Some(format!("Duplicate failed: {}", err)),
+    }
+}
+
+fn handle_move_input(key: KeyEvent, app: &mut App) {
+    let Some(move_state) = &mut app.move_state else {
+        app.mode = InteractionMode::Navigation;
+        return;
+    };
+    // All-day events move by whole days; Up/Down shift them by a week.
+    let vertical = if move_state.event.all_day {
+        Duration::weeks(1)
+    } else {
+        app.slot
+    };
+    match app.keys.action(&key) {
+        Some(Action::Left) => move_state.offset -= Duration::days(1),
+        Some(Action::Right) => move_state.offset += Duration::days(1),
+        Some(Action::Up) => move_state.offset -= vertical,
+        Some(Action::Down) => move_state.offset += vertical,
+        Some(Action::Back) => {
+            app.move_state = None;
+            app.mode = InteractionMode::Navigation;
+        }
+        Some(Action::Select) => {
+            if let Some(move_state) = app.move_state.take() {
+                app.mode = InteractionMode::Navigation;
+                if !move_state.offset.is_zero() {
+                    start_scoped_action(app, ScopeAction::Move(move_state.offset), move_state.event);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn undo(app: &mut App, redo: bool) {
+    let result = if redo {
+        app.history.redo(&app.conn)
+    } else {
+        app.history.undo(&app.conn)
+    };
+    app.event_cache.invalidate();
+    let message = match result {
+        Ok(Some(description)) if redo => format!("Redid: {}", description),
+        Ok(Some(description)) => format!("Undid: {}", description),
+        Ok(None) if redo => "Nothing to redo".to_string(),
+        Ok(None) => "Nothing to undo".to_string(),
+        Err(err) => format!("Undo failed: {}", err),
+    };
+    *app.banner.borrow_mut() = Some(message);
 }
 
 fn handle_navigation_input(key: KeyEvent, app: &mut App) {
+    let Some(action) = app.keys.action(&key) else {
+        return;
+    };
+    if action == Action::Search {
+        app.search_state = Some(SearchState {
+            query: String::new(),
+            results: Vec::new(),
+            selected: 0,
+        });
+        app.mode = InteractionMode::Search;
+        return;
+    }
+    if action == Action::QuickAdd {
+        app.quick_add_state = Some(QuickAddState {
+            input: String::new(),
+            preview: Err(String::new()),
+        });
+        app.mode = InteractionMode::QuickAdd;
+        return;
+    }
+    if action == Action::Filter {
+        app.tag_filter_state = Some(TagFilterState {
+            input: app.tag_filter.to_string(),
+            error: None,
+            known_tags: get_tags(&app.conn).unwrap_or_default(),
+        });
+        app.mode = InteractionMode::TagFilter;
+        return;
+    }
+    if action == Action::Calendars {
+        app.reload_calendars();
+        app.selected_calendar = app.selected_calendar.min(app.calendars.len().saturating_sub(1));
+        app.mode = InteractionMode::Calendars;
+        return;
+    }
     match app.state {
-        AppState::Year => match key.code {
-            KeyCode::Enter => app.mode = InteractionMode::Selection,
-            KeyCode::Left => {
+        AppState::Year => match action {
+            Action::Select => {
+                app.mode = InteractionMode::Selection;
+                return;
+            }
+            Action::Left => {
                 app.selected_date = app.selected_date.with_month(app.selected_date.month() - 1).unwrap_or_else(|| {
                     app.selected_date.with_year(app.selected_date.year() - 1).unwrap().with_month(12).unwrap()
                 })
             }
-            KeyCode::Right => {
+            Action::Right => {
                 app.selected_date = app.selected_date.with_month(app.selected_date.month() + 1).unwrap_or_else(|| {
                     app.selected_date.with_year(app.selected_date.year() + 1).unwrap().with_month(1).unwrap()
                 })
             }
-            KeyCode::Up => app.selected_date = app.selected_date.with_year(app.selected_date.year() - 1).unwrap_or(app.selected_date),
-            KeyCode::Down => app.selected_date = app.selected_date.with_year(app.selected_date.year() + 1).unwrap_or(app.selected_date),
-            _ => {}
+            Action::Up => app.selected_date = app.selected_date.with_year(app.selected_date.year() - 1).unwrap_or(app.selected_date),
+            Action::Down => app.selected_date = app.selected_date.with_year(app.selected_date.year() + 1).unwrap_or(app.selected_date),
+            _ => return,
         },
-        AppState::Month => match key.code {
-            KeyCode::Enter => app.mode = InteractionMode::Selection,
-            KeyCode::Left => app.selected_date -= Duration::days(1),
-            KeyCode::Right => app.selected_date += Duration::days(1),
-            KeyCode::Up => app.selected_date -= Duration::weeks(1),
-            KeyCode::Down => app.selected_date += Duration::weeks(1),
-            _ => {}
+        AppState::Month => match action {
+            Action::Select => {
+                app.mode = InteractionMode::Selection;
+                return;
+            }
+            Action::Left => app.selected_date -= Duration::days(1),
+            Action::Right => app.selected_date += Duration::days(1),
+            Action::Up => app.selected_date -= Duration::weeks(1),
+            Action::Down => app.selected_date += Duration::weeks(1),
+            _ => return,
         },
-        AppState::Week | AppState::Day => match key.code {
-            KeyCode::Enter => app.mode = InteractionMode::Selection,
-            KeyCode::Left => app.selected_date -= Duration::days(1),
-            KeyCode::Right => app.selected_date += Duration::days(1),
-            KeyCode::Up => app.selected_time = app.selected_time.overflowing_sub_signed(Duration::minutes(30)).0,
-            KeyCode::Down => app.selected_time = app.selected_time.overflowing_add_signed(Duration::minutes(30)).0,
-            _ => {}
+        AppState::Agenda => match action {
+            Action::Select => {
+                app.mode = InteractionMode::Selection;
+                return;
+            }
+            Action::Left => {
+                app.selected_date -= Duration::days(1);
+                app.agenda_selected = 0;
+            }
+            Action::Right => {
+                app.selected_date += Duration::days(1);
+                app.agenda_selected = 0;
+            }
+            Action::Up => {
+                if app.agenda_selected == 0 {
+                    app.selected_date -= Duration::days(1);
+                } else {
+                    app.agenda_selected -= 1;
+                }
+            }
+            Action::Down => {
+                app.agenda_selected = (app.agenda_selected + 1).min(app.agenda_entries().len().saturating_sub(1))
+            }
+            _ => return,
         },
+        AppState::Week | AppState::Day => match action {
+            Action::Select => {
+                app.mode = InteractionMode::Selection;
+                return;
+            }
+            Action::Left => app.selected_date -= Duration::days(1),
+            Action::Right => app.selected_date += Duration::days(1),
+            Action::Up => app.selected_time = app.selected_time.overflowing_sub_signed(app.slot).0,
+            Action::Down => app.selected_time = app.selected_time.overflowing_add_signed(app.slot).0,
+            _ => return,
+        },
+    }
+    // Moving the cursor releases the focus; in the agenda it follows the highlighted entry.
+    app.focus = None;
+    if matches!(app.state, AppState::Agenda) {
+        if let Some((_, event)) = app.agenda_entries().into_iter().nth(app.agenda_selected) {
+            app.focus_on(&event);
+        }
     }
 }
 
 fn handle_selection_input(key: KeyEvent, app: &mut App) {
-    match key.code {
-        KeyCode::Esc => {
+    match app.keys.action(&key) {
+        Some(Action::Back) => {
             app.mode = InteractionMode::Navigation;
         }
-        KeyCode::Enter => match app.state {
+        Some(Action::Select) => match app.state {
             AppState::Year => {
                 app.state = AppState::Month;
                 app.mode = InteractionMode::Navigation;
@@ -76,59 +402,332 @@ fn handle_selection_input(key: KeyEvent, app: &mut App) {
                 app.state = AppState::Day;
                 app.mode = InteractionMode::Navigation;
             }
+            AppState::Agenda => {
+                if let Some((date, event)) = app.agenda_entries().into_iter().nth(app.agenda_selected) {
+                    select_event_slot(app, date, &event);
+                }
+                app.state = AppState::Day;
+                app.mode = InteractionMode::Navigation;
+            }
             AppState::Week | AppState::Day => {
                 app.mode = InteractionMode::TimeSlot;
                 app.selection_start = Some(app.selected_time);
             }
         },
-        KeyCode::Char('e') | KeyCode::Char('d') => {
-            let start_of_slot = app.selected_date.and_time(app.selected_time);
-            let end_of_slot = start_of_slot + Duration::minutes(30);
-            let start_timestamp = start_of_slot.and_utc().timestamp();
-            let end_timestamp = end_of_slot.and_utc().timestamp();
-
-            if let Ok(events) = get_events_in_range(&app.conn, start_timestamp, end_timestamp) {
-                if let Some(event) = events.first() {
-                    if key.code == KeyCode::Char('e') {
-                        app.mode = InteractionMode::EventForm;
-                        app.event_form_state = Some(EventFormState {
-                            title: TextArea::from(event.title.lines().map(|s| s.to_string())),
-                            description: TextArea::from(
-                                event.description.as_deref().unwrap_or("").lines().map(|s| s.to_string()),
-                            ),
-                            location: TextArea::from(
-                                event.location.as_deref().unwrap_or("").lines().map(|s| s.to_string()),
-                            ),
-                            start_datetime: event.start_datetime.naive_utc(),
-                            end_datetime: event.end_datetime.naive_utc(),
-                            focused_field: 0,
-                        });
-                        app.selected_event_id = event.id;
-                    } else if key.code == KeyCode::Char('d') {
-                        if let Some(id) = event.id {
-                            let _ = delete_event(&app.conn, id);
-                        }
+        _ => {}
+    }
+}
+
+/// Runs `action` on `event`, asking for a scope first when it is an
+/// occurrence of a recurring series.
+fn start_scoped_action(app: &mut App, action: ScopeAction, event: Event) {
+    if event.recurrence_id.is_some() {
+        app.mode = InteractionMode::RecurrenceScope;
+        app.scope_prompt_state = Some(ScopePromptState {
+            action,
+            event,
+            selected: 0,
+        });
+    } else {
+        apply_scoped_action(app, action, event, None);
+    }
+}
+
+fn handle_calendars_input(key: KeyEvent, app: &mut App) {
+    let action = app.keys.action(&key);
+    match action {
+        Some(Action::Back | Action::Calendars) => app.mode = InteractionMode::Navigation,
+        Some(Action::Up) => app.selected_calendar = app.selected_calendar.saturating_sub(1),
+        Some(Action::Down) => {
+            app.selected_calendar = (app.selected_calendar + 1).min(app.calendars.len().saturating_sub(1))
+        }
+        _ if action == Some(Action::Select) || key.code == KeyCode::Char(' ') => {
+            if let Some(calendar) = app.calendars.get_mut(app.selected_calendar) {
+                calendar.visible = !calendar.visible;
+                let _ = update_calendar(&app.conn, calendar);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Upper bound on results shown in the search popup.
+const SEARCH_LIMIT: usize = 50;
+
+fn handle_search_input(key: KeyEvent, app: &mut App) {
+    let Some(search) = &mut app.search_state else {
+        return;
+    };
+    match key.code {
+        KeyCode::Esc => {
+            app.search_state = None;
+            app.mode = InteractionMode::Navigation;
+        }
+        KeyCode::Up => search.selected = search.selected.saturating_sub(1),
+        KeyCode::Down => search.selected = (search.selected + 1).min(search.results.len().saturating_sub(1)),
+        KeyCode::Enter => {
+            if let Some(event) = search.results.get(search.selected).cloned() {
+                jump_to_event(app, &event);
+                app.search_state = None;
+                app.mode = InteractionMode::Navigation;
+            }
+        }
+        KeyCode::Backspace | KeyCode::Char(_) => {
+            match key.code {
+                KeyCode::Char(c) => search.query.push(c),
+                _ => {
+                    search.query.pop();
+                }
+            }
+            let mut results = search_events(&app.conn, &search.query, SEARCH_LIMIT).unwrap_or_default();
+            let calendars = &app.calendars;
+            results.retain(|event| {
+                calendars
+                    .iter()
+                    .find(|c| c.id == event.calendar_id)
+                    .is_none_or(|c| c.visible)
+            });
+            search.results = results;
+            search.selected = 0;
+        }
+        _ => {}
+    }
+}
+
+fn handle_quick_add_input(key: KeyEvent, app: &mut App) {
+    let Some(quick_add) = &mut app.quick_add_state else {
+        return;
+    };
+    match key.code {
+        KeyCode::Esc => {
+            app.quick_add_state = None;
+            app.mode = InteractionMode::Navigation;
+        }
+        KeyCode::Enter => {
+            let Ok(event) = quick_add.preview.clone() else {
+                return;
+            };
+            let event = Event {
+                calendar_id: app.calendars.iter().find(|c| c.visible).and_then(|c| c.id),
+                ..event
+            };
+            let description = format!("add '{}'", event.title);
+            let result = app.history.perform(&app.conn, description, &[], |conn| Ok(vec![create_event(conn, &event)?]));
+            app.event_cache.invalidate();
+            match result {
+                Ok(created) => {
+                    app.quick_add_state = None;
+                    app.mode = InteractionMode::Navigation;
+                    jump_to_event(app, &Event { id: created.first().copied(), ..event.clone() });
+                    *app.banner.borrow_mut() = Some(format!("Added '{}'", event.title));
+                }
+                Err(err) => {
+                    if let Some(quick_add) = &mut app.quick_add_state {
+                        quick_add.preview = Err(format!("Could not save event: {}", err));
                     }
                 }
             }
         }
+        KeyCode::Backspace | KeyCode::Char(_) => {
+            match key.code {
+                KeyCode::Char(c) => quick_add.input.push(c),
+                _ => {
+                    quick_add.input.pop();
+                }
+            }
+            quick_add.preview =
+                parse_quick_add(&quick_add.input, app.selected_date, app.timezone).map_err(|err| err.to_string());
+        }
         _ => {}
     }
 }
 
-fn handle_timeslot_input(key: KeyEvent, app: &mut App) {
+fn handle_tag_filter_input(key: KeyEvent, app: &mut App) {
+    let Some(filter) = &mut app.tag_filter_state else {
+        return;
+    };
     match key.code {
         KeyCode::Esc => {
+            app.tag_filter_state = None;
+            app.mode = InteractionMode::Navigation;
+        }
+        KeyCode::Enter => match filter.input.parse::<TagFilter>() {
+            Ok(tag_filter) => {
+                app.tag_filter = tag_filter;
+                app.tag_filter_state = None;
+                app.mode = InteractionMode::Navigation;
+                app.focus = None;
+                app.agenda_selected = 0;
+            }
+            Err(err) => filter.error = Some(err.to_string()),
+        },
+        KeyCode::Backspace | KeyCode::Char(_) => {
+            match key.code {
+                KeyCode::Char(c) => filter.input.push(c),
+                _ => {
+                    filter.input.pop();
+                }
+            }
+            filter.error = None;
+        }
+        _ => {}
+    }
+}
+
+/// Moves the selection to `event`; for a recurring series, to its next
+/// occurrence within a year when there is one.
+fn jump_to_event(app: &mut App, event: &Event) {
+    let mut start = event.start_datetime;
+    if event.recurrence.is_some() {
+        let now = Utc::now();
+        let upcoming = get_events_in_range(
+            &app.conn,
+            now.timestamp(),
+            (now + Duration::days(366)).timestamp(),
+            &TagFilter::default(),
+        )
+        .unwrap_or_default();
+        if let Some(occurrence) = upcoming.iter().find(|e| e.id == event.id && e.start_datetime >= now) {
+            start = occurrence.start_datetime;
+        }
+    }
+    let local = if event.all_day { start.naive_utc() } else { app.to_local(start) };
+    select_slot(app, local);
+    app.focus = event.id.map(|event_id| Focus { event_id, start });
+}
+
+/// Selects `date` for an agenda entry, and the event's start slot when it starts that day.
+fn select_event_slot(app: &mut App, date: NaiveDate, event: &Event) {
+    let local = app.to_local(event.start_datetime);
+    if !event.all_day && local.date() == date {
+        select_slot(app, local);
+    } else {
+        app.selected_date = date;
+    }
+}
+
+/// Selects the grid slot containing `local`.
+fn select_slot(app: &mut App, local: NaiveDateTime) {
+    app.selected_date = local.date();
+    app.selected_time = slot_start(local.time(), app.slot);
+}
+
+fn handle_recurrence_scope_input(key: KeyEvent, app: &mut App) {
+    if let Some(prompt) = &mut app.scope_prompt_state {
+        match app.keys.action(&key) {
+            Some(Action::Back) => {
+                app.mode = InteractionMode::Selection;
+                app.scope_prompt_state = None;
+            }
+            Some(Action::Up) => prompt.selected = prompt.selected.saturating_sub(1),
+            Some(Action::Down) => prompt.selected = (prompt.selected + 1).min(RecurrenceScope::ALL.len() - 1),
+            Some(Action::Select) => {
+                if let Some(prompt) = app.scope_prompt_state.take() {
+                    app.mode = InteractionMode::Selection;
+                    let scope = RecurrenceScope::ALL[prompt.selected];
+                    apply_scoped_action(app, prompt.action, prompt.event, Some(scope));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn apply_scoped_action(app: &mut App, action: ScopeAction, event: Event, scope: Option<RecurrenceScope>) {
+    match action {
+        ScopeAction::Edit => {
+            let time_pattern = app.config.ui.time_pattern();
+            let (start, end) = if event.all_day {
+                let start = event.start_datetime.naive_utc();
+                (start, last_day(start, event.end_datetime.naive_utc()))
+            } else {
+                (app.to_local(event.start_datetime), app.to_local(event.end_datetime))
+            };
+            app.mode = InteractionMode::EventForm;
+            app.event_form_state = Some(EventFormState {
+                title: TextArea::from(event.title.lines().map(|s| s.to_string())),
+                description: TextArea::from(
+                    event.description.as_deref().unwrap_or("").lines().map(|s| s.to_string()),
+                ),
+                location: TextArea::from(
+                    event.location.as_deref().unwrap_or("").lines().map(|s| s.to_string()),
+                ),
+                recurrence: TextArea::from(event.recurrence.iter().map(|r| r.to_string())),
+                reminders: TextArea::from([format_reminders(&event.reminders)]),
+                tags: list_field(format_tags(&event.tags)),
+                known_tags: get_tags(&app.conn).unwrap_or_default(),
+                attendees: list_field(format_attendees(&event.attendees)),
+                // All-day events are floating dates, shown the same in every zone.
+                start: time_field(start, event.all_day, time_pattern),
+                end: time_field(end, event.all_day, time_pattern),
+                all_day: event.all_day,
+                calendar_id: event.calendar_id,
+                timezone: event.timezone.clone(),
+                focused_field: FormField::Title,
+                recurrence_id: event.recurrence_id,
+                recurrence_scope: scope,
+                error: None,
+            });
+            app.selected_event_id = event.id;
+        }
+        ScopeAction::Delete => {
+            let description = format!("delete '{}'", event.title);
+            let touched: Vec<i64> = event.id.into_iter().collect();
+            let scope = scope.unwrap_or(RecurrenceScope::AllOccurrences);
+            let result = app.history.perform(&app.conn, description, &touched, |conn| {
+                delete_occurrences(conn, &event, scope).map(|_| Vec::new())
+            });
+            app.event_cache.invalidate();
+            match result {
+                Ok(_) => app.focus = None,
+                Err(err) => *app.banner.borrow_mut() = Some(format!("Delete failed: {}", err)),
+            }
+        }
+        ScopeAction::Move(offset) => {
+            let moved = Event {
+                start_datetime: event.start_datetime + offset,
+                end_datetime: event.end_datetime + offset,
+                ..event.clone()
+            };
+            let description = format!("move '{}'", event.title);
+            let touched: Vec<i64> = event.id.into_iter().collect();
+            let result = app.history.perform(&app.conn, description, &touched, |conn| -> Result<Vec<i64>> {
+                match (event.recurrence_id, scope) {
+                    (Some(recurrence_id), Some(scope)) => {
+                        Ok(update_occurrences(conn, &moved, recurrence_id, scope)?.into_iter().collect())
+                    }
+                    _ => update_event(conn, &moved).map(|_| Vec::new()),
+                }
+            });
+            app.event_cache.invalidate();
+            match result {
+                // A moved single occurrence becomes a new event.
+                Ok(created) => {
+                    app.focus = created.first().or(event.id.as_ref()).map(|&event_id| Focus {
+                        event_id,
+                        start: moved.start_datetime,
+                    });
+                    reveal_event(app, &moved);
+                }
+                Err(err) => *app.banner.borrow_mut() = Some(format!("Move failed: {}", err)),
+            }
+        }
+    }
+}
+
+fn handle_timeslot_input(key: KeyEvent, app: &mut App) {
+    match app.keys.action(&key) {
+        Some(Action::Back) => {
             app.mode = InteractionMode::Selection;
             app.selection_start = None;
         }
-        KeyCode::Up => {
-            app.selected_time = app.selected_time.overflowing_sub_signed(Duration::minutes(30)).0;
+        Some(Action::Up) => {
+            app.selected_time = app.selected_time.overflowing_sub_signed(app.slot).0;
         }
-        KeyCode::Down => {
-            app.selected_time = app.selected_time.overflowing_add_signed(Duration::minutes(30)).0;
+        Some(Action::Down) => {
+            app.selected_time = app.selected_time.overflowing_add_signed(app.slot).0;
         }
-        KeyCode::Enter => {
+        Some(Action::Select) => {
             if let Some(start_time) = app.selection_start {
                 app.mode = InteractionMode::EventForm;
                 let (start, end) = if start_time < app.selected_time {
@@ -136,14 +735,29 @@ fn handle_timeslot_input(key: KeyEvent, app: &mut App) {
                 } else {
                     (app.selected_time, start_time)
                 };
+                let time_pattern = app.config.ui.time_pattern();
+                let start = app.selected_date.and_time(start);
+                let end = app.selected_date.and_time(end) + app.slot;
                 app.event_form_state = Some(EventFormState {
                     title: TextArea::default(),
                     description: TextArea::default(),
                     location: TextArea::default(),
-                    start_datetime: app.selected_date.and_time(start),
-                    end_datetime: app.selected_date.and_time(end) + Duration::minutes(30),
-                    focused_field: 0,
+                    recurrence: TextArea::default(),
+                    reminders: TextArea::default(),
+                    tags: TextArea::default(),
+                    known_tags: get_tags(&app.conn).unwrap_or_default(),
+                    attendees: TextArea::default(),
+                    start: time_field(start, false, time_pattern),
+                    end: time_field(end, false, time_pattern),
+                    all_day: false,
+                    calendar_id: app.calendars.iter().find(|c| c.visible).and_then(|c| c.id),
+                    timezone: Some(app.timezone.name().to_string()),
+                    focused_field: FormField::Title,
+                    recurrence_id: None,
+                    recurrence_scope: None,
+                    error: None,
                 });
+                app.selected_event_id = None;
                 app.selection_start = None;
             }
         }
@@ -151,42 +765,207 @@ fn handle_timeslot_input(key: KeyEvent, app: &mut App) {
     }
 }
 
+/// A list field such as tags or attendees holding `value`, with the cursor
+/// after it since lists are mostly appended to.
+fn list_field<'a>(value: String) -> TextArea<'a> {
+    let mut field = TextArea::from([value]);
+    field.move_cursor(tui_textarea::CursorMove::End);
+    field
+}
+
+/// Last day of an all-day event from its exclusive end.
+fn last_day(start: NaiveDateTime, end: NaiveDateTime) -> NaiveDateTime {
+    if end.time() == NaiveTime::MIN && end.date() > start.date() {
+        end - Duration::days(1)
+    } else {
+        end
+    }
+}
+
 fn handle_event_form_input<'a>(key: KeyEvent, app: &mut App<'a>) {
+    let time_pattern = app.config.ui.time_pattern();
     if let Some(form_state) = &mut app.event_form_state {
+        let editing_time = matches!(form_state.focused_field, FormField::Start | FormField::End);
         match key.code {
             KeyCode::Esc => {
                 app.mode = InteractionMode::Navigation;
                 app.event_form_state = None;
             }
+            // In the tags field Tab first completes the tag being typed.
             KeyCode::Tab => {
-                form_state.focused_field = (form_state.focused_field + 1) % 3;
+                let completion = match form_state.focused_field {
+                    FormField::Tags => form_state.tag_suggestion().map(str::to_string),
+                    _ => None,
+                };
+                match completion {
+                    Some(rest) => {
+                        form_state.tags.insert_str(rest);
+                    }
+                    None => form_state.focused_field = form_state.focused_field.next(),
+                }
+            }
+            // Up/Down step by a slot (a day for all-day events), PageUp/PageDown by a day (a week).
+            KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown if editing_time => {
+                let step = match (key.code, form_state.all_day) {
+                    (KeyCode::Up | KeyCode::Down, false) => app.slot,
+                    (KeyCode::Up | KeyCode::Down, true) | (_, false) => Duration::days(1),
+                    (_, true) => Duration::weeks(1),
+                };
+                let delta = if matches!(key.code, KeyCode::Down | KeyCode::PageDown) {
+                    -step
+                } else {
+                    step
+                };
+                if let (Ok(start), Ok(end)) = form_state.times(time_pattern) {
+                    // Moving the start keeps the duration.
+                    match form_state.focused_field {
+                        FormField::Start => form_state.set_times(start + delta, end + delta, time_pattern),
+                        _ => form_state.set_times(start, end + delta, time_pattern),
+                    }
+                }
             }
             KeyCode::Enter => {
+                let (start_error, end_error) = form_state.time_errors(time_pattern);
+                if let Some(error) = start_error
+                    .map(|e| format!("Start: {}", e))
+                    .or(end_error.map(|e| format!("End: {}", e)))
+                {
+                    form_state.error = Some(error);
+                    return;
+                }
+                let (Ok(start_input), Ok(end_input)) = form_state.times(time_pattern) else {
+                    return;
+                };
+                let rule = form_state.recurrence.lines().join("");
+                let recurrence = if rule.trim().is_empty() {
+                    None
+                } else {
+                    match rule.parse::<Recurrence>() {
+                        Ok(recurrence) => Some(recurrence),
+                        Err(err) => {
+                            form_state.error = Some(format!("Repeat: {}", err));
+                            return;
+                        }
+                    }
+                };
+                let reminders = match parse_reminders(&form_state.reminders.lines().join(",")) {
+                    Ok(reminders) => reminders,
+                    Err(err) => {
+                        form_state.error = Some(format!("Reminders: {}", err));
+                        return;
+                    }
+                };
+                let attendees = match form_state.parsed_attendees() {
+                    Ok(attendees) => attendees,
+                    Err(err) => {
+                        form_state.error = Some(format!("Attendees: {}", err));
+                        return;
+                    }
+                };
+                let (start_datetime, end_datetime, timezone) = if form_state.all_day {
+                    let (start, end) = Event::all_day_bounds(start_input.date(), end_input.date());
+                    (start, end, None)
+                } else {
+                    let (Some(start), Some(end)) = (
+                        local_to_utc(app.timezone, start_input),
+                        local_to_utc(app.timezone, end_input),
+                    ) else {
+                        form_state.error = Some("Time falls in a daylight saving gap".to_string());
+                        return;
+                    };
+                    let timezone = form_state
+                        .timezone
+                        .clone()
+                        .or_else(|| Some(app.timezone.name().to_string()));
+                    (start, end, timezone)
+                };
                 let event = Event {
                     id: app.selected_event_id,
                     title: form_state.title.lines().join("\n"),
                     description: Some(form_state.description.lines().join("\n")),
-                    start_datetime: Utc.from_utc_datetime(&form_state.start_datetime),
-                    end_datetime: Utc.from_utc_datetime(&form_state.end_datetime),
+                    start_datetime,
+                    end_datetime,
+                    all_day: form_state.all_day,
+                    calendar_id: form_state.calendar_id,
+                    timezone,
                     location: Some(form_state.location.lines().join("\n")),
                     created_at: Utc::now(),
                     updated_at: Utc::now(),
+                    recurrence,
+                    reminders,
+                    tags: parse_tags(&form_state.tags.lines().join(" ")),
+                    attendees,
+                    ..Default::default()
                 };
-                if event.id.is_some() {
-                    let _ = update_event(&app.conn, &event);
-                } else {
-                    let _ = create_event(&app.conn, &event);
+                let recurrence = form_state.recurrence_id.zip(form_state.recurrence_scope);
+                let (description, touched): (String, Vec<i64>) = match event.id {
+                    Some(id) => (format!("edit '{}'", event.title), vec![id]),
+                    None => (format!("create '{}'", event.title), Vec::new()),
+                };
+                let result = app.history.perform(&app.conn, description, &touched, |conn| -> Result<Vec<i64>> {
+                    match (event.id, recurrence) {
+                        (Some(_), Some((recurrence_id, scope))) => {
+                            Ok(update_occurrences(conn, &event, recurrence_id, scope)?.into_iter().collect())
+                        }
+                        (Some(_), None) => update_event(conn, &event).map(|_| Vec::new()),
+                        (None, _) => Ok(vec![create_event(conn, &event)?]),
+                    }
+                });
+                app.event_cache.invalidate();
+                if let Err(err) = result {
+                    form_state.error = Some(err.to_string());
+                    return;
                 }
                 app.mode = InteractionMode::Navigation;
                 app.event_form_state = None;
             }
+            KeyCode::Char(' ') if form_state.focused_field == FormField::AllDay => {
+                let times = form_state.times(time_pattern);
+                form_state.all_day = !form_state.all_day;
+                if let (Ok(start), Ok(end)) = times {
+                    if form_state.all_day {
+                        let first = start.date().and_time(NaiveTime::MIN);
+                        form_state.set_times(
+                            first,
+                            last_day(first, end).date().and_time(NaiveTime::MIN),
+                            time_pattern,
+                        );
+                    } else {
+                        // Timed again: start at the selected time on the first day.
+                        let start = start.date().and_time(app.selected_time);
+                        form_state.set_times(start, start + app.slot, time_pattern);
+                    }
+                }
+            }
+            KeyCode::Left | KeyCode::Right
+                if form_state.focused_field == FormField::Calendar && !app.calendars.is_empty() =>
+            {
+                let count = app.calendars.len();
+                let current = app
+                    .calendars
+                    .iter()
+                    .position(|c| c.id == form_state.calendar_id)
+                    .unwrap_or(0);
+                let next = if key.code == KeyCode::Right {
+                    (current + 1) % count
+                } else {
+                    (current + count - 1) % count
+                };
+                form_state.calendar_id = app.calendars[next].id;
+            }
             _ => {
                 let key_event: tui_textarea::Input = key.into();
                 match form_state.focused_field {
-                    0 => form_state.title.input(key_event),
-                    1 => form_state.description.input(key_event),
-                    2 => form_state.location.input(key_event),
-                    _ => false,
+                    FormField::Title => form_state.title.input(key_event),
+                    FormField::Start => form_state.start.input(key_event),
+                    FormField::End => form_state.end.input(key_event),
+                    FormField::Description => form_state.description.input(key_event),
+                    FormField::Location => form_state.location.input(key_event),
+                    FormField::Tags => form_state.tags.input(key_event),
+                    FormField::Attendees => form_state.attendees.input(key_event),
+                    FormField::Repeat => form_state.recurrence.input(key_event),
+                    FormField::Reminders => form_state.reminders.input(key_event),
+                    FormField::AllDay | FormField::Calendar => false,
                 };
             }
         }
@@ -0,0 +1,10 @@
+pub mod app;
+pub mod caldav;
+pub mod cli;
+pub mod history;
+pub mod ics;
+pub mod input;
+pub mod models;
+pub mod reminders;
+pub mod storage;
+pub mod ui;
@@ -1,13 +1,12 @@
-mod app;
-mod input;
-mod models;
-mod storage;
-mod ui;
-
-use crate::app::App;
-use crate::models::config::load_config;
-use crate::storage::db::initialize_db;
+use calendar_app::app::App;
+use calendar_app::cli::{self, Cli};
+use calendar_app::models::config::load_config;
+use calendar_app::models::keys::Action;
+use calendar_app::reminders::{self, ReminderScheduler, SystemClock};
+use calendar_app::storage::db::initialize_db;
+use calendar_app::{input, ui};
 use anyhow::Result;
+use clap::Parser;
 use crossterm::{
     event::{self, Event},
     execute,
@@ -18,6 +17,14 @@ use std::io;
 use std::time::Duration;
 
 fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let config = load_config()?;
+    let conn = initialize_db()?;
+
+    if let Some(command) = cli.command {
+        return cli::run(command, cli.json, &config, &conn);
+    }
+
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -26,10 +33,15 @@ fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // create app and run it
-    let config = load_config()?;
-    let conn = initialize_db()?;
     let mut app = App::new(config, conn);
-    let res = run_app(&mut terminal, &mut app);
+    let notifiers = reminders::notifiers(
+        &app.config.reminders,
+        app.timezone,
+        app.config.ui.time_pattern(),
+        app.banner.clone(),
+    );
+    let mut scheduler = ReminderScheduler::new(SystemClock, app.timezone, notifiers);
+    let res = run_app(&mut terminal, &mut app, &mut scheduler);
 
     // restore terminal
     disable_raw_mode()?;
@@ -46,13 +58,19 @@ fn main() -> Result<()> {
 fn run_app<'a, B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App<'a>,
+    scheduler: &mut ReminderScheduler<SystemClock>,
 ) -> io::Result<()> {
     loop {
+        if let Err(err) = scheduler.tick(&app.conn) {
+            *app.banner.borrow_mut() = Some(format!("Reminder failed: {}", err));
+        }
+        // Another process (e.g. `calendar-app sync`) may have changed the database.
+        let _ = app.event_cache.refresh(&app.conn);
         terminal.draw(|f| ui::draw(f, app))?;
 
         if event::poll(Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
-                if key.code == crossterm::event::KeyCode::Char('q') {
+                if !app.is_typing() && !app.show_help && app.keys.is(&key, Action::Quit) {
                     return Ok(());
                 }
                 input::handle_input(key, app);
@@ -0,0 +1,297 @@
+use anyhow::{anyhow, bail, Result};
+use std::str::FromStr;
+
+/// Part a person plays in an event. iCalendar writes the organizer as an
+/// ORGANIZER property and the others as ATTENDEE roles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Role {
+    Organizer,
+    Chair,
+    #[default]
+    Required,
+    Optional,
+    /// Copied for information only (NON-PARTICIPANT).
+    Fyi,
+}
+
+impl Role {
+    pub const ALL: [Role; 5] = [Role::Organizer, Role::Chair, Role::Required, Role::Optional, Role::Fyi];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::Organizer => "organizer",
+            Role::Chair => "chair",
+            Role::Required => "required",
+            Role::Optional => "optional",
+            Role::Fyi => "fyi",
+        }
+    }
+
+    /// ROLE parameter of the ATTENDEE property.
+    pub fn ics_name(&self) -> &'static str {
+        match self {
+            Role::Organizer | Role::Chair => "CHAIR",
+            Role::Required => "REQ-PARTICIPANT",
+            Role::Optional => "OPT-PARTICIPANT",
+            Role::Fyi => "NON-PARTICIPANT",
+        }
+    }
+
+    /// Role for a ROLE parameter; unknown and missing ones are required (RFC 5545 §3.2.16).
+    pub fn from_ics(value: Option<&str>) -> Role {
+        match value.map(str::to_ascii_uppercase).as_deref() {
+            Some("CHAIR") => Role::Chair,
+            Some("OPT-PARTICIPANT") => Role::Optional,
+            Some("NON-PARTICIPANT") => Role::Fyi,
+            _ => Role::Required,
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Role::ALL
+            .into_iter()
+            .find(|role| role.name().eq_ignore_ascii_case(value))
+            .ok_or_else(|| anyhow!("Unknown role '{}'", value))
+    }
+}
+
+/// Whether an attendee has answered the invitation (PARTSTAT).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Status {
+    #[default]
+    NeedsAction,
+    Accepted,
+    Declined,
+    Tentative,
+    Delegated,
+}
+
+impl Status {
+    pub const ALL: [Status; 5] = [
+        Status::NeedsAction,
+        Status::Accepted,
+        Status::Declined,
+        Status::Tentative,
+        Status::Delegated,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::NeedsAction => "needs-action",
+            Status::Accepted => "accepted",
+            Status::Declined => "declined",
+            Status::Tentative => "tentative",
+            Status::Delegated => "delegated",
+        }
+    }
+
+    pub fn ics_name(&self) -> &'static str {
+        match self {
+            Status::NeedsAction => "NEEDS-ACTION",
+            Status::Accepted => "ACCEPTED",
+            Status::Declined => "DECLINED",
+            Status::Tentative => "TENTATIVE",
+            Status::Delegated => "DELEGATED",
+        }
+    }
+
+    /// Status for a PARTSTAT parameter; unknown and missing ones need action.
+    pub fn from_ics(value: Option<&str>) -> Status {
+        value
+            .and_then(|value| Status::ALL.into_iter().find(|s| s.ics_name().eq_ignore_ascii_case(value)))
+            .unwrap_or_default()
+    }
+
+    /// One-character marker for lists of attendees.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Status::NeedsAction => "·",
+            Status::Accepted => "✓",
+            Status::Declined => "✗",
+            Status::Tentative => "?",
+            Status::Delegated => "→",
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Status::ALL
+            .into_iter()
+            .find(|status| status.name().eq_ignore_ascii_case(value))
+            .ok_or_else(|| anyhow!("Unknown participation status '{}'", value))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attendee {
+    pub name: Option<String>,
+    pub email: String,
+    pub role: Role,
+    pub status: Status,
+}
+
+impl Attendee {
+    /// The name when known, the email address otherwise.
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.email)
+    }
+}
+
+/// Splits `value` on commas outside double quotes, so names such as
+/// `"Kowalski, Jan"` stay whole.
+fn split_entries(value: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                entries.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    entries.push(&value[start..]);
+    entries
+}
+
+/// Parses attendees separated by commas, each an email address with an
+/// optional name and role or status words, e.g.
+/// `Ana Lopez <ana@example.com> organizer accepted, bob@example.com optional`.
+/// Roles default to required and statuses to needs-action.
+pub fn parse_attendees(value: &str) -> Result<Vec<Attendee>> {
+    let mut attendees: Vec<Attendee> = Vec::new();
+    for part in split_entries(value).into_iter().map(str::trim).filter(|p| !p.is_empty()) {
+        let (name, email, words) = match (part.find('<'), part.find('>')) {
+            (Some(open), Some(close)) if open < close => {
+                let name = part[..open].trim().trim_matches('"').trim();
+                (
+                    (!name.is_empty()).then(|| name.to_string()),
+                    part[open + 1..close].trim().to_string(),
+                    part[close + 1..].split_whitespace().collect::<Vec<_>>(),
+                )
+            }
+            _ => {
+                let mut words: Vec<&str> = part.split_whitespace().collect();
+                let Some(index) = words.iter().position(|w| w.contains('@')) else {
+                    bail!("Attendee '{}' has no email address", part);
+                };
+                (None, words.remove(index).to_string(), words)
+            }
+        };
+        if !email.contains('@') || email.contains(char::is_whitespace) {
+            bail!("Invalid email address '{}'", email);
+        }
+        if name.as_deref().is_some_and(|name| name.contains('@')) {
+            bail!("Separate attendees with commas in '{}'", part);
+        }
+        let mut attendee = Attendee {
+            name,
+            email,
+            role: Role::default(),
+            status: Status::default(),
+        };
+        for word in words {
+            if let Ok(role) = word.parse() {
+                attendee.role = role;
+            } else if let Ok(status) = word.parse() {
+                attendee.status = status;
+            } else {
+                bail!("Unknown role or status '{}' for {}", word, attendee.email);
+            }
+        }
+        if attendees.iter().any(|a| a.email.eq_ignore_ascii_case(&attendee.email)) {
+            bail!("{} is listed twice", attendee.email);
+        }
+        attendees.push(attendee);
+    }
+    if attendees.iter().filter(|a| a.role == Role::Organizer).count() > 1 {
+        bail!("Only one attendee can be the organizer");
+    }
+    Ok(attendees)
+}
+
+/// Formats an attendee the way `parse_attendees` reads it, leaving out the
+/// default role and status.
+pub fn format_attendee(attendee: &Attendee) -> String {
+    let mut text = match &attendee.name {
+        Some(name) if name.contains(',') => format!("\"{}\" <{}>", name, attendee.email),
+        Some(name) => format!("{} <{}>", name, attendee.email),
+        None => attendee.email.clone(),
+    };
+    if attendee.role != Role::default() {
+        text.push(' ');
+        text.push_str(attendee.role.name());
+    }
+    if attendee.status != Status::default() {
+        text.push(' ');
+        text.push_str(attendee.status.name());
+    }
+    text
+}
+
+pub fn format_attendees(attendees: &[Attendee]) -> String {
+    attendees.iter().map(format_attendee).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attendee(name: Option<&str>, email: &str, role: Role, status: Status) -> Attendee {
+        Attendee {
+            name: name.map(str::to_string),
+            email: email.to_string(),
+            role,
+            status,
+        }
+    }
+
+    #[test]
+    fn parses_names_roles_and_statuses() {
+        let attendees =
+            parse_attendees("Ana Lopez <ana@example.com> organizer accepted, bob@example.com optional, \
+                             \"Kowalski, Jan\" <jan@example.com> Declined,")
+                .unwrap();
+        assert_eq!(
+            attendees,
+            [
+                attendee(Some("Ana Lopez"), "ana@example.com", Role::Organizer, Status::Accepted),
+                attendee(None, "bob@example.com", Role::Optional, Status::NeedsAction),
+                attendee(Some("Kowalski, Jan"), "jan@example.com", Role::Required, Status::Declined),
+            ]
+        );
+        assert!(parse_attendees("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_attendees() {
+        for invalid in [
+            "Ana Lopez",
+            "Ana <ana@example.com> boss",
+            "ana@example.com, ANA@example.com",
+            "Ana <ana@example.com> organizer, Bob <bob@example.com> organizer",
+            "ana@example.com bob@example.com",
+        ] {
+            assert!(parse_attendees(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn formats_what_it_parses() {
+        let text = "Ana Lopez <ana@example.com> organizer accepted, bob@example.com, \
+                    \"Kowalski, Jan\" <jan@example.com> fyi tentative";
+        let attendees = parse_attendees(text).unwrap();
+        assert_eq!(format_attendees(&attendees), text);
+        assert_eq!(parse_attendees(&format_attendees(&attendees)).unwrap(), attendees);
+    }
+}
@@ -0,0 +1,11 @@
+/// A named group of events, e.g. Work or Personal.
+#[derive(Clone)]
+pub struct Calendar {
+    pub id: Option<i64>,
+    pub name: String,
+    /// Color used for the calendar's events: a name ("cyan"), "#rrggbb" or a
+    /// 0-255 index. Without one, events take the theme's event color.
+    pub color: Option<String>,
+    /// Hidden calendars' events are left out of the calendar views.
+    pub visible: bool,
+}
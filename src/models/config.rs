@@ -1,5 +1,11 @@
-use anyhow::{Context, Result};
+use crate::models::keys::{KeyBindings, KeySpec};
+use crate::models::theme::{StyleSpec, Theme};
+use crate::models::timezone::resolve_timezone;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Duration, Weekday};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -9,6 +15,14 @@ pub struct Config {
     pub ui: UiConfig,
     #[serde(default = "default_calendar")]
     pub calendar: CalendarConfig,
+    #[serde(default = "default_reminders")]
+    pub reminders: ReminderConfig,
+    #[serde(default)]
+    pub caldav: Option<CalDavConfig>,
+    #[serde(default = "default_keys")]
+    pub keys: KeysConfig,
+    #[serde(default = "default_theme")]
+    pub theme: ThemeConfig,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -23,6 +37,90 @@ pub struct CalendarConfig {
     pub visible_hours_start: String,
     pub visible_hours_end: String,
     pub slot_interval_minutes: u32,
+    /// IANA zone used for display and entry, e.g. "Europe/Warsaw". Defaults to the system zone.
+    pub timezone: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReminderConfig {
+    /// Ring the terminal bell when a reminder fires; the TUI banner is always shown.
+    #[serde(default = "default_bell")]
+    pub bell: bool,
+    /// Shell command run for every reminder, e.g. `notify-send "$CALENDAR_EVENT_TITLE"`.
+    /// Event fields are passed as `CALENDAR_*` environment variables.
+    pub command: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CalDavConfig {
+    /// Account URL: the server root, a principal or a calendar home.
+    pub url: String,
+    pub username: Option<String>,
+    /// Falls back to the CALDAV_PASSWORD environment variable.
+    pub password: Option<String>,
+    /// How far back and ahead of today events are pulled.
+    #[serde(default = "default_past_days")]
+    pub past_days: i64,
+    #[serde(default = "default_future_days")]
+    pub future_days: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KeysConfig {
+    /// "default", or "vim" to also move with h/j/k/l.
+    #[serde(default = "default_preset")]
+    pub preset: String,
+    /// Keys per action, replacing the preset's, e.g. `delete = "x"` or
+    /// `redo = ["ctrl-r", "U"]`.
+    #[serde(flatten)]
+    pub bindings: BTreeMap<String, KeySpec>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Built-in theme: "default", "light" or "high-contrast".
+    #[serde(default = "default_preset")]
+    pub preset: String,
+    /// Styles per slot, replacing the preset's, e.g.
+    /// `selection = { fg = "black", bg = "#ffd75f", modifiers = ["bold"] }`.
+    #[serde(flatten)]
+    pub slots: BTreeMap<String, StyleSpec>,
+}
+
+impl UiConfig {
+    /// strftime pattern for times of day: "%I:%M %p" when `time_format` is "12h".
+    pub fn time_pattern(&self) -> &'static str {
+        if self.time_format == "12h" {
+            "%I:%M %p"
+        } else {
+            "%H:%M"
+        }
+    }
+
+    /// First day of the week from `week_start_day`, e.g. "sunday" or "sat".
+    pub fn week_start(&self) -> Result<Weekday> {
+        self.week_start_day.parse().map_err(|_| {
+            anyhow!("Unknown week_start_day '{}', expected a weekday such as \"monday\"", self.week_start_day)
+        })
+    }
+}
+
+impl CalendarConfig {
+    /// Length of a day or week grid slot; `slot_interval_minutes` must divide an hour.
+    pub fn slot_interval(&self) -> Result<Duration> {
+        match self.slot_interval_minutes {
+            minutes if minutes > 0 && 60 % minutes == 0 => Ok(Duration::minutes(minutes as i64)),
+            minutes => bail!("slot_interval_minutes must divide 60 (e.g. 5, 10, 15, 30 or 60), got {}", minutes),
+        }
+    }
+}
+
+fn default_past_days() -> i64 {
+    30
+}
+
+fn default_future_days() -> i64 {
+    365
 }
 
 fn default_ui() -> UiConfig {
@@ -38,6 +136,36 @@ fn default_calendar() -> CalendarConfig {
         visible_hours_start: "06:00".to_string(),
         visible_hours_end: "18:00".to_string(),
         slot_interval_minutes: 30,
+        timezone: None,
+    }
+}
+
+fn default_preset() -> String {
+    "default".to_string()
+}
+
+fn default_keys() -> KeysConfig {
+    KeysConfig {
+        preset: default_preset(),
+        bindings: BTreeMap::new(),
+    }
+}
+
+fn default_theme() -> ThemeConfig {
+    ThemeConfig {
+        preset: default_preset(),
+        slots: BTreeMap::new(),
+    }
+}
+
+fn default_bell() -> bool {
+    true
+}
+
+fn default_reminders() -> ReminderConfig {
+    ReminderConfig {
+        bell: default_bell(),
+        command: None,
     }
 }
 
@@ -46,10 +174,28 @@ impl Default for Config {
         Self {
             ui: default_ui(),
             calendar: default_calendar(),
+            reminders: default_reminders(),
+            caldav: None,
+            keys: default_keys(),
+            theme: default_theme(),
         }
     }
 }
 
+impl Config {
+    pub fn timezone(&self) -> Tz {
+        resolve_timezone(self.calendar.timezone.as_deref())
+    }
+
+    pub fn key_bindings(&self) -> Result<KeyBindings> {
+        KeyBindings::new(&self.keys.preset, &self.keys.bindings)
+    }
+
+    pub fn theme(&self) -> Result<Theme> {
+        Theme::new(&self.theme.preset, &self.theme.slots)
+    }
+}
+
 fn get_config_path() -> Result<PathBuf> {
     let config_dir = directories::ProjectDirs::from("com", "calendar-app", "calendar-app")
         .context("Failed to get config directory")?
@@ -63,7 +209,11 @@ pub fn load_config() -> Result<Config> {
     let config_path = get_config_path()?;
     if config_path.exists() {
         let config_str = fs::read_to_string(config_path)?;
-        let config = toml::from_str(&config_str)?;
+        let config: Config = toml::from_str(&config_str)?;
+        config.key_bindings().context("Invalid [keys] section in the config file")?;
+        config.theme().context("Invalid [theme] section in the config file")?;
+        config.ui.week_start().context("Invalid [ui] section in the config file")?;
+        config.calendar.slot_interval().context("Invalid [calendar] section in the config file")?;
         Ok(config)
     } else {
         Ok(Config::default())
@@ -1,12 +1,82 @@
-use chrono::{DateTime, Utc};
+use crate::models::attendee::Attendee;
+use crate::models::recurrence::Recurrence;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
 
+#[derive(Clone, Default)]
 pub struct Event {
     pub id: Option<i64>,
+    /// iCalendar UID, used to deduplicate imports and keep exports stable.
+    pub uid: Option<String>,
+    /// Calendar the event belongs to; `None` files it under the default calendar.
+    pub calendar_id: Option<i64>,
     pub title: String,
     pub description: Option<String>,
     pub start_datetime: DateTime<Utc>,
     pub end_datetime: DateTime<Utc>,
+    /// All-day events span whole dates: they are stored as UTC midnights
+    /// (end exclusive) and shown on the same dates in every timezone.
+    pub all_day: bool,
+    /// IANA zone the event was scheduled in; recurrences keep its wall-clock time.
+    pub timezone: Option<String>,
     pub location: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub recurrence: Option<Recurrence>,
+    pub exception_dates: Vec<DateTime<Utc>>,
+    /// Minutes before the start at which to remind, ascending.
+    pub reminders: Vec<i64>,
+    /// Lowercase labels, sorted, as `tags::parse_tags` returns them.
+    pub tags: Vec<String>,
+    /// People invited, in the order they were entered.
+    pub attendees: Vec<Attendee>,
+    /// Original start of this occurrence when the event was expanded from a
+    /// recurring series; `None` for the series itself and one-off events.
+    pub recurrence_id: Option<DateTime<Utc>>,
+}
+
+impl Event {
+    /// Stored start and end of an all-day event covering `first..=last`.
+    pub fn all_day_bounds(first: NaiveDate, last: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        let midnight = |date: NaiveDate| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        (midnight(first), midnight(last.max(first) + Duration::days(1)))
+    }
+
+    pub fn tz(&self) -> Tz {
+        self.timezone
+            .as_deref()
+            .and_then(|name| name.parse().ok())
+            .unwrap_or(Tz::UTC)
+    }
+
+    /// First and last local date the event covers, inclusive.
+    pub fn date_span(&self, tz: Tz) -> (NaiveDate, NaiveDate) {
+        let (start, end) = if self.all_day {
+            (self.start_datetime.naive_utc(), self.end_datetime.naive_utc())
+        } else {
+            (
+                self.start_datetime.with_timezone(&tz).naive_local(),
+                self.end_datetime.with_timezone(&tz).naive_local(),
+            )
+        };
+        // The end is exclusive, so an event ending at midnight stops the day before.
+        let last = if end > start { (end - Duration::seconds(1)).date() } else { start.date() };
+        (start.date(), last)
+    }
+
+    pub fn covers_date(&self, tz: Tz, date: NaiveDate) -> bool {
+        let (first, last) = self.date_span(tz);
+        first <= date && date <= last
+    }
+
+    /// Whether the event belongs in the all-day banner rather than the time grid.
+    pub fn is_multi_day(&self, tz: Tz) -> bool {
+        let (first, last) = self.date_span(tz);
+        self.all_day || first != last
+    }
+
+    /// Series start in the event's own zone, as recurrence rules expect it.
+    pub fn local_start(&self) -> DateTime<Tz> {
+        self.start_datetime.with_timezone(&self.tz())
+    }
 }
@@ -0,0 +1,415 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Commands that can be bound to keys in the `[keys]` config section. Text
+/// fields and the event form keep their fixed editing keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Help,
+    NextView,
+    Left,
+    Right,
+    Up,
+    Down,
+    Select,
+    Back,
+    Search,
+    QuickAdd,
+    Filter,
+    Calendars,
+    Undo,
+    Redo,
+    NextEvent,
+    PreviousEvent,
+    CycleSlot,
+    Details,
+    Edit,
+    Delete,
+    Move,
+    Duplicate,
+}
+
+impl Action {
+    pub const ALL: [Action; 23] = [
+        Action::Quit,
+        Action::Help,
+        Action::NextView,
+        Action::Left,
+        Action::Right,
+        Action::Up,
+        Action::Down,
+        Action::Select,
+        Action::Back,
+        Action::Search,
+        Action::QuickAdd,
+        Action::Filter,
+        Action::Calendars,
+        Action::Undo,
+        Action::Redo,
+        Action::NextEvent,
+        Action::PreviousEvent,
+        Action::CycleSlot,
+        Action::Details,
+        Action::Edit,
+        Action::Delete,
+        Action::Move,
+        Action::Duplicate,
+    ];
+
+    /// Name used in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::NextView => "next_view",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Select => "select",
+            Action::Back => "back",
+            Action::Search => "search",
+            Action::QuickAdd => "quick_add",
+            Action::Filter => "filter",
+            Action::Calendars => "calendars",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::NextEvent => "next_event",
+            Action::PreviousEvent => "previous_event",
+            Action::CycleSlot => "cycle_slot",
+            Action::Details => "details",
+            Action::Edit => "edit",
+            Action::Delete => "delete",
+            Action::Move => "move",
+            Action::Duplicate => "duplicate",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Help => "Show this help",
+            Action::NextView => "Switch view (year, month, week, day, agenda)",
+            Action::Left => "Previous day or month; move event a day earlier",
+            Action::Right => "Next day or month; move event a day later",
+            Action::Up => "Previous week, slot or entry",
+            Action::Down => "Next week, slot or entry",
+            Action::Select => "Select, open or confirm",
+            Action::Back => "Go back or cancel",
+            Action::Search => "Search events",
+            Action::QuickAdd => "Quick add an event from a phrase",
+            Action::Filter => "Filter events by tags",
+            Action::Calendars => "Show or hide calendars",
+            Action::Undo => "Undo the last change",
+            Action::Redo => "Redo the last undone change",
+            Action::NextEvent => "Focus the next event",
+            Action::PreviousEvent => "Focus the previous event",
+            Action::CycleSlot => "Cycle through events in the selected slot",
+            Action::Details => "Toggle the details of the focused event",
+            Action::Edit => "Edit the focused event",
+            Action::Delete => "Delete the focused event",
+            Action::Move => "Move the focused event",
+            Action::Duplicate => "Duplicate the focused event",
+        }
+    }
+
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Help => &["?"],
+            Action::NextView => &["tab"],
+            Action::Left => &["left"],
+            Action::Right => &["right"],
+            Action::Up => &["up"],
+            Action::Down => &["down"],
+            Action::Select => &["enter"],
+            Action::Back => &["esc"],
+            Action::Search => &["/"],
+            Action::QuickAdd => &["a"],
+            Action::Filter => &["f"],
+            Action::Calendars => &["c"],
+            Action::Undo => &["u"],
+            Action::Redo => &["ctrl-r"],
+            Action::NextEvent => &["n"],
+            Action::PreviousEvent => &["p"],
+            Action::CycleSlot => &["o"],
+            Action::Details => &["i"],
+            Action::Edit => &["e"],
+            Action::Delete => &["d"],
+            Action::Move => &["m"],
+            Action::Duplicate => &["y"],
+        }
+    }
+
+    /// Keys the vim preset adds on top of the defaults.
+    fn vim_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Left => &["h"],
+            Action::Down => &["j"],
+            Action::Up => &["k"],
+            Action::Right => &["l"],
+            _ => &[],
+        }
+    }
+}
+
+/// A key with modifiers, written like "x", "G", "ctrl-r", "alt-left" or "f1".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// The chord as terminals report it: shift is part of the character for
+    /// printable keys ("shift-g" is "G") and shift-tab is backtab.
+    fn normalized(self) -> KeyChord {
+        let shift = self.modifiers.contains(KeyModifiers::SHIFT);
+        let code = match self.code {
+            KeyCode::Char(c) if shift => {
+                let mut upper = c.to_uppercase();
+                KeyCode::Char(match (upper.next(), upper.next()) {
+                    (Some(upper), None) => upper,
+                    _ => c,
+                })
+            }
+            KeyCode::Tab if shift => KeyCode::BackTab,
+            code => code,
+        };
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => self.modifiers - KeyModifiers::SHIFT,
+            _ => self.modifiers,
+        };
+        KeyChord { code, modifiers }
+    }
+
+    /// Whether `key` is this chord.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        *self == KeyChord { code: key.code, modifiers: key.modifiers }.normalized()
+    }
+}
+
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("space", KeyCode::Char(' ')),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+];
+
+impl FromStr for KeyChord {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = value;
+        // A trailing "-" is the key itself, as in "ctrl--".
+        while let Some((modifier, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "c" => KeyModifiers::CONTROL,
+                "alt" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => bail!("Unknown modifier '{}' in key '{}'", modifier, value),
+            };
+            rest = key;
+        }
+        let lower = rest.to_ascii_lowercase();
+        let code = if let Some((_, code)) = KEY_NAMES.iter().find(|(name, _)| *name == lower) {
+            *code
+        } else if let Some(number) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            KeyCode::F(number)
+        } else {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return Err(anyhow!("Unknown key '{}'", value)),
+            }
+        };
+        Ok(KeyChord { code, modifiers }.normalized())
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl-"),
+            (KeyModifiers::ALT, "alt-"),
+            (KeyModifiers::SHIFT, "shift-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}", name)?;
+            }
+        }
+        match (KEY_NAMES.iter().find(|(_, code)| *code == self.code), self.code) {
+            (Some((name, _)), _) => write!(f, "{}", name),
+            (None, KeyCode::F(number)) => write!(f, "f{}", number),
+            (None, KeyCode::Char(c)) => write!(f, "{}", c),
+            (None, code) => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// One key or a list of keys for an action in the config file.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeySpec {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeySpec::One(key) => vec![key],
+            KeySpec::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// The effective keys of every action.
+pub struct KeyBindings {
+    bindings: Vec<(Action, Vec<KeyChord>)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings::new("default", &BTreeMap::new()).unwrap_or(KeyBindings { bindings: Vec::new() })
+    }
+}
+
+impl KeyBindings {
+    /// Starts from `preset` ("default" or "vim") and replaces the keys of the
+    /// actions named in `overrides`. Fails on unknown actions or keys, and
+    /// when one key ends up bound to two actions.
+    pub fn new(preset: &str, overrides: &BTreeMap<String, KeySpec>) -> Result<KeyBindings> {
+        let vim = match preset {
+            "default" => false,
+            "vim" => true,
+            _ => bail!("Unknown key preset '{}', expected \"default\" or \"vim\"", preset),
+        };
+        if let Some(name) = overrides.keys().find(|name| !Action::ALL.iter().any(|a| a.name() == name.as_str())) {
+            bail!("Unknown action '{}' in [keys]", name);
+        }
+
+        let mut bindings = Vec::new();
+        for action in Action::ALL {
+            let keys = match overrides.get(action.name()) {
+                Some(spec) => spec.keys(),
+                None if vim => action.vim_keys().iter().chain(action.default_keys()).copied().collect(),
+                None => action.default_keys().to_vec(),
+            };
+            let chords = keys.into_iter().map(KeyChord::from_str).collect::<Result<Vec<_>>>()?;
+            bindings.push((action, chords));
+        }
+
+        let mut conflicts = Vec::new();
+        for (i, (action, chords)) in bindings.iter().enumerate() {
+            for (other, other_chords) in &bindings[i + 1..] {
+                for chord in chords.iter().filter(|c| other_chords.contains(c)) {
+                    conflicts.push(format!("'{}' is bound to both {} and {}", chord, action.name(), other.name()));
+                }
+            }
+        }
+        if !conflicts.is_empty() {
+            bail!("Conflicting key bindings: {}", conflicts.join("; "));
+        }
+        Ok(KeyBindings { bindings })
+    }
+
+    /// The action bound to `key`, if any.
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, chords)| chords.iter().any(|chord| chord.matches(key)))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn is(&self, key: &KeyEvent, action: Action) -> bool {
+        self.action(key) == Some(action)
+    }
+
+    /// Keys of `action` for hints and the help overlay, e.g. "h/left".
+    pub fn label(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, chords)| chords.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("/"))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(value: &str) -> KeyChord {
+        value.parse().unwrap()
+    }
+
+    fn bind(overrides: &[(&str, &str)]) -> Result<KeyBindings> {
+        let overrides = overrides
+            .iter()
+            .map(|(action, key)| (action.to_string(), KeySpec::One(key.to_string())))
+            .collect();
+        KeyBindings::new("default", &overrides)
+    }
+
+    #[test]
+    fn parses_keys_and_modifiers() {
+        assert_eq!(chord("x"), KeyChord { code: KeyCode::Char('x'), modifiers: KeyModifiers::NONE });
+        assert_eq!(chord("ctrl-r"), KeyChord { code: KeyCode::Char('r'), modifiers: KeyModifiers::CONTROL });
+        assert_eq!(chord("Alt-Left"), KeyChord { code: KeyCode::Left, modifiers: KeyModifiers::ALT });
+        assert_eq!(
+            chord("c-m-f1"),
+            KeyChord { code: KeyCode::F(1), modifiers: KeyModifiers::CONTROL | KeyModifiers::ALT }
+        );
+        assert_eq!(chord("ctrl--"), KeyChord { code: KeyCode::Char('-'), modifiers: KeyModifiers::CONTROL });
+        assert_eq!(chord("space"), KeyChord { code: KeyCode::Char(' '), modifiers: KeyModifiers::NONE });
+        assert!("hyper-x".parse::<KeyChord>().is_err());
+        assert!("xy".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn folds_shift_into_characters() {
+        assert_eq!(chord("shift-g"), chord("G"));
+        assert_eq!(chord("ctrl-shift-g"), KeyChord { code: KeyCode::Char('G'), modifiers: KeyModifiers::CONTROL });
+        assert_eq!(chord("shift-tab"), chord("backtab"));
+        assert_eq!(chord("shift-left").modifiers, KeyModifiers::SHIFT);
+        assert_eq!(chord("shift-g").to_string(), "G");
+    }
+
+    #[test]
+    fn matches_keys_as_terminals_send_them() {
+        assert!(chord("shift-g").matches(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+        assert!(chord("G").matches(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::NONE)));
+        assert!(chord("backtab").matches(&KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)));
+        assert!(!chord("g").matches(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+        assert!(!chord("left").matches(&KeyEvent::new(KeyCode::Left, KeyModifiers::SHIFT)));
+    }
+
+    #[test]
+    fn reports_keys_bound_twice() {
+        assert!(KeyBindings::new("default", &BTreeMap::new()).is_ok());
+        assert!(KeyBindings::new("vim", &BTreeMap::new()).is_ok());
+        assert!(bind(&[("undo", "shift-x"), ("redo", "X")]).is_err());
+        assert!(bind(&[("help", "shift-tab"), ("redo", "backtab")]).is_err());
+        assert!(bind(&[("delete", "q")]).is_err());
+        assert!(bind(&[("undo", "x"), ("redo", "shift-x")]).is_ok());
+    }
+}
@@ -1,2 +1,12 @@
+pub mod attendee;
+pub mod calendar;
 pub mod config;
 pub mod event;
+pub mod keys;
+pub mod quick_add;
+pub mod recurrence;
+pub mod reminder;
+pub mod tags;
+pub mod theme;
+pub mod time_input;
+pub mod timezone;
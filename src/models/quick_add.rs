@@ -0,0 +1,576 @@
+use crate::models::{
+    event::Event,
+    recurrence::{ByDay, Frequency, Recurrence},
+    timezone::{local_to_utc, local_to_utc_lenient},
+};
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, Duration, Month, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use chrono_tz::Tz;
+
+/// Length of a timed event when the phrase gives neither an end nor a duration.
+const DEFAULT_DURATION_MINUTES: i64 = 60;
+
+/// What a phrase says, before it is turned into an `Event`.
+#[derive(Default)]
+struct Phrase {
+    title: Vec<String>,
+    date: Option<NaiveDate>,
+    start: Option<NaiveTime>,
+    end: Option<NaiveTime>,
+    duration: Option<Duration>,
+    location: Option<String>,
+    recurrence: Option<Recurrence>,
+    until: Option<NaiveDate>,
+    all_day: bool,
+}
+
+/// Parses a phrase such as "Lunch with Ana tomorrow 12:30-13:30 at Cafe Nero"
+/// or "standup every weekday 9am for 15m" into an unsaved event. Relative
+/// dates count from `base`, times are wall-clock times in `tz`, and phrases
+/// without a time become all-day events. Words that are not understood make
+/// up the title.
+pub fn parse_quick_add(input: &str, base: NaiveDate, tz: Tz) -> Result<Event> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let mut phrase = Phrase::default();
+    let mut i = 0;
+    while i < words.len() {
+        match parse_part(&words[i..], base, &mut phrase)? {
+            0 => {
+                phrase.title.push(words[i].to_string());
+                i += 1;
+            }
+            consumed => i += consumed,
+        }
+    }
+    build_event(phrase, base, tz)
+}
+
+/// One-line description of a parsed event, for previews.
+pub fn describe(event: &Event, tz: Tz, time_pattern: &str) -> String {
+    let (first, last) = event.date_span(tz);
+    let mut text = first.format("%a %Y-%m-%d").to_string();
+    if event.all_day {
+        if last != first {
+            text.push_str(&format!(" - {}", last.format("%a %Y-%m-%d")));
+        }
+        text.push_str(", all day");
+    } else {
+        let start = event.start_datetime.with_timezone(&tz);
+        let end = event.end_datetime.with_timezone(&tz);
+        text.push_str(&format!(" {}-{}", start.format(time_pattern), end.format(time_pattern)));
+        if last != first {
+            text.push_str(&format!(" ({})", last.format("%a %Y-%m-%d")));
+        }
+    }
+    if let Some(location) = &event.location {
+        text.push_str(&format!(" @ {}", location));
+    }
+    if let Some(recurrence) = &event.recurrence {
+        text.push_str(&format!(", repeats {}", recurrence));
+    }
+    text
+}
+
+fn build_event(phrase: Phrase, base: NaiveDate, tz: Tz) -> Result<Event> {
+    let title = phrase.title.join(" ");
+    if title.is_empty() {
+        bail!("Missing a title");
+    }
+    let mut recurrence = phrase.recurrence;
+    // "every monday" starts on the next Monday unless a date says otherwise.
+    let date = phrase.date.unwrap_or_else(|| {
+        let by_day = recurrence.as_ref().map(|r| r.by_day.as_slice()).unwrap_or_default();
+        (0..7)
+            .map(|days| base + Duration::days(days))
+            .find(|date| by_day.iter().any(|d| d.weekday == date.weekday()))
+            .unwrap_or(base)
+    });
+    if let (Some(recurrence), Some(until)) = (&mut recurrence, phrase.until) {
+        let end_of_day = until.and_hms_opt(23, 59, 59).unwrap_or_default();
+        recurrence.until = Some(local_to_utc_lenient(tz, end_of_day));
+    }
+
+    let too_long = || anyhow!("The event is too long");
+    let (start_datetime, end_datetime, all_day) = match phrase.start.filter(|_| !phrase.all_day) {
+        None => {
+            let days = phrase.duration.map_or(1, |d| d.num_days().max(1));
+            let last = Duration::try_days(days - 1).and_then(|d| date.checked_add_signed(d)).ok_or_else(too_long)?;
+            let (start, end) = Event::all_day_bounds(date, last);
+            (start, end, true)
+        }
+        Some(start) => {
+            let start = date.and_time(start);
+            let end = match (phrase.end, phrase.duration) {
+                // An end before the start, as in "22:00-01:00", is on the next day.
+                (Some(end), _) if date.and_time(end) <= start => date.and_time(end) + Duration::days(1),
+                (Some(end), _) => date.and_time(end),
+                (None, Some(duration)) => start.checked_add_signed(duration).ok_or_else(too_long)?,
+                (None, None) => start + Duration::minutes(DEFAULT_DURATION_MINUTES),
+            };
+            let utc = |naive: NaiveDateTime| {
+                local_to_utc(tz, naive).ok_or_else(|| anyhow!("{} does not exist in {} (daylight saving gap)", naive, tz))
+            };
+            (utc(start)?, utc(end)?, false)
+        }
+    };
+    Ok(Event {
+        title,
+        location: phrase.location,
+        start_datetime,
+        end_datetime,
+        all_day,
+        timezone: (!all_day).then(|| tz.name().to_string()),
+        recurrence,
+        ..Default::default()
+    })
+}
+
+/// Word compared against keywords: lowercase, without trailing punctuation.
+fn key(word: &str) -> String {
+    word.trim_end_matches([',', '.', ';', '!']).to_lowercase()
+}
+
+/// Recognises a date, time, duration, location or recurrence at the start of
+/// `words` and records it in `phrase`. Returns the number of words used, 0 when
+/// the first word belongs to the title, and an error for dates and durations
+/// out of range.
+fn parse_part(words: &[&str], base: NaiveDate, phrase: &mut Phrase) -> Result<usize> {
+    let first = key(words[0]);
+    let rest = &words[1..];
+    match first.as_str() {
+        "every" => {
+            if let Some((recurrence, consumed)) = parse_every(rest) {
+                phrase.recurrence = Some(recurrence);
+                return Ok(1 + consumed);
+            }
+        }
+        "daily" | "weekly" | "monthly" | "yearly" | "annually" => {
+            let frequency = match first.as_str() {
+                "daily" => Frequency::Daily,
+                "weekly" => Frequency::Weekly,
+                "monthly" => Frequency::Monthly,
+                _ => Frequency::Yearly,
+            };
+            phrase.recurrence = Some(recurrence(frequency, 1, Vec::new()));
+            return Ok(1);
+        }
+        "until" | "till" if phrase.recurrence.is_some() => {
+            if let Some((date, consumed)) = parse_date(rest, base)? {
+                phrase.until = Some(date);
+                return Ok(1 + consumed);
+            }
+        }
+        "all-day" | "allday" => {
+            phrase.all_day = true;
+            return Ok(1);
+        }
+        "all" if rest.first().is_some_and(|w| key(w) == "day") => {
+            phrase.all_day = true;
+            return Ok(2);
+        }
+        "on" => {
+            if let Some((date, consumed)) = parse_date(rest, base)? {
+                phrase.date = Some(date);
+                return Ok(1 + consumed);
+            }
+        }
+        "for" => {
+            if let Some((duration, consumed)) = parse_duration(rest)? {
+                phrase.duration = Some(duration);
+                return Ok(1 + consumed);
+            }
+        }
+        "at" | "from" | "@" => {
+            if let Some((start, end, consumed)) = parse_times(rest, true) {
+                phrase.start = Some(start);
+                phrase.end = end.or(phrase.end);
+                return Ok(1 + consumed);
+            }
+            if first != "from" {
+                // A location runs until the next part that is understood.
+                let length = (0..rest.len())
+                    .find(|&i| !matches!(parse_part(&rest[i..], base, &mut Phrase::default()), Ok(0)))
+                    .unwrap_or(rest.len());
+                if length > 0 {
+                    phrase.location = Some(rest[..length].join(" "));
+                    return Ok(1 + length);
+                }
+            }
+        }
+        "to" | "until" | "till" | "-" if phrase.start.is_some() => {
+            if let Some((end, _, consumed)) = parse_times(rest, true) {
+                phrase.end = Some(end);
+                return Ok(1 + consumed);
+            }
+        }
+        _ => {}
+    }
+    if let Some((date, consumed)) = parse_date(words, base)? {
+        phrase.date = Some(date);
+        return Ok(consumed);
+    }
+    if let Some((start, end, consumed)) = parse_times(words, false) {
+        phrase.start = Some(start);
+        phrase.end = end.or(phrase.end);
+        return Ok(consumed);
+    }
+    Ok(0)
+}
+
+fn recurrence(frequency: Frequency, interval: u32, by_day: Vec<Weekday>) -> Recurrence {
+    Recurrence {
+        frequency,
+        interval,
+        by_day: by_day.into_iter().map(|weekday| ByDay { ordinal: None, weekday }).collect(),
+        count: None,
+        until: None,
+    }
+}
+
+/// The words after "every": "day", "2 weeks", "other month", "weekday",
+/// "monday and thursday", "tue,thu".
+fn parse_every(words: &[&str]) -> Option<(Recurrence, usize)> {
+    let (interval, skip) = match key(words.first()?).as_str() {
+        "other" => (2, 1),
+        word => match word.parse::<u32>() {
+            Ok(interval) if interval > 0 => (interval, 1),
+            _ => (1, 0),
+        },
+    };
+    let unit = key(words.get(skip)?);
+    let unit = unit.strip_suffix('s').unwrap_or(&unit);
+    let simple = match unit {
+        "day" => Some(recurrence(Frequency::Daily, interval, Vec::new())),
+        "week" => Some(recurrence(Frequency::Weekly, interval, Vec::new())),
+        "month" => Some(recurrence(Frequency::Monthly, interval, Vec::new())),
+        "year" => Some(recurrence(Frequency::Yearly, interval, Vec::new())),
+        "weekday" => Some(recurrence(
+            Frequency::Weekly,
+            interval,
+            vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+        )),
+        "weekend" => Some(recurrence(Frequency::Weekly, interval, vec![Weekday::Sat, Weekday::Sun])),
+        _ => None,
+    };
+    if let Some(simple) = simple {
+        return Some((simple, skip + 1));
+    }
+
+    let mut weekdays = Vec::new();
+    let mut consumed = skip;
+    while let Some(word) = words.get(consumed) {
+        let word = key(word);
+        let days: Option<Vec<Weekday>> = word.split(',').filter(|d| !d.is_empty()).map(parse_weekday).collect();
+        match days {
+            Some(days) if !days.is_empty() => weekdays.extend(days),
+            // "and" only joins two weekdays.
+            _ if word == "and" && !weekdays.is_empty() => {
+                if words.get(consumed + 1).is_none_or(|w| parse_weekday(&key(w)).is_none()) {
+                    break;
+                }
+            }
+            _ => break,
+        }
+        consumed += 1;
+    }
+    (!weekdays.is_empty()).then(|| (recurrence(Frequency::Weekly, interval, weekdays), consumed))
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    let word = match word {
+        "tues" => "tue",
+        "weds" => "wed",
+        "thur" | "thurs" => "thu",
+        word => word,
+    };
+    word.parse().ok().or_else(|| word.strip_suffix('s')?.parse().ok())
+}
+
+/// A date at the start of `words`: "today", "tomorrow", "friday", "next friday",
+/// "in 3 days", "2026-10-20", "oct 20", "20th october 2027". Relative dates
+/// beyond the calendar's range are an error.
+fn parse_date(words: &[&str], base: NaiveDate) -> Result<Option<(NaiveDate, usize)>> {
+    if key(words.first().unwrap_or(&"")) == "in" {
+        let (Some(amount), Some(unit)) = (words.get(1).and_then(|w| key(w).parse::<i64>().ok()), words.get(2)) else {
+            return Ok(None);
+        };
+        let days = match key(unit).trim_end_matches('s') {
+            "day" => Some(amount),
+            "week" => amount.checked_mul(7),
+            _ => return Ok(None),
+        };
+        let date = days.and_then(Duration::try_days).and_then(|days| base.checked_add_signed(days));
+        return match date {
+            Some(date) => Ok(Some((date, 3))),
+            None => bail!("'{}' is too far away", words[..3].join(" ")),
+        };
+    }
+    Ok(parse_named_date(words, base))
+}
+
+/// Any date `parse_date` reads but "in N days".
+fn parse_named_date(words: &[&str], base: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let first = key(words.first()?);
+    let second = words.get(1).map(|w| key(w));
+    match first.as_str() {
+        "today" | "tonight" => return Some((base, 1)),
+        "tomorrow" | "tmrw" => return Some((base.succ_opt()?, 1)),
+        "next" => {
+            let weekday = parse_weekday(&second?)?;
+            return Some((next_weekday(base.succ_opt()?, weekday), 2));
+        }
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&first, "%Y-%m-%d") {
+        return Some((date, 1));
+    }
+    if let Some(weekday) = parse_weekday(&first) {
+        return Some((next_weekday(base, weekday), 1));
+    }
+
+    // "oct 20" or "20 oct", optionally followed by a year.
+    let second = second?;
+    let (month, day) = match (first.parse::<Month>(), parse_day(&first)) {
+        (Ok(month), _) => (month, parse_day(&second)?),
+        (_, Some(day)) => (second.parse::<Month>().ok()?, day),
+        _ => return None,
+    };
+    let month = month.number_from_month();
+    if let Some(year) = words.get(2).and_then(|w| key(w).parse::<i32>().ok()).filter(|y| *y >= 1000) {
+        return Some((NaiveDate::from_ymd_opt(year, month, day)?, 3));
+    }
+    // Without a year, the next such date.
+    let date = NaiveDate::from_ymd_opt(base.year(), month, day)?;
+    let date = if date < base { NaiveDate::from_ymd_opt(base.year() + 1, month, day)? } else { date };
+    Some((date, 2))
+}
+
+/// Day of the month, e.g. "20" or "20th".
+fn parse_day(word: &str) -> Option<u32> {
+    let digits = ["st", "nd", "rd", "th"].iter().find_map(|s| word.strip_suffix(s)).unwrap_or(word);
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+/// First `weekday` on or after `from`.
+fn next_weekday(from: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days = (7 + weekday.num_days_from_monday() - from.weekday().num_days_from_monday()) % 7;
+    from + Duration::days(days as i64)
+}
+
+/// A time or time range at the start of `words`: "9am", "9 am", "12:30",
+/// "noon", "12:30-13:30", "9-10am". A plain hour such as "9" only counts
+/// when `bare` is set, i.e. after "at" or "from".
+fn parse_times(words: &[&str], bare: bool) -> Option<(NaiveTime, Option<NaiveTime>, usize)> {
+    let first = key(words.first()?);
+    if let Some(meridiem) = words.get(1).map(|w| key(w)).filter(|w| w == "am" || w == "pm") {
+        return Some((parse_time(&format!("{}{}", first, meridiem), true)?, None, 2));
+    }
+    if let Some((from, to)) = first.split_once('-').filter(|(from, to)| !from.is_empty() && !to.is_empty()) {
+        let end = parse_time(to, true)?;
+        // "9-10am": the start takes the end's am/pm unless that puts it after the end.
+        let meridiem = ["am", "pm"].into_iter().find(|m| to.ends_with(m) && !from.ends_with(m));
+        let start = meridiem
+            .and_then(|m| parse_time(&format!("{}{}", from, m), true))
+            .filter(|start| *start < end)
+            .or_else(|| parse_time(from, true))?;
+        return Some((start, Some(end), 1));
+    }
+    Some((parse_time(&first, bare)?, None, 1))
+}
+
+/// "9am", "9:30pm", "14:30", "noon", "midnight", and with `bare` "9".
+fn parse_time(word: &str, bare: bool) -> Option<NaiveTime> {
+    match word {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return Some(NaiveTime::MIN),
+        _ => {}
+    }
+    let (clock, pm) = match (word.strip_suffix("am"), word.strip_suffix("pm")) {
+        (Some(clock), _) => (clock, Some(false)),
+        (_, Some(clock)) => (clock, Some(true)),
+        _ => (word, None),
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour, minute.parse().ok()?),
+        Some(_) => return None,
+        None if pm.is_some() || bare => (clock, 0),
+        None => return None,
+    };
+    if hour.is_empty() || hour.len() > 2 {
+        return None;
+    }
+    let hour: u32 = hour.parse().ok()?;
+    let hour = match pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// "15m", "1h30m", "90 minutes", "2 hours", "3 days". Durations too long to
+/// represent are an error.
+fn parse_duration(words: &[&str]) -> Result<Option<(Duration, usize)>> {
+    let Some(first) = words.first().map(|w| key(w)) else {
+        return Ok(None);
+    };
+    let too_long = || anyhow!("'{}' is too long", words[..words.len().min(2)].join(" "));
+    if let Ok(amount) = first.parse::<i64>() {
+        let Some(minutes) = words.get(1).and_then(|unit| unit_minutes(&key(unit))) else {
+            return Ok(None);
+        };
+        let duration = amount.checked_mul(minutes).and_then(Duration::try_minutes).ok_or_else(too_long)?;
+        return Ok(Some((duration, 2)));
+    }
+    let mut total: i64 = 0;
+    let mut rest = first.as_str();
+    while !rest.is_empty() {
+        let Some(digits) = rest.find(|c: char| !c.is_ascii_digit()).filter(|&i| i > 0) else {
+            return Ok(None);
+        };
+        let unit = rest[digits..].find(|c: char| c.is_ascii_digit()).map_or(rest.len(), |i| digits + i);
+        let Some(minutes) = unit_minutes(&rest[digits..unit]) else {
+            return Ok(None);
+        };
+        total = rest[..digits]
+            .parse::<i64>()
+            .ok()
+            .and_then(|amount| amount.checked_mul(minutes))
+            .and_then(|minutes| total.checked_add(minutes))
+            .ok_or_else(too_long)?;
+        rest = &rest[unit..];
+    }
+    if total <= 0 {
+        return Ok(None);
+    }
+    Ok(Some((Duration::try_minutes(total).ok_or_else(too_long)?, 1)))
+}
+
+fn unit_minutes(unit: &str) -> Option<i64> {
+    match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(1),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(60),
+        "d" | "day" | "days" => Some(24 * 60),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Sunday; Warsaw is on summer time (UTC+2) until October 25.
+    fn base() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn parse(input: &str) -> Event {
+        parse_quick_add(input, base(), chrono_tz::Europe::Warsaw).unwrap()
+    }
+
+    /// Start and end in Warsaw time, e.g. "2026-10-19 12:30 - 2026-10-19 13:30".
+    fn span(event: &Event) -> String {
+        let local = |datetime: chrono::DateTime<chrono::Utc>| {
+            datetime.with_timezone(&chrono_tz::Europe::Warsaw).format("%Y-%m-%d %H:%M").to_string()
+        };
+        format!("{} - {}", local(event.start_datetime), local(event.end_datetime))
+    }
+
+    fn date(input: &str) -> NaiveDate {
+        parse(input).start_datetime.date_naive()
+    }
+
+    #[test]
+    fn parses_the_lunch_example() {
+        let event = parse("Lunch with Ana tomorrow 12:30-13:30 at Cafe Nero");
+        assert_eq!(event.title, "Lunch with Ana");
+        assert_eq!(span(&event), "2026-10-19 12:30 - 2026-10-19 13:30");
+        assert_eq!(event.location.as_deref(), Some("Cafe Nero"));
+        assert!(!event.all_day && event.recurrence.is_none());
+        assert_eq!(event.timezone.as_deref(), Some("Europe/Warsaw"));
+    }
+
+    #[test]
+    fn parses_the_standup_example() {
+        let event = parse("standup every weekday 9am for 15m");
+        assert_eq!(event.title, "standup");
+        // Sunday is not a weekday, so the series starts on Monday.
+        assert_eq!(span(&event), "2026-10-19 09:00 - 2026-10-19 09:15");
+        assert_eq!(event.recurrence.unwrap().to_string(), "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR");
+    }
+
+    #[test]
+    fn parses_time_ranges() {
+        assert_eq!(span(&parse("call 9-10am")), "2026-10-18 09:00 - 2026-10-18 10:00");
+        assert_eq!(span(&parse("call 11-1pm")), "2026-10-18 11:00 - 2026-10-18 13:00");
+        assert_eq!(span(&parse("party 22:00-01:00")), "2026-10-18 22:00 - 2026-10-19 01:00");
+        assert_eq!(span(&parse("call at 3pm")), "2026-10-18 15:00 - 2026-10-18 16:00");
+        assert_eq!(span(&parse("call from 9 to 11:30")), "2026-10-18 09:00 - 2026-10-18 11:30");
+    }
+
+    #[test]
+    fn parses_relative_and_calendar_dates() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        assert_eq!(date("review next friday"), day(23));
+        assert_eq!(date("review friday"), day(23));
+        assert_eq!(date("review sunday"), day(18));
+        assert_eq!(date("review oct 20"), day(20));
+        assert_eq!(date("review 20th october 2027"), NaiveDate::from_ymd_opt(2027, 10, 20).unwrap());
+        assert_eq!(date("review oct 1"), NaiveDate::from_ymd_opt(2027, 10, 1).unwrap());
+        assert_eq!(date("review in 3 days"), day(21));
+        assert_eq!(date("review in 2 weeks"), NaiveDate::from_ymd_opt(2026, 11, 1).unwrap());
+        assert_eq!(date("review 2026-12-24"), NaiveDate::from_ymd_opt(2026, 12, 24).unwrap());
+    }
+
+    #[test]
+    fn phrases_without_a_time_are_all_day() {
+        let event = parse("Holiday oct 20 for 3 days");
+        assert!(event.all_day);
+        let (first, last) = event.date_span(chrono_tz::Europe::Warsaw);
+        assert_eq!((first.to_string(), last.to_string()), ("2026-10-20".to_string(), "2026-10-22".to_string()));
+        assert!(parse("Meeting tomorrow at 10 all day").all_day);
+    }
+
+    #[test]
+    fn parses_every_and_until() {
+        let event = parse("gym every monday and thursday 7am until nov 30");
+        assert_eq!(event.title, "gym");
+        assert_eq!(span(&event), "2026-10-19 07:00 - 2026-10-19 08:00");
+        // The last day counts in full, in winter time (UTC+1).
+        assert_eq!(event.recurrence.unwrap().to_string(), "FREQ=WEEKLY;BYDAY=MO,TH;UNTIL=20261130T225959Z");
+        assert_eq!(parse("sync every other week").recurrence.unwrap().to_string(), "FREQ=WEEKLY;INTERVAL=2");
+        assert_eq!(parse("rent monthly").recurrence.unwrap().to_string(), "FREQ=MONTHLY");
+        // Without a recurrence, "until" is part of the title.
+        assert_eq!(parse("wait until nov 30").title, "wait until");
+    }
+
+    #[test]
+    fn ends_locations_at_a_recognised_part() {
+        let event = parse("Dinner at Cafe Nero tomorrow 19:00");
+        assert_eq!(event.title, "Dinner");
+        assert_eq!(event.location.as_deref(), Some("Cafe Nero"));
+        assert_eq!(span(&event), "2026-10-19 19:00 - 2026-10-19 20:00");
+        assert_eq!(parse("Drinks @ The Old Bell").location.as_deref(), Some("The Old Bell"));
+    }
+
+    #[test]
+    fn rejects_phrases_without_a_title() {
+        assert!(parse_quick_add("tomorrow 9am", base(), chrono_tz::UTC).is_err());
+    }
+
+    #[test]
+    fn rejects_dates_and_durations_out_of_range() {
+        for input in [
+            "trip in 99999999 days",
+            "trip in 9999999999999999 weeks",
+            "trip in 9223372036854775807 weeks",
+            "trip for 99999999 days",
+            "trip 9am for 99999999 days",
+            "trip 9am for 9223372036854775807 minutes",
+            "trip 9am for 9999999999999999999h",
+            "trip 9am for 2562047788015215h2562047788015215h",
+        ] {
+            assert!(parse_quick_add(input, base(), chrono_tz::UTC).is_err(), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,497 @@
+use crate::models::timezone::local_to_utc_lenient;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use std::fmt;
+use std::str::FromStr;
+
+/// Upper bound on the number of recurrence periods walked while expanding a
+/// rule, so a malformed rule can never hang the UI.
+const MAX_PERIODS: u32 = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A BYDAY entry, e.g. `MO`, `1MO` (first Monday) or `-1FR` (last Friday).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ByDay {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+/// Subset of the RFC 5545 RRULE grammar: FREQ, INTERVAL, BYDAY, COUNT and UNTIL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<ByDay>,
+    pub count: Option<u32>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Which part of a recurring series an edit or delete applies to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecurrenceScope {
+    ThisOccurrence,
+    ThisAndFollowing,
+    AllOccurrences,
+}
+
+impl RecurrenceScope {
+    pub const ALL: [RecurrenceScope; 3] = [
+        RecurrenceScope::ThisOccurrence,
+        RecurrenceScope::ThisAndFollowing,
+        RecurrenceScope::AllOccurrences,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RecurrenceScope::ThisOccurrence => "This occurrence",
+            RecurrenceScope::ThisAndFollowing => "This and following",
+            RecurrenceScope::AllOccurrences => "All occurrences",
+        }
+    }
+}
+
+impl Recurrence {
+    /// Returns the start of every occurrence that overlaps `[window_start, window_end)`.
+    /// Occurrences keep the wall-clock time of `dtstart` in its own zone.
+    pub fn occurrences(
+        &self,
+        dtstart: DateTime<Tz>,
+        duration: Duration,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+    ) -> Vec<DateTime<Utc>> {
+        let mut result = Vec::new();
+        self.walk(dtstart, |occurrence| {
+            if occurrence >= window_end {
+                return false;
+            }
+            if occurrence + duration > window_start {
+                result.push(occurrence);
+            }
+            true
+        });
+        result
+    }
+
+    /// Number of occurrences strictly before `before`.
+    pub fn count_before(&self, dtstart: DateTime<Tz>, before: DateTime<Utc>) -> u32 {
+        let mut n = 0;
+        self.walk(dtstart, |occurrence| {
+            if occurrence >= before {
+                return false;
+            }
+            n += 1;
+            true
+        });
+        n
+    }
+
+    /// Ends the series right before the occurrence starting at `occurrence`,
+    /// keeping COUNT-based rules COUNT-based.
+    pub fn truncate_before(&mut self, dtstart: DateTime<Tz>, occurrence: DateTime<Utc>) {
+        if self.count.is_some() {
+            self.count = Some(self.count_before(dtstart, occurrence));
+        } else {
+            self.until = Some(occurrence - Duration::seconds(1));
+        }
+    }
+
+    /// Visits occurrences in chronological order until `visit` returns false,
+    /// COUNT is exhausted or UNTIL is passed.
+    fn walk(&self, dtstart: DateTime<Tz>, mut visit: impl FnMut(DateTime<Utc>) -> bool) {
+        let interval = self.interval.max(1);
+        let tz = dtstart.timezone();
+        let local_start = dtstart.naive_local();
+        let mut emitted = 0;
+
+        for period in 0..MAX_PERIODS {
+            let step = period * interval;
+            let dates = match self.period_dates(local_start.date(), step) {
+                Some(dates) => dates,
+                None => return,
+            };
+            for date in dates {
+                let local = date.and_time(local_start.time());
+                if local < local_start {
+                    continue;
+                }
+                let occurrence = local_to_utc_lenient(tz, local);
+                if self.until.is_some_and(|until| occurrence > until) {
+                    return;
+                }
+                if self.count.is_some_and(|count| emitted >= count) {
+                    return;
+                }
+                emitted += 1;
+                if !visit(occurrence) {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Candidate dates of the `step`-th period after the one containing `start`.
+    fn period_dates(&self, start: NaiveDate, step: u32) -> Option<Vec<NaiveDate>> {
+        let dates = match self.frequency {
+            Frequency::Daily => {
+                let day = start.checked_add_signed(Duration::days(step as i64))?;
+                if self.by_day.is_empty() || self.by_day.iter().any(|b| b.weekday == day.weekday()) {
+                    vec![day]
+                } else {
+                    vec![]
+                }
+            }
+            Frequency::Weekly => {
+                let week_start = start - Duration::days(start.weekday().num_days_from_monday() as i64);
+                let first = week_start.checked_add_signed(Duration::weeks(step as i64))?;
+                let days: Vec<NaiveDate> = (0..7).map(|offset| first + Duration::days(offset)).collect();
+                if self.by_day.is_empty() {
+                    days.into_iter().filter(|d| d.weekday() == start.weekday()).collect()
+                } else {
+                    days.into_iter()
+                        .filter(|d| self.by_day.iter().any(|b| b.weekday == d.weekday()))
+                        .collect()
+                }
+            }
+            Frequency::Monthly => {
+                let first = start.with_day(1)?.checked_add_months(Months::new(step))?;
+                let next = first.checked_add_months(Months::new(1))?;
+                if self.by_day.is_empty() {
+                    first.with_day(start.day()).into_iter().collect()
+                } else {
+                    self.expand_by_day(first, next)
+                }
+            }
+            Frequency::Yearly => {
+                let year = start.year().checked_add(step as i32)?;
+                if self.by_day.is_empty() {
+                    NaiveDate::from_ymd_opt(year, start.month(), start.day()).into_iter().collect()
+                } else {
+                    let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                    let next = NaiveDate::from_ymd_opt(year + 1, 1, 1)?;
+                    self.expand_by_day(first, next)
+                }
+            }
+        };
+        Some(dates)
+    }
+
+    /// Resolves BYDAY entries within `[first, next)`, honouring ordinals.
+    fn expand_by_day(&self, first: NaiveDate, next: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        for by_day in &self.by_day {
+            let matching: Vec<NaiveDate> = first
+                .iter_days()
+                .take_while(|d| *d < next)
+                .filter(|d| d.weekday() == by_day.weekday)
+                .collect();
+            match by_day.ordinal {
+                None => dates.extend(matching),
+                Some(n) if n > 0 => dates.extend(matching.get(n as usize - 1)),
+                Some(n) if n < 0 => {
+                    let from_end = n.unsigned_abs() as usize;
+                    if from_end <= matching.len() {
+                        dates.push(matching[matching.len() - from_end]);
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+        dates.sort();
+        dates.dedup();
+        dates
+    }
+}
+
+fn parse_weekday(s: &str) -> Result<Weekday> {
+    Ok(match s {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => bail!("Invalid weekday '{}'", s),
+    })
+}
+
+fn format_weekday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_until(s: &str) -> Result<DateTime<Utc>> {
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s.trim_end_matches('Z'), "%Y%m%dT%H%M%S") {
+        return Ok(Utc.from_utc_datetime(&dt));
+    }
+    let date = NaiveDate::parse_from_str(s, "%Y%m%d").map_err(|_| anyhow!("Invalid UNTIL '{}'", s))?;
+    Ok(Utc.from_utc_datetime(&date.and_hms_opt(23, 59, 59).unwrap_or_default()))
+}
+
+impl FromStr for ByDay {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.len() < 2 {
+            bail!("Invalid BYDAY '{}'", s);
+        }
+        let (ordinal, day) = s.split_at(s.len() - 2);
+        let ordinal = if ordinal.is_empty() {
+            None
+        } else {
+            Some(ordinal.parse::<i32>().map_err(|_| anyhow!("Invalid BYDAY '{}'", s))?)
+        };
+        Ok(ByDay {
+            ordinal,
+            weekday: parse_weekday(day)?,
+        })
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let s = s.strip_prefix("RRULE:").unwrap_or(s);
+        let mut frequency = None;
+        let mut recurrence = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 1,
+            by_day: Vec::new(),
+            count: None,
+            until: None,
+        };
+
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid rule part '{}'", part))?;
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.trim().to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => bail!("Unsupported FREQ '{}'", other),
+                    })
+                }
+                "INTERVAL" => {
+                    recurrence.interval = value.trim().parse().map_err(|_| anyhow!("Invalid INTERVAL '{}'", value))?;
+                    if recurrence.interval == 0 {
+                        bail!("INTERVAL must be positive");
+                    }
+                }
+                "BYDAY" => {
+                    recurrence.by_day = value
+                        .to_ascii_uppercase()
+                        .split(',')
+                        .map(str::parse)
+                        .collect::<Result<_>>()?;
+                }
+                "COUNT" => {
+                    recurrence.count = Some(value.trim().parse().map_err(|_| anyhow!("Invalid COUNT '{}'", value))?)
+                }
+                "UNTIL" => recurrence.until = Some(parse_until(value.trim())?),
+                // WKST and friends are accepted but not interpreted.
+                _ => {}
+            }
+        }
+
+        if recurrence.count.is_some() && recurrence.until.is_some() {
+            bail!("COUNT and UNTIL cannot both be set");
+        }
+        recurrence.frequency = frequency.ok_or_else(|| anyhow!("Missing FREQ"))?;
+        Ok(recurrence)
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self
+                .by_day
+                .iter()
+                .map(|b| match b.ordinal {
+                    Some(n) => format!("{}{}", n, format_weekday(b.weekday)),
+                    None => format_weekday(b.weekday).to_string(),
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(value: &str) -> Recurrence {
+        value.parse().unwrap()
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+
+    /// Occurrences of `value` starting at `start` in UTC, within the next 10 years.
+    fn starts(value: &str, start: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let end = start + Duration::days(3653);
+        rule(value).occurrences(start.with_timezone(&Tz::UTC), Duration::hours(1), start, end)
+    }
+
+    #[test]
+    fn parses_and_prints_rules() {
+        let monthly = rule("RRULE:freq=monthly;interval=2;byday=-1fr,2MO;COUNT=5;WKST=MO");
+        assert_eq!(monthly.frequency, Frequency::Monthly);
+        assert_eq!(monthly.interval, 2);
+        assert_eq!(
+            monthly.by_day,
+            [
+                ByDay { ordinal: Some(-1), weekday: Weekday::Fri },
+                ByDay { ordinal: Some(2), weekday: Weekday::Mon },
+            ]
+        );
+        assert_eq!(monthly.to_string(), "FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR,2MO;COUNT=5");
+        assert_eq!(rule(&monthly.to_string()), monthly);
+
+        // A date-only UNTIL includes the whole day.
+        let until = rule("FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20261231");
+        assert_eq!(until.until, Some(utc(2026, 12, 31, 23) + Duration::seconds(59 * 60 + 59)));
+        assert_eq!(until.to_string(), "FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20261231T235959Z");
+        assert_eq!(rule(&until.to_string()), until);
+
+        for invalid in [
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;BYDAY=XX",
+            "FREQ=DAILY;COUNT=2;UNTIL=20261231",
+        ] {
+            assert!(invalid.parse::<Recurrence>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn resolves_ordinal_weekdays_within_each_month() {
+        assert_eq!(
+            starts("FREQ=MONTHLY;BYDAY=2MO,-1FR;COUNT=6", utc(2026, 1, 12, 9)),
+            [
+                utc(2026, 1, 12, 9),
+                utc(2026, 1, 30, 9),
+                utc(2026, 2, 9, 9),
+                utc(2026, 2, 27, 9),
+                utc(2026, 3, 9, 9),
+                utc(2026, 3, 27, 9),
+            ]
+        );
+        // Weekly rules list the days of each week in order, starting from the series start.
+        assert_eq!(
+            starts("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;COUNT=4", utc(2026, 3, 4, 9)),
+            [utc(2026, 3, 6, 9), utc(2026, 3, 16, 9), utc(2026, 3, 20, 9), utc(2026, 3, 30, 9)]
+        );
+    }
+
+    #[test]
+    fn stops_at_count_or_until() {
+        assert_eq!(
+            starts("FREQ=DAILY;COUNT=3", utc(2026, 3, 1, 9)),
+            [utc(2026, 3, 1, 9), utc(2026, 3, 2, 9), utc(2026, 3, 3, 9)]
+        );
+        assert_eq!(
+            starts("FREQ=DAILY;UNTIL=20260303", utc(2026, 3, 1, 9)),
+            [utc(2026, 3, 1, 9), utc(2026, 3, 2, 9), utc(2026, 3, 3, 9)]
+        );
+        assert_eq!(starts("FREQ=DAILY;UNTIL=20260303T085959Z", utc(2026, 3, 1, 9)).len(), 2);
+        // COUNT counts from the series start even when the window starts later.
+        let daily = rule("FREQ=DAILY;COUNT=3");
+        let start = utc(2026, 3, 1, 9).with_timezone(&Tz::UTC);
+        assert_eq!(
+            daily.occurrences(start, Duration::hours(1), utc(2026, 3, 3, 0), utc(2026, 4, 1, 0)),
+            [utc(2026, 3, 3, 9)]
+        );
+        assert_eq!(daily.count_before(start, utc(2026, 3, 3, 9)), 2);
+    }
+
+    #[test]
+    fn skips_months_without_the_start_day() {
+        assert_eq!(
+            starts("FREQ=MONTHLY;COUNT=4", utc(2026, 1, 31, 9)),
+            [utc(2026, 1, 31, 9), utc(2026, 3, 31, 9), utc(2026, 5, 31, 9), utc(2026, 7, 31, 9)]
+        );
+        assert_eq!(
+            starts("FREQ=YEARLY;COUNT=2", utc(2024, 2, 29, 9)),
+            [utc(2024, 2, 29, 9), utc(2028, 2, 29, 9)]
+        );
+    }
+
+    #[test]
+    fn gives_up_on_rules_that_never_match() {
+        // No month has a sixth Monday; the walk ends after MAX_PERIODS months.
+        let never = rule("FREQ=MONTHLY;BYDAY=6MO");
+        let start = utc(2026, 1, 1, 9);
+        assert!(never
+            .occurrences(start.with_timezone(&Tz::UTC), Duration::hours(1), start, DateTime::<Utc>::MAX_UTC)
+            .is_empty());
+    }
+
+    #[test]
+    fn keeps_the_local_time_across_dst() {
+        let warsaw: Tz = "Europe/Warsaw".parse().unwrap();
+        let start = utc(2026, 10, 24, 7).with_timezone(&warsaw);
+        let occurrences = rule("FREQ=DAILY;COUNT=3").occurrences(
+            start,
+            Duration::hours(1),
+            utc(2026, 10, 1, 0),
+            utc(2026, 11, 1, 0),
+        );
+        assert_eq!(occurrences, [utc(2026, 10, 24, 7), utc(2026, 10, 25, 8), utc(2026, 10, 26, 8)]);
+    }
+
+    #[test]
+    fn truncates_count_and_until_rules() {
+        let start = utc(2026, 3, 1, 9).with_timezone(&Tz::UTC);
+        let mut counted = rule("FREQ=DAILY;COUNT=5");
+        counted.truncate_before(start, utc(2026, 3, 3, 9));
+        assert_eq!(counted.to_string(), "FREQ=DAILY;COUNT=2");
+
+        let mut open = rule("FREQ=DAILY");
+        open.truncate_before(start, utc(2026, 3, 3, 9));
+        assert_eq!(open.until, Some(utc(2026, 3, 3, 9) - Duration::seconds(1)));
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::{anyhow, bail, Result};
+
+/// Longest reminder offset accepted: four weeks.
+pub const MAX_REMINDER_MINUTES: i64 = 4 * 7 * 24 * 60;
+
+/// Parses a list of reminder offsets such as `10m, 1h, 1d` into minutes before
+/// the event start. A bare number is taken as minutes.
+pub fn parse_reminders(value: &str) -> Result<Vec<i64>> {
+    let mut minutes = Vec::new();
+    for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (number, unit) = match part.find(|c: char| !c.is_ascii_digit()) {
+            Some(i) => part.split_at(i),
+            None => (part, "m"),
+        };
+        let n: i64 = number.parse().map_err(|_| anyhow!("Invalid reminder '{}'", part))?;
+        let factor = match unit.trim().to_ascii_lowercase().as_str() {
+            "m" | "min" => 1,
+            "h" => 60,
+            "d" => 60 * 24,
+            "w" => 60 * 24 * 7,
+            _ => bail!("Invalid reminder '{}', expected e.g. 10m, 2h or 1d", part),
+        };
+        match n.checked_mul(factor) {
+            Some(offset) if offset <= MAX_REMINDER_MINUTES => minutes.push(offset),
+            _ => bail!("Reminder '{}' is more than 4 weeks before the event", part),
+        }
+    }
+    minutes.sort_unstable();
+    minutes.dedup();
+    Ok(minutes)
+}
+
+/// Formats a reminder offset in the largest whole unit, e.g. `90m`, `2h`, `1d`.
+pub fn format_reminder(minutes: i64) -> String {
+    match minutes {
+        0 => "0m".to_string(),
+        m if m % (60 * 24 * 7) == 0 => format!("{}w", m / (60 * 24 * 7)),
+        m if m % (60 * 24) == 0 => format!("{}d", m / (60 * 24)),
+        m if m % 60 == 0 => format!("{}h", m / 60),
+        m => format!("{}m", m),
+    }
+}
+
+pub fn format_reminders(minutes: &[i64]) -> String {
+    minutes.iter().map(|m| format_reminder(*m)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_units_and_sorts() {
+        assert_eq!(parse_reminders("1h, 10, 1d, 10m").unwrap(), vec![10, 60, 1440]);
+        assert_eq!(format_reminders(&[10, 60, 1440, 10080]), "10m, 1h, 1d, 1w");
+    }
+
+    #[test]
+    fn rejects_offsets_too_far_ahead() {
+        assert!(parse_reminders("4w").is_ok());
+        assert!(parse_reminders("5w").is_err());
+        assert!(parse_reminders("9223372036854775807w").is_err());
+        assert!(parse_reminders("99999999999999999999").is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::{bail, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Tags typed as words separated by spaces or commas, e.g. "1:1, travel
+/// #focus". Tags are lowercase; a leading `#` is dropped.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|word| word.trim_start_matches('#').to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+pub fn format_tags(tags: &[String]) -> String {
+    tags.join(", ")
+}
+
+/// Completion of the tag being typed at the end of `input` from `known`
+/// tags: the characters still missing, or `None`.
+pub fn complete_tag<'a>(input: &str, known: &'a [String]) -> Option<&'a str> {
+    let partial = input.rsplit(|c: char| c == ',' || c.is_whitespace()).next()?;
+    let partial = partial.trim_start_matches('#').to_lowercase();
+    if partial.is_empty() {
+        return None;
+    }
+    let typed = parse_tags(input);
+    known
+        .iter()
+        .filter(|tag| !typed.contains(tag))
+        .find(|tag| tag.starts_with(&partial) && tag.len() > partial.len())
+        .map(|tag| &tag[partial.len()..])
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Tag(String),
+    Not(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+}
+
+impl Node {
+    fn matches(&self, tags: &[String]) -> bool {
+        match self {
+            Node::Tag(tag) => tags.contains(tag),
+            Node::Not(node) => !node.matches(tags),
+            Node::And(left, right) => left.matches(tags) && right.matches(tags),
+            Node::Or(left, right) => left.matches(tags) || right.matches(tags),
+        }
+    }
+}
+
+/// Which events to show by their tags, e.g. "travel or focus",
+/// "interview and not 1:1" or "-travel (focus or 1:1)". Adjacent terms are
+/// and-ed; `-tag` is short for `not tag`. The empty filter shows everything.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TagFilter {
+    expression: String,
+    root: Option<Node>,
+}
+
+impl TagFilter {
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn matches(&self, tags: &[String]) -> bool {
+        self.root.as_ref().is_none_or(|root| root.matches(tags))
+    }
+}
+
+impl fmt::Display for TagFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+impl FromStr for TagFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        let tokens = tokenize(expression);
+        if tokens.is_empty() {
+            return Ok(TagFilter::default());
+        }
+        let mut parser = Parser { tokens, position: 0 };
+        let root = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.position) {
+            bail!("Unexpected '{}' in tag filter", token);
+        }
+        Ok(TagFilter {
+            expression: expression.trim().to_string(),
+            root: Some(root),
+        })
+    }
+}
+
+fn tokenize(expression: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    for c in expression.chars() {
+        if c.is_whitespace() || c == '(' || c == ')' || (c == '-' && word.is_empty()) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+/// Recursive descent over `or` < `and` < `not`.
+struct Parser {
+    tokens: Vec<String>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<String> {
+        self.tokens.get(self.position).map(|token| token.to_lowercase())
+    }
+
+    fn or(&mut self) -> Result<Node> {
+        let mut node = self.and()?;
+        while self.peek().as_deref() == Some("or") {
+            self.position += 1;
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node> {
+        let mut node = self.not()?;
+        loop {
+            match self.peek().as_deref() {
+                Some("and") => self.position += 1,
+                None | Some("or") | Some(")") => return Ok(node),
+                _ => {}
+            }
+            node = Node::And(Box::new(node), Box::new(self.not()?));
+        }
+    }
+
+    fn not(&mut self) -> Result<Node> {
+        let Some(token) = self.peek() else {
+            bail!("Incomplete tag filter");
+        };
+        self.position += 1;
+        match token.as_str() {
+            "not" | "-" => Ok(Node::Not(Box::new(self.not()?))),
+            "(" => {
+                let node = self.or()?;
+                if self.peek().as_deref() != Some(")") {
+                    bail!("Missing ')' in tag filter");
+                }
+                self.position += 1;
+                Ok(node)
+            }
+            "and" | "or" | ")" => bail!("Unexpected '{}' in tag filter", token),
+            _ => Ok(Node::Tag(token.trim_start_matches('#').to_string())),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::{anyhow, bail, Result};
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Style of one slot in the `[theme]` config section, e.g.
+/// `header = { fg = "white", bg = "#1e3a8a", modifiers = ["bold"] }`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StyleSpec {
+    /// Color name ("red", "light-blue"), "#rrggbb" or a 0-255 palette index.
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    #[serde(default)]
+    pub modifiers: Vec<String>,
+}
+
+const MODIFIERS: &[(&str, Modifier)] = &[
+    ("bold", Modifier::BOLD),
+    ("dim", Modifier::DIM),
+    ("italic", Modifier::ITALIC),
+    ("underlined", Modifier::UNDERLINED),
+    ("slow_blink", Modifier::SLOW_BLINK),
+    ("rapid_blink", Modifier::RAPID_BLINK),
+    ("reversed", Modifier::REVERSED),
+    ("hidden", Modifier::HIDDEN),
+    ("crossed_out", Modifier::CROSSED_OUT),
+];
+
+impl StyleSpec {
+    fn style(&self) -> Result<Style> {
+        let color = |value: &str| {
+            value
+                .parse::<Color>()
+                .map_err(|_| anyhow!("Invalid color '{}', expected a name, #rrggbb or 0-255", value))
+        };
+        let mut style = Style::default();
+        if let Some(fg) = &self.fg {
+            style = style.fg(color(fg)?);
+        }
+        if let Some(bg) = &self.bg {
+            style = style.bg(color(bg)?);
+        }
+        for name in &self.modifiers {
+            let (_, modifier) = MODIFIERS
+                .iter()
+                .find(|(known, _)| known.eq_ignore_ascii_case(name))
+                .ok_or_else(|| anyhow!("Unknown modifier '{}'", name))?;
+            style = style.add_modifier(*modifier);
+        }
+        Ok(style)
+    }
+}
+
+/// Styles every view draws with.
+pub struct Theme {
+    /// Weekday headers of the month, week, day and year views and agenda dates.
+    pub header: Style,
+    /// Selected day, slot or list entry.
+    pub selection: Style,
+    /// Events of calendars without a usable color; the calendar color replaces the background otherwise.
+    pub event: Style,
+    pub today: Style,
+    pub weekend: Style,
+    /// Marker of slots where events overlap.
+    pub conflict: Style,
+    pub border: Style,
+    /// Hints, locations and times skipped by DST.
+    pub muted: Style,
+    pub error: Style,
+    /// Status line while an event is being moved.
+    pub status: Style,
+    /// Messages such as reminders and undo results.
+    pub banner: Style,
+    /// Tag chips next to event titles.
+    pub tag: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            header: Style::default().fg(Color::Red).bg(Color::Blue),
+            selection: Style::default().fg(Color::Black).bg(Color::Yellow),
+            event: Style::default().bg(Color::Cyan),
+            today: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            weekend: Style::default(),
+            conflict: Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+            border: Style::default(),
+            muted: Style::default().fg(Color::DarkGray),
+            error: Style::default().fg(Color::Red),
+            status: Style::default().fg(Color::White).bg(Color::Blue),
+            banner: Style::default().fg(Color::White).bg(Color::Magenta),
+            tag: Style::default().fg(Color::Magenta),
+        }
+    }
+}
+
+impl Theme {
+    pub const PRESETS: [&'static str; 3] = ["default", "light", "high-contrast"];
+
+    /// Starts from the built-in `preset` and replaces the slots named in
+    /// `overrides`. Fails on unknown presets, slots, colors or modifiers.
+    pub fn new(preset: &str, overrides: &BTreeMap<String, StyleSpec>) -> Result<Theme> {
+        let mut theme = match preset {
+            "default" => Theme::default(),
+            "light" => Theme::light(),
+            "high-contrast" => Theme::high_contrast(),
+            _ => bail!("Unknown theme '{}', expected one of {}", preset, Theme::PRESETS.join(", ")),
+        };
+        for (name, spec) in overrides {
+            let slot = theme.slot_mut(name).ok_or_else(|| anyhow!("Unknown theme slot '{}'", name))?;
+            *slot = spec.style().map_err(|e| anyhow!("Theme slot '{}': {}", name, e))?;
+        }
+        Ok(theme)
+    }
+
+    fn slot_mut(&mut self, name: &str) -> Option<&mut Style> {
+        Some(match name {
+            "header" => &mut self.header,
+            "selection" => &mut self.selection,
+            "event" => &mut self.event,
+            "today" => &mut self.today,
+            "weekend" => &mut self.weekend,
+            "conflict" => &mut self.conflict,
+            "border" => &mut self.border,
+            "muted" => &mut self.muted,
+            "error" => &mut self.error,
+            "status" => &mut self.status,
+            "banner" => &mut self.banner,
+            "tag" => &mut self.tag,
+            _ => return None,
+        })
+    }
+
+    /// For terminals with a light background.
+    fn light() -> Theme {
+        Theme {
+            header: Style::default().fg(Color::White).bg(Color::Blue).add_modifier(Modifier::BOLD),
+            selection: Style::default().fg(Color::Black).bg(Color::LightYellow),
+            event: Style::default().fg(Color::Black).bg(Color::LightCyan),
+            today: Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            weekend: Style::default().fg(Color::Red),
+            conflict: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            border: Style::default().fg(Color::Gray),
+            muted: Style::default().fg(Color::Gray),
+            error: Style::default().fg(Color::Red),
+            status: Style::default().fg(Color::White).bg(Color::Blue),
+            banner: Style::default().fg(Color::White).bg(Color::Magenta),
+            tag: Style::default().fg(Color::Magenta).add_modifier(Modifier::ITALIC),
+        }
+    }
+
+    /// Bright colors on black and white, without dim grays.
+    fn high_contrast() -> Theme {
+        Theme {
+            header: Style::default().fg(Color::Black).bg(Color::White).add_modifier(Modifier::BOLD),
+            selection: Style::default().fg(Color::Black).bg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            event: Style::default().fg(Color::Black).bg(Color::White),
+            today: Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            weekend: Style::default().fg(Color::LightCyan),
+            conflict: Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+            border: Style::default().fg(Color::White),
+            muted: Style::default().fg(Color::Gray),
+            error: Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+            status: Style::default().fg(Color::Black).bg(Color::White).add_modifier(Modifier::BOLD),
+            banner: Style::default().fg(Color::Black).bg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            tag: Style::default().fg(Color::LightMagenta).add_modifier(Modifier::BOLD),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(fg: Option<&str>, bg: Option<&str>, modifiers: &[&str]) -> StyleSpec {
+        StyleSpec {
+            fg: fg.map(str::to_string),
+            bg: bg.map(str::to_string),
+            modifiers: modifiers.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn loads_every_preset() {
+        for preset in Theme::PRESETS {
+            assert!(Theme::new(preset, &BTreeMap::new()).is_ok(), "{}", preset);
+        }
+        assert_eq!(Theme::new("light", &BTreeMap::new()).unwrap().event.bg, Some(Color::LightCyan));
+        assert_eq!(Theme::new("high-contrast", &BTreeMap::new()).unwrap().event.bg, Some(Color::White));
+        assert!(Theme::new("solarized", &BTreeMap::new()).is_err());
+    }
+
+    #[test]
+    fn replaces_the_slots_it_is_given() {
+        let overrides = BTreeMap::from([
+            ("header".to_string(), spec(Some("white"), Some("#1e3a8a"), &["bold", "Italic"])),
+            ("event".to_string(), spec(None, Some("208"), &[])),
+        ]);
+        let theme = Theme::new("light", &overrides).unwrap();
+        assert_eq!(
+            theme.header,
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Rgb(0x1e, 0x3a, 0x8a))
+                .add_modifier(Modifier::BOLD | Modifier::ITALIC)
+        );
+        // A slot is replaced as a whole, not patched over the preset.
+        assert_eq!(theme.event, Style::default().bg(Color::Indexed(208)));
+        assert_eq!(theme.selection, Theme::light().selection);
+    }
+
+    #[test]
+    fn rejects_unknown_slots_colors_and_modifiers() {
+        for (slot, spec) in [
+            ("sidebar", spec(Some("red"), None, &[])),
+            ("header", spec(Some("reddish"), None, &[])),
+            ("header", spec(None, Some("#12345"), &[])),
+            ("header", spec(None, None, &["sparkly"])),
+        ] {
+            let overrides = BTreeMap::from([(slot.to_string(), spec)]);
+            assert!(Theme::new("default", &overrides).is_err(), "{}", slot);
+        }
+    }
+}
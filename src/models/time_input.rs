@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+/// Time patterns accepted in date/time fields whatever `time_format` is set to.
+const TIME_PATTERNS: &[&str] = &["%H:%M", "%I:%M %p", "%I:%M%p"];
+
+/// Placeholder describing what a date/time field expects.
+pub fn input_hint(all_day: bool, time_pattern: &str) -> &'static str {
+    match (all_day, time_pattern.contains("%p")) {
+        (true, _) => "YYYY-MM-DD",
+        (false, true) => "YYYY-MM-DD hh:mm AM",
+        (false, false) => "YYYY-MM-DD HH:MM",
+    }
+}
+
+/// Text shown in a date/time field; all-day events only show the date.
+pub fn format_input(value: NaiveDateTime, all_day: bool, time_pattern: &str) -> String {
+    if all_day {
+        value.format("%Y-%m-%d").to_string()
+    } else {
+        format!("{} {}", value.format("%Y-%m-%d"), value.format(time_pattern))
+    }
+}
+
+/// Parses a date/time field: a `YYYY-MM-DD` date and, unless `all_day`, a
+/// 24-hour ("14:30") or 12-hour ("2:30 pm") time.
+pub fn parse_input(input: &str, all_day: bool, time_pattern: &str) -> Result<NaiveDateTime> {
+    let input = input.trim();
+    let invalid = || anyhow!("expected {}", input_hint(all_day, time_pattern));
+    let (date, time) = match input.split_once([' ', 'T']) {
+        Some((date, time)) => (date, time.trim()),
+        None => (input, ""),
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| invalid())?;
+    if all_day {
+        return if time.is_empty() {
+            Ok(date.and_time(NaiveTime::MIN))
+        } else {
+            Err(invalid())
+        };
+    }
+    // chrono wants minutes, so "2 pm" and "14" become "2:00 PM" and "14:00".
+    let time = time.to_uppercase();
+    let time = match time.find(|c: char| !c.is_ascii_digit()) {
+        _ if time.contains(':') => time,
+        Some(index) => format!("{}:00{}", &time[..index], &time[index..]),
+        None => format!("{}:00", time),
+    };
+    let time = TIME_PATTERNS
+        .iter()
+        .find_map(|pattern| NaiveTime::parse_from_str(&time, pattern).ok())
+        .ok_or_else(invalid)?;
+    Ok(date.and_time(time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{time_field, EventFormState, FormField};
+    use crate::models::timezone::{local_instants, local_to_utc};
+    use chrono_tz::Europe::Warsaw;
+    use tui_textarea::TextArea;
+
+    const H24: &str = "%H:%M";
+    const H12: &str = "%I:%M %p";
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    /// An event form with only the time fields filled in.
+    fn form<'a>(start: &str, end: &str, all_day: bool) -> EventFormState<'a> {
+        EventFormState {
+            title: TextArea::default(),
+            description: TextArea::default(),
+            location: TextArea::default(),
+            tags: TextArea::default(),
+            known_tags: Vec::new(),
+            attendees: TextArea::default(),
+            recurrence: TextArea::default(),
+            reminders: TextArea::default(),
+            start: TextArea::from([start.to_string()]),
+            end: TextArea::from([end.to_string()]),
+            all_day,
+            calendar_id: None,
+            timezone: None,
+            focused_field: FormField::Title,
+            recurrence_id: None,
+            recurrence_scope: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn parses_times_in_either_format() {
+        let expected = at("2026-10-19", "14:30");
+        for input in ["2026-10-19 14:30", " 2026-10-19T14:30 ", "2026-10-19 2:30 pm", "2026-10-19 2:30PM"] {
+            assert_eq!(parse_input(input, false, H24).unwrap(), expected, "{}", input);
+            assert_eq!(parse_input(input, false, H12).unwrap(), expected, "{}", input);
+        }
+        assert_eq!(parse_input("2026-10-19 14", false, H24).unwrap(), at("2026-10-19", "14:00"));
+        assert_eq!(parse_input("2026-10-19 2 pm", false, H24).unwrap(), at("2026-10-19", "14:00"));
+        assert_eq!(parse_input("2026-10-19 12am", false, H12).unwrap(), at("2026-10-19", "00:00"));
+    }
+
+    #[test]
+    fn all_day_input_is_a_date_alone() {
+        assert_eq!(parse_input("2026-10-19", true, H24).unwrap(), at("2026-10-19", "00:00"));
+        let err = parse_input("2026-10-19 14:30", true, H24).unwrap_err();
+        assert_eq!(err.to_string(), "expected YYYY-MM-DD");
+        // A timed field needs the time.
+        let err = parse_input("2026-10-19", false, H12).unwrap_err();
+        assert_eq!(err.to_string(), "expected YYYY-MM-DD hh:mm AM");
+    }
+
+    #[test]
+    fn rejects_invalid_dates_and_times() {
+        let inputs = [
+            "",
+            "2026-02-29 10:00",
+            "2026-13-01 10:00",
+            "19.10.2026 10:00",
+            "2026-10-19 25:00",
+            "2026-10-19 10:60",
+            "2026-10-19 13 pm",
+            "2026-10-19 noon",
+        ];
+        for input in inputs {
+            let err = parse_input(input, false, H24).unwrap_err();
+            assert_eq!(err.to_string(), "expected YYYY-MM-DD HH:MM", "{:?}", input);
+        }
+        assert!(parse_input("2026-02-30", true, H24).is_err());
+        assert!(parse_input("2028-02-29", true, H24).is_ok());
+    }
+
+    #[test]
+    fn formats_what_it_parses() {
+        let value = at("2026-10-19", "14:30");
+        assert_eq!(format_input(value, false, H24), "2026-10-19 14:30");
+        assert_eq!(format_input(value, false, H12), "2026-10-19 02:30 PM");
+        assert_eq!(format_input(value, true, H12), "2026-10-19");
+        for (all_day, pattern) in [(false, H24), (false, H12)] {
+            assert_eq!(parse_input(&format_input(value, all_day, pattern), all_day, pattern).unwrap(), value);
+        }
+        assert_eq!(parse_input(&format_input(value, true, H24), true, H24).unwrap(), at("2026-10-19", "00:00"));
+    }
+
+    #[test]
+    fn hints_follow_the_field_and_time_format() {
+        assert_eq!(input_hint(true, H12), "YYYY-MM-DD");
+        assert_eq!(input_hint(false, H24), "YYYY-MM-DD HH:MM");
+        assert_eq!(input_hint(false, H12), "YYYY-MM-DD hh:mm AM");
+    }
+
+    #[test]
+    fn flags_an_end_before_the_start() {
+        let errors = |start, end, all_day| form(start, end, all_day).time_errors(H24);
+        assert_eq!(errors("2026-10-19 14:30", "2026-10-19 15:00", false), (None, None));
+        assert_eq!(
+            errors("2026-10-19 14:30", "2026-10-19 14:30", false),
+            (None, Some("must be after the start".to_string()))
+        );
+        assert_eq!(
+            errors("2026-10-19 14:30", "2026-10-18 16:00", false),
+            (None, Some("must be after the start".to_string()))
+        );
+        // The end of an all-day event is its last day, which may be the first.
+        assert_eq!(errors("2026-10-19", "2026-10-19", true), (None, None));
+        assert_eq!(
+            errors("2026-10-19", "2026-10-18", true),
+            (None, Some("last day is before the first".to_string()))
+        );
+        assert_eq!(
+            errors("2026-10-32", "2026-10-18", true),
+            (Some("expected YYYY-MM-DD".to_string()), None)
+        );
+    }
+
+    #[test]
+    fn accepts_a_time_skipped_by_dst_that_the_form_then_refuses() {
+        // Warsaw clocks jump from 02:00 to 03:00 on 2026-03-29.
+        let skipped = parse_input("2026-03-29 02:30", false, H24).unwrap();
+        assert_eq!(skipped, at("2026-03-29", "02:30"));
+        assert!(local_instants(Warsaw, skipped).is_empty());
+        assert_eq!(local_to_utc(Warsaw, skipped), None);
+        let after = parse_input("2026-03-29 03:30", false, H24).unwrap();
+        assert_eq!(local_to_utc(Warsaw, after).unwrap().to_rfc3339(), "2026-03-29T01:30:00+00:00");
+        // The fields keep showing the skipped time as typed.
+        assert_eq!(time_field(skipped, false, H24).lines(), ["2026-03-29 02:30"]);
+    }
+}
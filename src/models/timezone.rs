@@ -0,0 +1,47 @@
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Resolves a configured IANA zone name, falling back to the system zone and then UTC.
+pub fn resolve_timezone(name: Option<&str>) -> Tz {
+    name.and_then(|name| name.parse::<Tz>().ok())
+        .or_else(|| iana_time_zone::get_timezone().ok().and_then(|name| name.parse().ok()))
+        .unwrap_or(Tz::UTC)
+}
+
+/// Converts a wall-clock time to UTC. Ambiguous times (DST fall-back) resolve
+/// to the earlier instant; nonexistent ones (DST spring-forward) to `None`.
+pub fn local_to_utc(tz: Tz, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&naive).earliest().map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Like `local_to_utc`, but moves times inside a DST gap forward past the gap.
+pub fn local_to_utc_lenient(tz: Tz, naive: NaiveDateTime) -> DateTime<Utc> {
+    (0..=4)
+        .find_map(|hours| local_to_utc(tz, naive + Duration::minutes(30 * hours)))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&naive))
+}
+
+/// Every instant at which `naive` occurs on the wall clock: none inside a DST
+/// gap, two during a fall-back overlap.
+pub fn local_instants(tz: Tz, naive: NaiveDateTime) -> Vec<DateTime<Utc>> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => vec![dt.with_timezone(&Utc)],
+        LocalResult::Ambiguous(earlier, later) => vec![earlier.with_timezone(&Utc), later.with_timezone(&Utc)],
+        LocalResult::None => vec![],
+    }
+}
+
+pub fn to_local(tz: Tz, datetime: DateTime<Utc>) -> NaiveDateTime {
+    datetime.with_timezone(&tz).naive_local()
+}
+
+/// Start of `date` in `tz`, as a UTC timestamp.
+pub fn start_of_day(tz: Tz, date: NaiveDate) -> i64 {
+    local_to_utc_lenient(tz, date.and_hms_opt(0, 0, 0).unwrap_or_default()).timestamp()
+}
+
+/// UTC timestamps bounding the local days `first..=last`, which need not be
+/// 24 hours long around DST transitions.
+pub fn day_range(tz: Tz, first: NaiveDate, last: NaiveDate) -> (i64, i64) {
+    (start_of_day(tz, first), start_of_day(tz, last + Duration::days(1)))
+}
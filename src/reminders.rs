@@ -0,0 +1,364 @@
+use crate::models::{
+    config::ReminderConfig,
+    event::Event,
+    reminder::format_reminder,
+    timezone::{local_to_utc_lenient, to_local},
+};
+use crate::models::tags::TagFilter;
+use crate::storage::db::{get_events_in_range, max_reminder_minutes};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use rusqlite::Connection;
+use std::cell::RefCell;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+/// Source of the current time, so the scheduler can be driven by a fake clock.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A reminder whose time has come, for one occurrence of an event.
+pub struct DueReminder {
+    pub event: Event,
+    pub minutes_before: i64,
+    pub remind_at: DateTime<Utc>,
+}
+
+impl DueReminder {
+    pub fn message(&self, tz: Tz, time_pattern: &str) -> String {
+        let when = if self.event.all_day {
+            "all day".to_string()
+        } else {
+            to_local(tz, self.event.start_datetime).format(time_pattern).to_string()
+        };
+        match self.minutes_before {
+            0 => format!("Now: {} ({})", self.event.title, when),
+            m => format!("In {}: {} ({})", format_reminder(m), self.event.title, when),
+        }
+    }
+}
+
+pub trait Notifier {
+    fn notify(&mut self, reminder: &DueReminder) -> Result<()>;
+}
+
+/// Shows reminders in a banner at the bottom of the TUI, optionally ringing the bell.
+pub struct TerminalNotifier {
+    pub bell: bool,
+    pub timezone: Tz,
+    pub time_pattern: &'static str,
+    pub banner: Rc<RefCell<Option<String>>>,
+}
+
+impl Notifier for TerminalNotifier {
+    fn notify(&mut self, reminder: &DueReminder) -> Result<()> {
+        *self.banner.borrow_mut() = Some(reminder.message(self.timezone, self.time_pattern));
+        if self.bell {
+            let mut stdout = std::io::stdout();
+            stdout.write_all(b"\x07")?;
+            stdout.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs a user-configured shell command with the event's fields in its environment.
+pub struct CommandNotifier {
+    pub command: String,
+    pub timezone: Tz,
+    pub time_pattern: &'static str,
+}
+
+impl Notifier for CommandNotifier {
+    fn notify(&mut self, reminder: &DueReminder) -> Result<()> {
+        let event = &reminder.event;
+        let local = |datetime| to_local(self.timezone, datetime).format("%Y-%m-%dT%H:%M:%S").to_string();
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .env("CALENDAR_EVENT_ID", event.id.map(|id| id.to_string()).unwrap_or_default())
+            .env("CALENDAR_EVENT_TITLE", &event.title)
+            .env("CALENDAR_EVENT_DESCRIPTION", event.description.as_deref().unwrap_or(""))
+            .env("CALENDAR_EVENT_LOCATION", event.location.as_deref().unwrap_or(""))
+            .env("CALENDAR_EVENT_START", local(event.start_datetime))
+            .env("CALENDAR_EVENT_END", local(event.end_datetime))
+            .env("CALENDAR_EVENT_ALL_DAY", if event.all_day { "1" } else { "0" })
+            .env("CALENDAR_REMINDER_MINUTES", reminder.minutes_before.to_string())
+            .env("CALENDAR_REMINDER_MESSAGE", reminder.message(self.timezone, self.time_pattern))
+            // The TUI owns the terminal; the hook must not write over it.
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run reminder command '{}'", self.command))?;
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+/// Notifiers selected by the `[reminders]` config section.
+pub fn notifiers(
+    config: &ReminderConfig,
+    timezone: Tz,
+    time_pattern: &'static str,
+    banner: Rc<RefCell<Option<String>>>,
+) -> Vec<Box<dyn Notifier>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = vec![Box::new(TerminalNotifier {
+        bell: config.bell,
+        timezone,
+        time_pattern,
+        banner,
+    })];
+    if let Some(command) = config.command.as_deref().filter(|c| !c.trim().is_empty()) {
+        notifiers.push(Box::new(CommandNotifier {
+            command: command.to_string(),
+            timezone,
+            time_pattern,
+        }));
+    }
+    notifiers
+}
+
+/// Longest wait between two reads of the database, so reminders added in the
+/// meantime are at most this late.
+const CHECK_INTERVAL: Duration = Duration::minutes(1);
+
+/// Fires reminders whose time falls between two consecutive checks. Only
+/// reminders that come due while the app is running are delivered.
+pub struct ReminderScheduler<C: Clock> {
+    clock: C,
+    timezone: Tz,
+    last_check: DateTime<Utc>,
+    /// The database is not read again before this: the next reminder time
+    /// known at the last check, or `CHECK_INTERVAL` after it.
+    next_check: DateTime<Utc>,
+    notifiers: Vec<Box<dyn Notifier>>,
+}
+
+impl<C: Clock> ReminderScheduler<C> {
+    pub fn new(clock: C, timezone: Tz, notifiers: Vec<Box<dyn Notifier>>) -> Self {
+        let last_check = clock.now();
+        ReminderScheduler {
+            clock,
+            timezone,
+            last_check,
+            next_check: last_check,
+            notifiers,
+        }
+    }
+
+    /// Reminders due in `(last check, now]`, ordered by reminder time.
+    pub fn due(&mut self, conn: &Connection) -> Result<Vec<DueReminder>> {
+        let now = self.clock.now();
+        if now <= self.last_check || now < self.next_check {
+            return Ok(Vec::new());
+        }
+        let since = std::mem::replace(&mut self.last_check, now);
+        self.next_check = now + CHECK_INTERVAL;
+        let Some(max_minutes) = max_reminder_minutes(conn)? else {
+            return Ok(Vec::new());
+        };
+
+        // All-day events start at local midnight, up to a day off their stored time.
+        let window_end = now + Duration::minutes(max_minutes) + Duration::days(1);
+        // Reminders fire whatever tag filter the views show.
+        let events = get_events_in_range(
+            conn,
+            (since - Duration::days(1)).timestamp(),
+            window_end.timestamp(),
+            &TagFilter::default(),
+        )?;
+        let mut due = Vec::new();
+        for event in events {
+            let start = if event.all_day {
+                local_to_utc_lenient(self.timezone, event.start_datetime.naive_utc())
+            } else {
+                event.start_datetime
+            };
+            for &minutes_before in &event.reminders {
+                let remind_at = start - Duration::minutes(minutes_before);
+                if since < remind_at && remind_at <= now {
+                    due.push(DueReminder {
+                        event: event.clone(),
+                        minutes_before,
+                        remind_at,
+                    });
+                } else if now < remind_at {
+                    self.next_check = self.next_check.min(remind_at);
+                }
+            }
+        }
+        due.sort_by_key(|r| r.remind_at);
+        Ok(due)
+    }
+
+    /// Delivers due reminders to every notifier. A failing notifier does not
+    /// stop the others; the first error is returned.
+    pub fn tick(&mut self, conn: &Connection) -> Result<()> {
+        let mut result = Ok(());
+        for reminder in self.due(conn)? {
+            for notifier in self.notifiers.iter_mut() {
+                if let Err(err) = notifier.notify(&reminder) {
+                    if result.is_ok() {
+                        result = Err(err);
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::recurrence::Recurrence;
+    use crate::storage::db::{create_event, open_memory_db};
+    use chrono::TimeZone;
+    use std::cell::Cell;
+
+    #[derive(Clone)]
+    struct FakeClock(Rc<Cell<DateTime<Utc>>>);
+
+    impl FakeClock {
+        fn set(&self, now: DateTime<Utc>) {
+            self.0.set(now);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0.get()
+        }
+    }
+
+    struct RecordingNotifier(Rc<RefCell<Vec<String>>>);
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&mut self, reminder: &DueReminder) -> Result<()> {
+            self.0.borrow_mut().push(format!("{} {}", reminder.event.title, reminder.remind_at.format("%d %H:%M")));
+            Ok(())
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap()
+    }
+
+    fn add_event(conn: &Connection, title: &str, start: DateTime<Utc>, reminders: Vec<i64>) -> Event {
+        let event = Event {
+            title: title.to_string(),
+            start_datetime: start,
+            end_datetime: start + Duration::hours(1),
+            reminders,
+            ..Event::default()
+        };
+        Event {
+            id: Some(create_event(conn, &event).unwrap()),
+            ..event
+        }
+    }
+
+    fn scheduler(start: DateTime<Utc>) -> (FakeClock, ReminderScheduler<FakeClock>, Rc<RefCell<Vec<String>>>) {
+        let clock = FakeClock(Rc::new(Cell::new(start)));
+        let fired = Rc::new(RefCell::new(Vec::new()));
+        let notifier = RecordingNotifier(fired.clone());
+        let scheduler = ReminderScheduler::new(clock.clone(), Tz::UTC, vec![Box::new(notifier)]);
+        (clock, scheduler, fired)
+    }
+
+    #[test]
+    fn fires_once_at_the_reminder_time() {
+        let conn = open_memory_db();
+        add_event(&conn, "Standup", at(2, 9, 0), vec![10]);
+        let (clock, mut scheduler, fired) = scheduler(at(2, 8, 0));
+
+        for now in [at(2, 8, 30), at(2, 8, 49)] {
+            clock.set(now);
+            scheduler.tick(&conn).unwrap();
+        }
+        assert!(fired.borrow().is_empty());
+
+        clock.set(at(2, 8, 50));
+        scheduler.tick(&conn).unwrap();
+        assert_eq!(*fired.borrow(), vec!["Standup 02 08:50"]);
+
+        for now in [at(2, 8, 50), at(2, 8, 51), at(2, 9, 30)] {
+            clock.set(now);
+            scheduler.tick(&conn).unwrap();
+        }
+        assert_eq!(fired.borrow().len(), 1);
+    }
+
+    #[test]
+    fn fires_each_occurrence_of_a_series() {
+        let conn = open_memory_db();
+        let series = Event {
+            recurrence: Some("FREQ=DAILY".parse::<Recurrence>().unwrap()),
+            ..add_event(&conn, "Daily", at(2, 9, 0), vec![0])
+        };
+        crate::storage::db::update_event(&conn, &series).unwrap();
+        let (clock, mut scheduler, fired) = scheduler(at(4, 8, 0));
+
+        clock.set(at(4, 9, 0));
+        scheduler.tick(&conn).unwrap();
+        clock.set(at(5, 9, 0));
+        scheduler.tick(&conn).unwrap();
+        assert_eq!(*fired.borrow(), vec!["Daily 04 09:00", "Daily 05 09:00"]);
+    }
+
+    #[test]
+    fn looks_ahead_as_far_as_the_longest_reminder() {
+        let conn = open_memory_db();
+        add_event(&conn, "Trip", at(20, 9, 0), vec![7 * 24 * 60]);
+        add_event(&conn, "Later", at(31, 9, 0), vec![60]);
+        let (clock, mut scheduler, fired) = scheduler(at(13, 8, 0));
+
+        clock.set(at(13, 9, 0));
+        scheduler.tick(&conn).unwrap();
+        assert_eq!(*fired.borrow(), vec!["Trip 13 09:00"]);
+    }
+
+    #[test]
+    fn reads_the_database_at_most_once_a_minute() {
+        let conn = open_memory_db();
+        let (clock, mut scheduler, fired) = scheduler(at(2, 8, 0));
+        clock.set(at(2, 8, 0) + Duration::seconds(1));
+        scheduler.tick(&conn).unwrap();
+
+        // Added between checks: picked up by the next one, a minute later at most.
+        add_event(&conn, "Call", at(2, 8, 0) + Duration::seconds(20), vec![0]);
+        clock.set(at(2, 8, 0) + Duration::seconds(30));
+        scheduler.tick(&conn).unwrap();
+        assert!(fired.borrow().is_empty());
+
+        clock.set(at(2, 8, 1) + Duration::seconds(1));
+        scheduler.tick(&conn).unwrap();
+        assert_eq!(*fired.borrow(), vec!["Call 02 08:00"]);
+    }
+
+    #[test]
+    fn wakes_up_for_a_known_reminder_before_the_interval() {
+        let conn = open_memory_db();
+        add_event(&conn, "Call", at(2, 8, 0) + Duration::seconds(30), vec![0]);
+        let (clock, mut scheduler, _) = scheduler(at(2, 8, 0));
+        clock.set(at(2, 8, 0) + Duration::seconds(1));
+        assert!(scheduler.due(&conn).unwrap().is_empty());
+
+        clock.set(at(2, 8, 0) + Duration::seconds(30));
+        let due = scheduler.due(&conn).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].message(Tz::UTC, "%H:%M:%S"), "Now: Call (08:00:30)");
+    }
+}
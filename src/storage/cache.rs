@@ -0,0 +1,65 @@
+use crate::models::event::Event;
+use crate::models::tags::TagFilter;
+use crate::storage::db::get_events_in_range;
+use anyhow::Result;
+use rusqlite::Connection;
+use std::cell::{Cell, RefCell};
+
+/// Ranges kept; views query a handful per frame (the visible range, the
+/// focused event and the selected day).
+const CACHED_RANGES: usize = 8;
+
+struct CachedRange {
+    start: i64,
+    end: i64,
+    filter: TagFilter,
+    events: Vec<Event>,
+}
+
+/// Events of recently queried ranges, so redrawing does not hit SQLite. The
+/// owner calls `invalidate` after writing through its own connection;
+/// commits from other connections are noticed by `refresh`.
+#[derive(Default)]
+pub struct EventCache {
+    ranges: RefCell<Vec<CachedRange>>,
+    /// `PRAGMA data_version` when the ranges were loaded. It changes when
+    /// another connection commits, not on this connection's own writes.
+    data_version: Cell<Option<i64>>,
+}
+
+impl EventCache {
+    /// Like `get_events_in_range`, answered from memory for a range seen since the last invalidation.
+    pub fn events_in_range(&self, conn: &Connection, start: i64, end: i64, filter: &TagFilter) -> Result<Vec<Event>> {
+        if let Some(cached) =
+            self.ranges.borrow().iter().find(|r| r.start == start && r.end == end && r.filter == *filter)
+        {
+            return Ok(cached.events.clone());
+        }
+        let events = get_events_in_range(conn, start, end, filter)?;
+        let mut ranges = self.ranges.borrow_mut();
+        if ranges.len() == CACHED_RANGES {
+            ranges.remove(0);
+        }
+        ranges.push(CachedRange {
+            start,
+            end,
+            filter: filter.clone(),
+            events: events.clone(),
+        });
+        Ok(events)
+    }
+
+    pub fn invalidate(&self) {
+        self.ranges.borrow_mut().clear();
+    }
+
+    /// Drops the cached ranges when another process changed the database
+    /// since they were loaded.
+    pub fn refresh(&self, conn: &Connection) -> Result<()> {
+        let version: i64 = conn.query_row("PRAGMA data_version", [], |row| row.get(0))?;
+        if self.data_version.replace(Some(version)) != Some(version) {
+            self.invalidate();
+        }
+        Ok(())
+    }
+}
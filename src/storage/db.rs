@@ -1,9 +1,18 @@
+use crate::models::attendee::Attendee;
+use crate::models::calendar::Calendar;
 use crate::models::event::Event;
-use anyhow::{anyhow, Context, Result};
-use chrono::{TimeZone, Utc};
-use rusqlite::{types::Type, params, Connection, Error as RusqliteError};
+use crate::models::recurrence::{Recurrence, RecurrenceScope};
+use crate::models::tags::TagFilter;
+use crate::storage::migrations;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::{types::Type, params, Connection, Error as RusqliteError, OptionalExtension, Row};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+const EVENT_COLUMNS: &str =
+    "id, title, description, start_datetime, end_datetime, location, created_at, updated_at, rrule, uid, tzid, all_day, calendar_id";
 
 fn get_db_path() -> Result<PathBuf> {
     let data_dir = directories::ProjectDirs::from("com", "calendar-app", "calendar-app")
@@ -15,81 +24,395 @@ fn get_db_path() -> Result<PathBuf> {
 }
 
 pub fn initialize_db() -> Result<Connection> {
-    let db_path = get_db_path()?;
-    let conn = Connection::open(db_path)?;
-    conn.execute_batch(
-        "
-        CREATE TABLE IF NOT EXISTS events (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            title TEXT NOT NULL,
-            description TEXT,
-            start_datetime INTEGER NOT NULL,
-            end_datetime INTEGER NOT NULL,
-            location TEXT,
-            created_at INTEGER DEFAULT (strftime('%s', 'now')),
-            updated_at INTEGER DEFAULT (strftime('%s', 'now'))
-        );
-        CREATE INDEX IF NOT EXISTS idx_events_start ON events(start_datetime);
-        ",
-    )?;
+    open_db(&get_db_path()?)
+}
+
+/// Opens the database at `path`, migrating it to the current schema and
+/// keeping a copy of the previous version next to it when it changes.
+pub fn open_db(path: &Path) -> Result<Connection> {
+    let mut conn = Connection::open(path)?;
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    let version = migrations::schema_version(&conn)?;
+    migrations::migrate(&mut conn, Some(&migrations::backup_path(path, version)))?;
     Ok(conn)
 }
 
-pub fn get_events_in_range(conn: &Connection, start: i64, end: i64) -> Result<Vec<Event>> {
+/// A fresh in-memory database at the current schema.
+#[cfg(test)]
+pub fn open_memory_db() -> Connection {
+    let mut conn = Connection::open_in_memory().expect("in-memory database");
+    conn.execute_batch("PRAGMA foreign_keys = ON;").expect("foreign keys");
+    migrations::migrate(&mut conn, None).expect("migrations");
+    conn
+}
+
+fn timestamp_column(row: &Row, idx: usize) -> rusqlite::Result<DateTime<Utc>> {
+    let ts: i64 = row.get(idx)?;
+    Utc.timestamp_opt(ts, 0)
+        .single()
+        .ok_or_else(|| RusqliteError::InvalidColumnType(idx, "Invalid timestamp".into(), Type::Integer))
+}
+
+fn event_from_row(row: &Row) -> rusqlite::Result<Event> {
+    let rrule: Option<String> = row.get(8)?;
+    let recurrence = rrule
+        .map(|rule| rule.parse::<Recurrence>())
+        .transpose()
+        .map_err(|_| RusqliteError::InvalidColumnType(8, "Invalid recurrence rule".into(), Type::Text))?;
+
+    Ok(Event {
+        id: Some(row.get(0)?),
+        uid: row.get(9)?,
+        calendar_id: row.get(12)?,
+        title: row.get(1)?,
+        description: row.get(2)?,
+        start_datetime: timestamp_column(row, 3)?,
+        end_datetime: timestamp_column(row, 4)?,
+        timezone: row.get(10)?,
+        all_day: row.get(11)?,
+        location: row.get(5)?,
+        created_at: timestamp_column(row, 6)?,
+        updated_at: timestamp_column(row, 7)?,
+        recurrence,
+        exception_dates: Vec::new(),
+        reminders: Vec::new(),
+        tags: Vec::new(),
+        attendees: Vec::new(),
+        recurrence_id: None,
+    })
+}
+
+/// Runs `write` in a savepoint, so that a failure leaves no partial change
+/// behind. Savepoints nest, unlike transactions, so callers may still wrap
+/// several writes in a transaction of their own.
+fn atomically<T>(conn: &Connection, write: impl FnOnce() -> Result<T>) -> Result<T> {
+    conn.execute_batch("SAVEPOINT atomically")?;
+    match write() {
+        Ok(value) => {
+            conn.execute_batch("RELEASE atomically")?;
+            Ok(value)
+        }
+        Err(err) => {
+            conn.execute_batch("ROLLBACK TO atomically; RELEASE atomically")?;
+            Err(err)
+        }
+    }
+}
+
+/// Child rows of the events `ids`, grouped by event. `sql` selects the event
+/// id first and filters on `event_id IN ({ids})`.
+fn load_grouped<T>(
+    conn: &Connection,
+    sql: &str,
+    ids: &[i64],
+    value: impl Fn(&Row) -> rusqlite::Result<T>,
+) -> Result<HashMap<i64, Vec<T>>> {
+    let mut grouped: HashMap<i64, Vec<T>> = HashMap::new();
+    if ids.is_empty() {
+        return Ok(grouped);
+    }
+    let ids = ids.iter().map(i64::to_string).collect::<Vec<_>>().join(",");
+    let mut stmt = conn.prepare(&sql.replace("{ids}", &ids))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        grouped.entry(row.get(0)?).or_default().push(value(row)?);
+    }
+    Ok(grouped)
+}
+
+/// Fills in the tags of `events`, drops those not matching `filter` and fills
+/// in the other child rows of the rest, with one query per table.
+fn load_children(conn: &Connection, events: &mut Vec<Event>, filter: &TagFilter) -> Result<()> {
+    let ids: Vec<i64> = events.iter().filter_map(|e| e.id).collect();
+    let mut tags = load_tags(conn, &ids)?;
+    for event in events.iter_mut() {
+        event.tags = event.id.and_then(|id| tags.remove(&id)).unwrap_or_default();
+    }
+    events.retain(|event| filter.matches(&event.tags));
+
+    let ids: Vec<i64> = events.iter().filter_map(|e| e.id).collect();
+    let mut exception_dates = load_exception_dates(conn, &ids)?;
+    let mut reminders = load_reminders(conn, &ids)?;
+    let mut attendees = load_attendees(conn, &ids)?;
+    for event in events.iter_mut() {
+        if let Some(id) = event.id {
+            event.exception_dates = exception_dates.remove(&id).unwrap_or_default();
+            event.reminders = reminders.remove(&id).unwrap_or_default();
+            event.attendees = attendees.remove(&id).unwrap_or_default();
+        }
+    }
+    Ok(())
+}
+
+fn load_exception_dates(conn: &Connection, ids: &[i64]) -> Result<HashMap<i64, Vec<DateTime<Utc>>>> {
+    load_grouped(
+        conn,
+        "SELECT event_id, occurrence_start FROM event_exceptions WHERE event_id IN ({ids}) ORDER BY occurrence_start",
+        ids,
+        |row| timestamp_column(row, 1),
+    )
+}
+
+fn save_exception_dates(conn: &Connection, event_id: i64, dates: &[DateTime<Utc>]) -> Result<()> {
+    conn.execute("DELETE FROM event_exceptions WHERE event_id = ?1", params![event_id])?;
     let mut stmt = conn.prepare(
-        "SELECT id, title, description, start_datetime, end_datetime, location, created_at, updated_at
-         FROM events
-         WHERE start_datetime < ?2 AND end_datetime > ?1",
+        "INSERT OR IGNORE INTO event_exceptions (event_id, occurrence_start) VALUES (?1, ?2)",
+    )?;
+    for date in dates {
+        stmt.execute(params![event_id, date.timestamp()])?;
+    }
+    Ok(())
+}
+
+fn load_reminders(conn: &Connection, ids: &[i64]) -> Result<HashMap<i64, Vec<i64>>> {
+    load_grouped(
+        conn,
+        "SELECT event_id, minutes_before FROM reminders WHERE event_id IN ({ids}) ORDER BY minutes_before",
+        ids,
+        |row| row.get(1),
+    )
+}
+
+fn save_reminders(conn: &Connection, event_id: i64, reminders: &[i64]) -> Result<()> {
+    conn.execute("DELETE FROM reminders WHERE event_id = ?1", params![event_id])?;
+    let mut stmt =
+        conn.prepare("INSERT OR IGNORE INTO reminders (event_id, minutes_before) VALUES (?1, ?2)")?;
+    for minutes in reminders {
+        stmt.execute(params![event_id, minutes])?;
+    }
+    Ok(())
+}
+
+fn load_attendees(conn: &Connection, ids: &[i64]) -> Result<HashMap<i64, Vec<Attendee>>> {
+    load_grouped(
+        conn,
+        "SELECT event_id, name, email, role, status FROM attendees WHERE event_id IN ({ids}) ORDER BY position",
+        ids,
+        |row| {
+            // Values written by a newer build fall back to the defaults.
+            Ok(Attendee {
+                name: row.get(1)?,
+                email: row.get(2)?,
+                role: row.get::<_, String>(3)?.parse().unwrap_or_default(),
+                status: row.get::<_, String>(4)?.parse().unwrap_or_default(),
+            })
+        },
+    )
+}
+
+fn save_attendees(conn: &Connection, event_id: i64, attendees: &[Attendee]) -> Result<()> {
+    conn.execute("DELETE FROM attendees WHERE event_id = ?1", params![event_id])?;
+    let mut stmt = conn.prepare(
+        "INSERT OR IGNORE INTO attendees (event_id, position, name, email, role, status)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
     )?;
-    let event_iter = stmt.query_map([start, end], |row| {
-        let start_ts: i64 = row.get(3)?;
-        let end_ts: i64 = row.get(4)?;
-        let created_ts: i64 = row.get(6)?;
-        let updated_ts: i64 = row.get(7)?;
-
-        Ok(Event {
-            id: Some(row.get(0)?),
-            title: row.get(1)?,
-            description: row.get(2)?,
-            start_datetime: Utc
-                .timestamp_opt(start_ts, 0)
-                .single()
-                .ok_or_else(|| RusqliteError::InvalidColumnType(3, "Invalid timestamp".into(), Type::Integer))?,
-            end_datetime: Utc
-                .timestamp_opt(end_ts, 0)
-                .single()
-                .ok_or_else(|| RusqliteError::InvalidColumnType(4, "Invalid timestamp".into(), Type::Integer))?,
-            location: row.get(5)?,
-            created_at: Utc
-                .timestamp_opt(created_ts, 0)
-                .single()
-                .ok_or_else(|| RusqliteError::InvalidColumnType(6, "Invalid timestamp".into(), Type::Integer))?,
-            updated_at: Utc
-                .timestamp_opt(updated_ts, 0)
-                .single()
-                .ok_or_else(|| RusqliteError::InvalidColumnType(7, "Invalid timestamp".into(), Type::Integer))?,
+    for (position, attendee) in attendees.iter().enumerate() {
+        stmt.execute(params![
+            event_id,
+            position as i64,
+            attendee.name,
+            attendee.email,
+            attendee.role.name(),
+            attendee.status.name(),
+        ])?;
+    }
+    Ok(())
+}
+
+fn load_tags(conn: &Connection, ids: &[i64]) -> Result<HashMap<i64, Vec<String>>> {
+    load_grouped(
+        conn,
+        "SELECT event_tags.event_id, tags.name FROM event_tags JOIN tags ON tags.id = event_tags.tag_id
+         WHERE event_tags.event_id IN ({ids}) ORDER BY tags.name",
+        ids,
+        |row| row.get(1),
+    )
+}
+
+fn save_tags(conn: &Connection, event_id: i64, tags: &[String]) -> Result<()> {
+    conn.execute("DELETE FROM event_tags WHERE event_id = ?1", params![event_id])?;
+    for tag in tags {
+        conn.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", params![tag])?;
+        conn.execute(
+            "INSERT OR IGNORE INTO event_tags (event_id, tag_id) SELECT ?1, id FROM tags WHERE name = ?2",
+            params![event_id, tag],
+        )?;
+    }
+    prune_tags(conn)
+}
+
+/// Drops tags no event uses any more, so they stop being suggested.
+fn prune_tags(conn: &Connection) -> Result<()> {
+    conn.execute("DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM event_tags)", [])?;
+    Ok(())
+}
+
+/// Every tag in use, alphabetically.
+pub fn get_tags(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM tags ORDER BY name")?;
+    let tags = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(tags)
+}
+
+/// Keeps the full-text index row of event `id` in step with its text fields.
+fn index_event(conn: &Connection, id: i64, event: &Event) -> Result<()> {
+    conn.execute("DELETE FROM events_fts WHERE rowid = ?1", params![id])?;
+    conn.execute(
+        "INSERT INTO events_fts (rowid, title, description, location) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            id,
+            event.title,
+            event.description.as_deref().unwrap_or(""),
+            event.location.as_deref().unwrap_or(""),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Events whose title, description or location match every word of `query`
+/// as a prefix, best matches first. Recurring series are returned unexpanded.
+pub fn search_events(conn: &Connection, query: &str, limit: usize) -> Result<Vec<Event>> {
+    // Quote each word so FTS5 operators in user input are taken literally.
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}
+         FROM events
+         JOIN (SELECT rowid AS hit, rank FROM events_fts WHERE events_fts MATCH ?1) ON hit = events.id
+         ORDER BY rank
+         LIMIT ?2",
+        EVENT_COLUMNS
+    ))?;
+    let events = stmt
+        .query_map(params![terms.join(" "), limit as i64], event_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(events)
+}
+
+/// Largest reminder offset of any event, i.e. how far ahead the scheduler must look.
+pub fn max_reminder_minutes(conn: &Connection) -> Result<Option<i64>> {
+    Ok(conn.query_row("SELECT MAX(minutes_before) FROM reminders", [], |row| row.get(0))?)
+}
+
+/// Expands a recurring event into the occurrences overlapping `[start, end)`.
+fn expand_occurrences(event: &Event, start: i64, end: i64) -> Vec<Event> {
+    let Some(recurrence) = &event.recurrence else {
+        return vec![event.clone()];
+    };
+    let (Some(window_start), Some(window_end)) = (
+        Utc.timestamp_opt(start, 0).single(),
+        Utc.timestamp_opt(end, 0).single(),
+    ) else {
+        return Vec::new();
+    };
+    let duration = event.end_datetime - event.start_datetime;
+
+    recurrence
+        .occurrences(event.local_start(), duration, window_start, window_end)
+        .into_iter()
+        .filter(|occurrence| !event.exception_dates.contains(occurrence))
+        .map(|occurrence| Event {
+            start_datetime: occurrence,
+            end_datetime: occurrence + duration,
+            recurrence_id: Some(occurrence),
+            ..event.clone()
         })
-    })?;
+        .collect()
+}
+
+pub fn get_event(conn: &Connection, id: i64) -> Result<Option<Event>> {
+    let event = conn
+        .query_row(&format!("SELECT {} FROM events WHERE id = ?1", EVENT_COLUMNS), [id], event_from_row)
+        .optional()?;
+    let mut events: Vec<Event> = event.into_iter().collect();
+    load_children(conn, &mut events, &TagFilter::default())?;
+    Ok(events.pop())
+}
 
-    let mut events = Vec::new();
-    for event in event_iter {
-        events.push(event?);
+pub fn get_event_by_uid(conn: &Connection, uid: &str) -> Result<Option<Event>> {
+    let id: Option<i64> = conn
+        .query_row("SELECT id FROM events WHERE uid = ?1", [uid], |row| row.get(0))
+        .optional()?;
+    match id {
+        Some(id) => get_event(conn, id),
+        None => Ok(None),
     }
+}
+
+/// Every stored event, with recurring series left unexpanded.
+pub fn get_all_events(conn: &Connection) -> Result<Vec<Event>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM events ORDER BY start_datetime", EVENT_COLUMNS))?;
+    let mut events = stmt
+        .query_map([], event_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    load_children(conn, &mut events, &TagFilter::default())?;
+    Ok(events)
+}
+
+/// Occurrences overlapping `[start, end)` of the events whose tags match `filter`.
+pub fn get_events_in_range(conn: &Connection, start: i64, end: i64, filter: &TagFilter) -> Result<Vec<Event>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}
+         FROM events
+         WHERE start_datetime < ?2 AND (end_datetime > ?1 OR rrule IS NOT NULL)",
+        EVENT_COLUMNS
+    ))?;
+    let mut series = stmt
+        .query_map([start, end], event_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    load_children(conn, &mut series, filter)?;
+
+    let mut events: Vec<Event> = series.iter().flat_map(|event| expand_occurrences(event, start, end)).collect();
+    events.sort_by_key(|e| e.start_datetime);
     Ok(events)
 }
 
 pub fn create_event(conn: &Connection, event: &Event) -> Result<i64> {
+    insert_event(conn, None, event)
+}
+
+/// Re-creates a deleted event under its original id.
+pub fn restore_event(conn: &Connection, event: &Event) -> Result<i64> {
+    let id = event
+        .id
+        .ok_or_else(|| anyhow!("Cannot restore event without ID"))?;
+    insert_event(conn, Some(id), event)
+}
+
+fn insert_event(conn: &Connection, id: Option<i64>, event: &Event) -> Result<i64> {
+    let calendar_id = match event.calendar_id {
+        Some(id) => id,
+        None => default_calendar_id(conn)?,
+    };
     let mut stmt = conn.prepare(
-        "INSERT INTO events (title, description, start_datetime, end_datetime, location)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
+        "INSERT INTO events (id, title, description, start_datetime, end_datetime, location, rrule, uid, tzid, all_day, calendar_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
     )?;
     let id = stmt.insert(params![
+        id,
         event.title,
         event.description,
         event.start_datetime.timestamp(),
         event.end_datetime.timestamp(),
         event.location,
+        event.recurrence.as_ref().map(|r| r.to_string()),
+        event.uid,
+        event.timezone,
+        event.all_day,
+        calendar_id,
     ])?;
+    save_exception_dates(conn, id, &event.exception_dates)?;
+    save_reminders(conn, id, &event.reminders)?;
+    save_tags(conn, id, &event.tags)?;
+    save_attendees(conn, id, &event.attendees)?;
+    index_event(conn, id, event)?;
     Ok(id)
 }
 
@@ -97,21 +420,477 @@ pub fn update_event(conn: &Connection, event: &Event) -> Result<()> {
     let id = event
         .id
         .ok_or_else(|| anyhow!("Cannot update event without ID"))?;
+    let calendar_id = match event.calendar_id {
+        Some(id) => id,
+        None => default_calendar_id(conn)?,
+    };
     conn.execute(
-        "UPDATE events SET title = ?1, description = ?2, start_datetime = ?3, end_datetime = ?4, location = ?5, updated_at = strftime('%s', 'now') WHERE id = ?6",
+        "UPDATE events SET title = ?1, description = ?2, start_datetime = ?3, end_datetime = ?4, location = ?5, rrule = ?6, tzid = ?7, all_day = ?8, calendar_id = ?9, updated_at = strftime('%s', 'now') WHERE id = ?10",
         params![
             event.title,
             event.description,
             event.start_datetime.timestamp(),
             event.end_datetime.timestamp(),
             event.location,
+            event.recurrence.as_ref().map(|r| r.to_string()),
+            event.timezone,
+            event.all_day,
+            calendar_id,
             id,
         ],
     )?;
+    save_exception_dates(conn, id, &event.exception_dates)?;
+    save_reminders(conn, id, &event.reminders)?;
+    save_tags(conn, id, &event.tags)?;
+    save_attendees(conn, id, &event.attendees)?;
+    index_event(conn, id, event)?;
+    mark_dirty(conn, id)?;
+    Ok(())
+}
+
+/// Flags a synced event as changed locally so the next sync uploads it.
+fn mark_dirty(conn: &Connection, event_id: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE caldav_resources SET dirty = 1 WHERE event_id = ?1 AND is_override = 0",
+        params![event_id],
+    )?;
     Ok(())
 }
 
 pub fn delete_event(conn: &Connection, id: i64) -> Result<()> {
-    conn.execute("DELETE FROM events WHERE id = ?1", params![id])?;
+    atomically(conn, || {
+        conn.execute("DELETE FROM event_exceptions WHERE event_id = ?1", params![id])?;
+        conn.execute("DELETE FROM reminders WHERE event_id = ?1", params![id])?;
+        conn.execute("DELETE FROM event_tags WHERE event_id = ?1", params![id])?;
+        conn.execute("DELETE FROM attendees WHERE event_id = ?1", params![id])?;
+        conn.execute("DELETE FROM events_fts WHERE rowid = ?1", params![id])?;
+        conn.execute("DELETE FROM events WHERE id = ?1", params![id])?;
+        prune_tags(conn)
+    })
+}
+
+pub fn set_event_uid(conn: &Connection, id: i64, uid: &str) -> Result<()> {
+    conn.execute("UPDATE events SET uid = ?1 WHERE id = ?2", params![uid, id])?;
+    Ok(())
+}
+
+/// Excludes a single occurrence from a recurring series.
+pub fn add_exception_date(conn: &Connection, event_id: i64, occurrence_start: DateTime<Utc>) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO event_exceptions (event_id, occurrence_start) VALUES (?1, ?2)",
+        params![event_id, occurrence_start.timestamp()],
+    )?;
+    mark_dirty(conn, event_id)
+}
+
+/// Applies `edited` to the occurrence of a recurring series that originally
+/// started at `recurrence_id`. Returns the id of the event split off the
+/// series, if any.
+pub fn update_occurrences(
+    conn: &Connection,
+    edited: &Event,
+    recurrence_id: DateTime<Utc>,
+    scope: RecurrenceScope,
+) -> Result<Option<i64>> {
+    atomically(conn, || {
+        let id = edited
+            .id
+            .ok_or_else(|| anyhow!("Cannot update event without ID"))?;
+        let mut series = get_event(conn, id)?.ok_or_else(|| anyhow!("Event {} not found", id))?;
+        let delta = edited.start_datetime - recurrence_id;
+        let duration = edited.end_datetime - edited.start_datetime;
+
+        let scope = match scope {
+            RecurrenceScope::ThisAndFollowing if recurrence_id <= series.start_datetime => {
+                RecurrenceScope::AllOccurrences
+            }
+            scope => scope,
+        };
+
+        match scope {
+            RecurrenceScope::ThisOccurrence => {
+                add_exception_date(conn, id, recurrence_id)?;
+                let created = create_event(
+                    conn,
+                    &Event {
+                        id: None,
+                        uid: None,
+                        recurrence: None,
+                        exception_dates: Vec::new(),
+                        recurrence_id: None,
+                        ..edited.clone()
+                    },
+                )?;
+                Ok(Some(created))
+            }
+            RecurrenceScope::ThisAndFollowing => {
+                let mut recurrence = edited.recurrence.clone();
+                let series_start = series.local_start();
+                if let Some(series_rule) = series.recurrence.as_mut() {
+                    let original_count = series_rule.count;
+                    let prior = series_rule.count_before(series_start, recurrence_id);
+                    series_rule.truncate_before(series_start, recurrence_id);
+                    if let Some(rule) = recurrence.as_mut() {
+                        if rule.count.is_some() && rule.count == original_count {
+                            rule.count = rule.count.map(|c| c.saturating_sub(prior).max(1));
+                        }
+                    }
+                }
+                let exception_dates = series
+                    .exception_dates
+                    .iter()
+                    .filter(|d| **d >= recurrence_id)
+                    .map(|d| *d + delta)
+                    .collect();
+                series.exception_dates.retain(|d| *d < recurrence_id);
+                update_event(conn, &series)?;
+                let created = create_event(
+                    conn,
+                    &Event {
+                        id: None,
+                        uid: None,
+                        recurrence,
+                        exception_dates,
+                        recurrence_id: None,
+                        ..edited.clone()
+                    },
+                )?;
+                Ok(Some(created))
+            }
+            RecurrenceScope::AllOccurrences => {
+                series.title = edited.title.clone();
+                series.description = edited.description.clone();
+                series.location = edited.location.clone();
+                series.all_day = edited.all_day;
+                series.reminders = edited.reminders.clone();
+                series.tags = edited.tags.clone();
+                series.attendees = edited.attendees.clone();
+                series.calendar_id = edited.calendar_id;
+                series.recurrence = edited.recurrence.clone();
+                series.start_datetime += delta;
+                series.end_datetime = series.start_datetime + duration;
+                for date in series.exception_dates.iter_mut() {
+                    *date += delta;
+                }
+                update_event(conn, &series)?;
+                Ok(None)
+            }
+        }
+    })
+}
+
+/// Deletes the occurrence of `occurrence`'s series selected by `scope`.
+pub fn delete_occurrences(conn: &Connection, occurrence: &Event, scope: RecurrenceScope) -> Result<()> {
+    atomically(conn, || {
+        let id = occurrence
+            .id
+            .ok_or_else(|| anyhow!("Cannot delete event without ID"))?;
+        let Some(recurrence_id) = occurrence.recurrence_id else {
+            return delete_event(conn, id);
+        };
+
+        match scope {
+            RecurrenceScope::ThisOccurrence => add_exception_date(conn, id, recurrence_id),
+            RecurrenceScope::ThisAndFollowing => {
+                let mut series = get_event(conn, id)?.ok_or_else(|| anyhow!("Event {} not found", id))?;
+                if recurrence_id <= series.start_datetime {
+                    return delete_event(conn, id);
+                }
+                let series_start = series.local_start();
+                if let Some(rule) = series.recurrence.as_mut() {
+                    rule.truncate_before(series_start, recurrence_id);
+                }
+                series.exception_dates.retain(|d| *d < recurrence_id);
+                update_event(conn, &series)
+            }
+            RecurrenceScope::AllOccurrences => delete_event(conn, id),
+        }
+    })
+}
+
+fn calendar_from_row(row: &Row) -> rusqlite::Result<Calendar> {
+    Ok(Calendar {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        // An empty color, which the column stores for `None`, leaves the choice to the theme.
+        color: Some(row.get::<_, String>(2)?).filter(|color| !color.is_empty()),
+        visible: row.get(3)?,
+    })
+}
+
+pub fn get_calendars(conn: &Connection) -> Result<Vec<Calendar>> {
+    let mut stmt = conn.prepare("SELECT id, name, color, visible FROM calendars ORDER BY id")?;
+    let calendars = stmt
+        .query_map([], calendar_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(calendars)
+}
+
+pub fn get_calendar_by_name(conn: &Connection, name: &str) -> Result<Option<Calendar>> {
+    Ok(conn
+        .query_row(
+            "SELECT id, name, color, visible FROM calendars WHERE name = ?1 COLLATE NOCASE",
+            [name],
+            calendar_from_row,
+        )
+        .optional()?)
+}
+
+/// Calendar that events without an explicit one are filed under.
+fn default_calendar_id(conn: &Connection) -> Result<i64> {
+    conn.query_row("SELECT MIN(id) FROM calendars", [], |row| row.get::<_, Option<i64>>(0))?
+        .ok_or_else(|| anyhow!("No calendars defined"))
+}
+
+pub fn create_calendar(conn: &Connection, calendar: &Calendar) -> Result<i64> {
+    let mut stmt = conn.prepare("INSERT INTO calendars (name, color, visible) VALUES (?1, ?2, ?3)")?;
+    Ok(stmt.insert(params![calendar.name, calendar.color.as_deref().unwrap_or(""), calendar.visible])?)
+}
+
+pub fn update_calendar(conn: &Connection, calendar: &Calendar) -> Result<()> {
+    let id = calendar
+        .id
+        .ok_or_else(|| anyhow!("Cannot update calendar without ID"))?;
+    conn.execute(
+        "UPDATE calendars SET name = ?1, color = ?2, visible = ?3 WHERE id = ?4",
+        params![calendar.name, calendar.color.as_deref().unwrap_or(""), calendar.visible, id],
+    )?;
     Ok(())
 }
+
+/// Deletes a calendar together with its events.
+pub fn delete_calendar(conn: &Connection, id: i64) -> Result<()> {
+    let remaining: i64 = conn.query_row("SELECT COUNT(*) FROM calendars WHERE id != ?1", [id], |row| row.get(0))?;
+    if remaining == 0 {
+        bail!("Cannot delete the last calendar");
+    }
+    conn.execute(
+        "DELETE FROM events_fts WHERE rowid IN (SELECT id FROM events WHERE calendar_id = ?1)",
+        params![id],
+    )?;
+    conn.execute("DELETE FROM calendars WHERE id = ?1", params![id])?;
+    prune_tags(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+    }
+
+    fn add_event(conn: &Connection, title: &str, start: DateTime<Utc>, tags: &[&str]) -> i64 {
+        let event = Event {
+            title: title.to_string(),
+            start_datetime: start,
+            end_datetime: start + Duration::hours(1),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Event::default()
+        };
+        create_event(conn, &event).unwrap()
+    }
+
+    #[test]
+    fn loads_the_child_rows_of_each_event_in_range() {
+        let conn = open_memory_db();
+        let standup = add_event(&conn, "Standup", at(2, 9), &["work"]);
+        let mut event = get_event(&conn, standup).unwrap().unwrap();
+        event.recurrence = Some("FREQ=DAILY".parse().unwrap());
+        event.reminders = vec![5, 15];
+        event.attendees = vec![Attendee {
+            name: Some("Ana".to_string()),
+            email: "ana@example.com".to_string(),
+            role: Default::default(),
+            status: Default::default(),
+        }];
+        update_event(&conn, &event).unwrap();
+        add_exception_date(&conn, standup, at(3, 9)).unwrap();
+        add_event(&conn, "Dentist", at(3, 12), &["health", "personal"]);
+        add_event(&conn, "Gym", at(4, 18), &[]);
+
+        let events = get_events_in_range(&conn, at(2, 0).timestamp(), at(5, 0).timestamp(), &TagFilter::default())
+            .unwrap();
+        let titles: Vec<_> = events.iter().map(|e| (e.title.as_str(), e.start_datetime)).collect();
+        assert_eq!(
+            titles,
+            [
+                ("Standup", at(2, 9)),
+                ("Dentist", at(3, 12)),
+                ("Standup", at(4, 9)),
+                ("Gym", at(4, 18)),
+            ]
+        );
+        assert_eq!(events[0].reminders, [5, 15]);
+        assert_eq!(events[0].attendees, event.attendees);
+        assert_eq!(events[0].exception_dates, [at(3, 9)]);
+        assert_eq!(events[1].tags, ["health", "personal"]);
+        assert!(events[3].tags.is_empty() && events[3].reminders.is_empty());
+
+        let filter: TagFilter = "work or health".parse().unwrap();
+        let events = get_events_in_range(&conn, at(2, 0).timestamp(), at(5, 0).timestamp(), &filter).unwrap();
+        let titles: Vec<_> = events.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["Standup", "Dentist", "Standup"]);
+    }
+
+    #[test]
+    fn leaves_the_series_alone_when_a_split_fails() {
+        let conn = open_memory_db();
+        let id = add_event(&conn, "Standup", at(2, 9), &[]);
+        let mut series = get_event(&conn, id).unwrap().unwrap();
+        series.recurrence = Some("FREQ=DAILY".parse().unwrap());
+        update_event(&conn, &series).unwrap();
+
+        // The split-off event names a calendar that does not exist, so inserting it fails.
+        let edited = Event {
+            calendar_id: Some(999),
+            start_datetime: at(4, 10),
+            end_datetime: at(4, 11),
+            recurrence_id: Some(at(4, 9)),
+            ..series.clone()
+        };
+        for scope in RecurrenceScope::ALL {
+            assert!(update_occurrences(&conn, &edited, at(4, 9), scope).is_err());
+            let stored = get_event(&conn, id).unwrap().unwrap();
+            assert!(stored.exception_dates.is_empty());
+            assert_eq!(stored.recurrence, series.recurrence);
+            assert_eq!(get_all_events(&conn).unwrap().len(), 1);
+        }
+
+        // Inside a caller's transaction the writes nest and commit with it.
+        let tx = conn.unchecked_transaction().unwrap();
+        delete_occurrences(&tx, &Event { recurrence_id: Some(at(4, 9)), ..series }, RecurrenceScope::ThisOccurrence)
+            .unwrap();
+        tx.commit().unwrap();
+        assert_eq!(get_event(&conn, id).unwrap().unwrap().exception_dates, [at(4, 9)]);
+    }
+
+    fn add_series(conn: &Connection, rule: &str) -> Event {
+        let id = add_event(conn, "Standup", at(2, 9), &["work"]);
+        let mut series = get_event(conn, id).unwrap().unwrap();
+        series.recurrence = Some(rule.parse().unwrap());
+        series.reminders = vec![10];
+        update_event(conn, &series).unwrap();
+        get_event(conn, id).unwrap().unwrap()
+    }
+
+    fn occurrence(series: &Event, start: DateTime<Utc>) -> Event {
+        Event {
+            start_datetime: start,
+            end_datetime: start + Duration::hours(1),
+            recurrence_id: Some(start),
+            ..series.clone()
+        }
+    }
+
+    /// Titles and starts of the occurrences in March.
+    fn march(conn: &Connection) -> Vec<(String, DateTime<Utc>)> {
+        get_events_in_range(conn, at(1, 0).timestamp(), at(31, 0).timestamp(), &TagFilter::default())
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.title, e.start_datetime))
+            .collect()
+    }
+
+    fn standup(days: &[u32]) -> Vec<(String, DateTime<Utc>)> {
+        days.iter().map(|day| ("Standup".to_string(), at(*day, 9))).collect()
+    }
+
+    #[test]
+    fn edits_a_single_occurrence() {
+        let conn = open_memory_db();
+        let series = add_series(&conn, "FREQ=DAILY;COUNT=4");
+        let edited = Event {
+            title: "Moved".to_string(),
+            start_datetime: at(3, 11),
+            end_datetime: at(3, 12),
+            ..occurrence(&series, at(3, 9))
+        };
+        let created = update_occurrences(&conn, &edited, at(3, 9), RecurrenceScope::ThisOccurrence).unwrap();
+
+        let mut expected = standup(&[2, 4, 5]);
+        expected.insert(1, ("Moved".to_string(), at(3, 11)));
+        assert_eq!(march(&conn), expected);
+        let created = get_event(&conn, created.unwrap()).unwrap().unwrap();
+        assert!(created.recurrence.is_none());
+        assert_eq!(created.tags, ["work"]);
+        assert_eq!(get_event(&conn, series.id.unwrap()).unwrap().unwrap().exception_dates, [at(3, 9)]);
+    }
+
+    #[test]
+    fn splits_a_series_at_an_edited_occurrence() {
+        let conn = open_memory_db();
+        let mut series = add_series(&conn, "FREQ=DAILY;COUNT=5");
+        series.exception_dates = vec![at(5, 9)];
+        update_event(&conn, &series).unwrap();
+
+        let edited = Event {
+            title: "Later".to_string(),
+            start_datetime: at(4, 10),
+            end_datetime: at(4, 11),
+            ..occurrence(&series, at(4, 9))
+        };
+        let created = update_occurrences(&conn, &edited, at(4, 9), RecurrenceScope::ThisAndFollowing)
+            .unwrap()
+            .unwrap();
+
+        let mut expected = standup(&[2, 3]);
+        expected.extend([("Later".to_string(), at(4, 10)), ("Later".to_string(), at(6, 10))]);
+        assert_eq!(march(&conn), expected);
+        let series = get_event(&conn, series.id.unwrap()).unwrap().unwrap();
+        assert_eq!(series.recurrence.as_ref().unwrap().to_string(), "FREQ=DAILY;COUNT=2");
+        assert!(series.exception_dates.is_empty());
+        let created = get_event(&conn, created).unwrap().unwrap();
+        assert_eq!(created.recurrence.unwrap().to_string(), "FREQ=DAILY;COUNT=3");
+        assert_eq!(created.exception_dates, [at(5, 10)]);
+
+        // Splitting at the first occurrence edits the whole series instead.
+        let first = Event { title: "Renamed".to_string(), ..occurrence(&series, at(2, 9)) };
+        assert!(update_occurrences(&conn, &first, at(2, 9), RecurrenceScope::ThisAndFollowing).unwrap().is_none());
+        assert_eq!(march(&conn)[..2], [("Renamed".to_string(), at(2, 9)), ("Renamed".to_string(), at(3, 9))]);
+    }
+
+    #[test]
+    fn edits_every_occurrence() {
+        let conn = open_memory_db();
+        let mut series = add_series(&conn, "FREQ=DAILY;COUNT=3");
+        series.exception_dates = vec![at(3, 9)];
+        update_event(&conn, &series).unwrap();
+
+        // Moving the third occurrence by an hour moves the whole series.
+        let edited = Event {
+            title: "Sync".to_string(),
+            start_datetime: at(4, 10),
+            end_datetime: at(4, 10) + Duration::minutes(30),
+            ..occurrence(&series, at(4, 9))
+        };
+        assert!(update_occurrences(&conn, &edited, at(4, 9), RecurrenceScope::AllOccurrences).unwrap().is_none());
+        assert_eq!(march(&conn), [("Sync".to_string(), at(2, 10)), ("Sync".to_string(), at(4, 10))]);
+        let series = get_event(&conn, series.id.unwrap()).unwrap().unwrap();
+        assert_eq!(series.exception_dates, [at(3, 10)]);
+        assert_eq!(series.end_datetime - series.start_datetime, Duration::minutes(30));
+        assert_eq!(get_all_events(&conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn deletes_occurrences_by_scope() {
+        let conn = open_memory_db();
+        let series = add_series(&conn, "FREQ=DAILY");
+        let id = series.id.unwrap();
+
+        delete_occurrences(&conn, &occurrence(&series, at(3, 9)), RecurrenceScope::ThisOccurrence).unwrap();
+        delete_occurrences(&conn, &occurrence(&series, at(6, 9)), RecurrenceScope::ThisAndFollowing).unwrap();
+        assert_eq!(march(&conn), standup(&[2, 4, 5]));
+        let stored = get_event(&conn, id).unwrap().unwrap();
+        assert_eq!(stored.exception_dates, [at(3, 9)]);
+        assert_eq!(stored.recurrence.unwrap().until, Some(at(6, 9) - Duration::seconds(1)));
+
+        delete_occurrences(&conn, &occurrence(&series, at(4, 9)), RecurrenceScope::AllOccurrences).unwrap();
+        assert!(march(&conn).is_empty());
+        for table in ["event_exceptions", "reminders", "event_tags", "tags"] {
+            let rows: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap();
+            assert_eq!(rows, 0, "{}", table);
+        }
+    }
+}
@@ -0,0 +1,427 @@
+use anyhow::{bail, Context, Result};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+
+/// One schema change. Step `n` in `MIGRATIONS` moves the database from
+/// `user_version` `n` to `n + 1`.
+struct Migration {
+    description: &'static str,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// Ordered schema history. Never edit or reorder a released step; append a new one.
+///
+/// Databases written before versioning was introduced have `user_version` 0 and
+/// may already contain some of these columns, so the early steps only add what
+/// is missing.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "create events",
+        apply: |conn| {
+            conn.execute_batch(
+                "
+                CREATE TABLE IF NOT EXISTS events (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    title TEXT NOT NULL,
+                    description TEXT,
+                    start_datetime INTEGER NOT NULL,
+                    end_datetime INTEGER NOT NULL,
+                    location TEXT,
+                    created_at INTEGER DEFAULT (strftime('%s', 'now')),
+                    updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+                );
+                CREATE INDEX IF NOT EXISTS idx_events_start ON events(start_datetime);
+                ",
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        description: "add recurrence rules and exception dates",
+        apply: |conn| {
+            ensure_column(conn, "events", "rrule", "TEXT")?;
+            conn.execute_batch(
+                "
+                CREATE TABLE IF NOT EXISTS event_exceptions (
+                    event_id INTEGER NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+                    occurrence_start INTEGER NOT NULL,
+                    PRIMARY KEY (event_id, occurrence_start)
+                );
+                ",
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        description: "add iCalendar UIDs",
+        apply: |conn| {
+            ensure_column(conn, "events", "uid", "TEXT")?;
+            conn.execute_batch("CREATE UNIQUE INDEX IF NOT EXISTS idx_events_uid ON events(uid);")?;
+            Ok(())
+        },
+    },
+    Migration {
+        description: "add event timezones",
+        apply: |conn| ensure_column(conn, "events", "tzid", "TEXT"),
+    },
+    Migration {
+        description: "add all-day events",
+        apply: |conn| ensure_column(conn, "events", "all_day", "INTEGER NOT NULL DEFAULT 0"),
+    },
+    Migration {
+        description: "add reminders",
+        apply: |conn| {
+            conn.execute_batch(
+                "
+                CREATE TABLE IF NOT EXISTS reminders (
+                    event_id INTEGER NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+                    minutes_before INTEGER NOT NULL,
+                    PRIMARY KEY (event_id, minutes_before)
+                );
+                ",
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        description: "add calendars",
+        apply: |conn| {
+            conn.execute_batch(
+                "
+                CREATE TABLE IF NOT EXISTS calendars (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE,
+                    color TEXT NOT NULL DEFAULT 'cyan',
+                    visible INTEGER NOT NULL DEFAULT 1
+                );
+                ",
+            )?;
+            ensure_column(conn, "events", "calendar_id", "INTEGER REFERENCES calendars(id) ON DELETE CASCADE")?;
+            conn.execute_batch(
+                "
+                INSERT INTO calendars (name) SELECT 'Personal' WHERE NOT EXISTS (SELECT 1 FROM calendars);
+                UPDATE events SET calendar_id = (SELECT MIN(id) FROM calendars) WHERE calendar_id IS NULL;
+                ",
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        description: "add full-text search",
+        apply: |conn| {
+            conn.execute_batch(
+                "
+                CREATE VIRTUAL TABLE IF NOT EXISTS events_fts USING fts5(title, description, location);
+                INSERT INTO events_fts (rowid, title, description, location)
+                    SELECT id, title, COALESCE(description, ''), COALESCE(location, '') FROM events;
+                ",
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        description: "add CalDAV sync state",
+        apply: |conn| {
+            conn.execute_batch(
+                "
+                CREATE TABLE caldav_calendars (
+                    calendar_id INTEGER PRIMARY KEY REFERENCES calendars(id) ON DELETE CASCADE,
+                    href TEXT NOT NULL UNIQUE,
+                    ctag TEXT
+                );
+                CREATE TABLE caldav_resources (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    calendar_id INTEGER NOT NULL REFERENCES calendars(id) ON DELETE CASCADE,
+                    href TEXT NOT NULL,
+                    event_id INTEGER UNIQUE REFERENCES events(id) ON DELETE SET NULL,
+                    etag TEXT,
+                    is_override INTEGER NOT NULL DEFAULT 0,
+                    dirty INTEGER NOT NULL DEFAULT 0
+                );
+                CREATE INDEX idx_caldav_resources_href ON caldav_resources(href);
+                ",
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        description: "add tags",
+        apply: |conn| {
+            conn.execute_batch(
+                "
+                CREATE TABLE tags (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE COLLATE NOCASE
+                );
+                CREATE TABLE event_tags (
+                    event_id INTEGER NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+                    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+                    PRIMARY KEY (event_id, tag_id)
+                );
+                CREATE INDEX idx_event_tags_tag ON event_tags(tag_id);
+                ",
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        description: "add attendees",
+        apply: |conn| {
+            conn.execute_batch(
+                "
+                CREATE TABLE attendees (
+                    event_id INTEGER NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+                    position INTEGER NOT NULL,
+                    name TEXT,
+                    email TEXT NOT NULL COLLATE NOCASE,
+                    role TEXT NOT NULL DEFAULT 'required',
+                    status TEXT NOT NULL DEFAULT 'needs-action',
+                    PRIMARY KEY (event_id, email)
+                );
+                ",
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        description: "leave default calendar colors to the theme",
+        // Calendars that never had a color chosen got the column default;
+        // an empty color now means the theme's event color.
+        apply: |conn| {
+            conn.execute("UPDATE calendars SET color = '' WHERE color = 'cyan'", [])?;
+            Ok(())
+        },
+    },
+];
+
+/// Schema version written by this build.
+pub fn latest_version() -> i64 {
+    MIGRATIONS.len() as i64
+}
+
+pub fn schema_version(conn: &Connection) -> Result<i64> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Brings the database up to `latest_version`, one transaction per step. When
+/// `backup_path` is given and an existing database is about to change, a copy
+/// is written there first.
+pub fn migrate(conn: &mut Connection, backup_path: Option<&Path>) -> Result<()> {
+    let version = schema_version(conn)?;
+    if version > latest_version() {
+        bail!(
+            "Database schema version {} is newer than this build supports ({}); please upgrade",
+            version,
+            latest_version()
+        );
+    }
+    if version == latest_version() {
+        return Ok(());
+    }
+
+    if let Some(path) = backup_path {
+        if has_tables(conn)? {
+            backup(conn, path)?;
+        }
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let tx = conn.transaction()?;
+        (migration.apply)(&tx).with_context(|| format!("Migration {} ({}) failed", index + 1, migration.description))?;
+        tx.pragma_update(None, "user_version", index as i64 + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Where the pre-migration copy of `db_path` goes, e.g. `events.db.v3.bak`.
+pub fn backup_path(db_path: &Path, version: i64) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    db_path.with_file_name(name)
+}
+
+fn has_tables(conn: &Connection) -> Result<bool> {
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'", [], |row| {
+        row.get(0)
+    })?;
+    Ok(count > 0)
+}
+
+fn backup(conn: &Connection, path: &Path) -> Result<()> {
+    // VACUUM INTO refuses to overwrite, so replace a stale copy from an earlier attempt.
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    conn.execute("VACUUM INTO ?1", [path.to_string_lossy()])
+        .with_context(|| format!("Failed to back up database to {}", path.display()))?;
+    Ok(())
+}
+
+/// Adds `column` to `table` unless an older build already added it.
+fn ensure_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::{get_calendars, get_event, open_db};
+
+    /// A fresh directory for one test's database files.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("calendar-app-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).unwrap();
+        let names = stmt.query_map([], |row| row.get(1)).unwrap();
+        names.map(|name| name.unwrap()).collect()
+    }
+
+    fn has_table(conn: &Connection, table: &str) -> bool {
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE name = ?1", [table], |row| row.get::<_, i64>(0))
+            .unwrap()
+            > 0
+    }
+
+    fn assert_latest_schema(conn: &Connection) {
+        assert_eq!(schema_version(conn).unwrap(), latest_version());
+        let events = columns(conn, "events");
+        for column in ["rrule", "uid", "tzid", "all_day", "calendar_id"] {
+            assert!(events.contains(&column.to_string()), "events.{} is missing", column);
+        }
+        let tables = ["event_exceptions", "reminders", "calendars", "events_fts", "caldav_resources", "tags", "attendees"];
+        for table in tables {
+            assert!(has_table(conn, table), "table {} is missing", table);
+        }
+    }
+
+    #[test]
+    fn migrates_a_database_from_before_versioning() {
+        let dir = temp_dir("v0");
+        let path = dir.join("events.db");
+        {
+            // The schema of the first releases, which never set user_version.
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "
+                CREATE TABLE events (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    title TEXT NOT NULL,
+                    description TEXT,
+                    start_datetime INTEGER NOT NULL,
+                    end_datetime INTEGER NOT NULL,
+                    location TEXT,
+                    created_at INTEGER DEFAULT (strftime('%s', 'now')),
+                    updated_at INTEGER DEFAULT (strftime('%s', 'now')),
+                    rrule TEXT
+                );
+                INSERT INTO events (title, description, start_datetime, end_datetime, location, rrule)
+                    VALUES ('Dentist', 'Bring the card', 1780000000, 1780003600, 'Main St', 'FREQ=YEARLY');
+                ",
+            )
+            .unwrap();
+        }
+
+        let conn = open_db(&path).unwrap();
+        assert_latest_schema(&conn);
+        let event = get_event(&conn, 1).unwrap().unwrap();
+        assert_eq!(event.title, "Dentist");
+        assert_eq!(event.description.as_deref(), Some("Bring the card"));
+        assert_eq!(event.location.as_deref(), Some("Main St"));
+        assert_eq!(event.start_datetime.timestamp(), 1780000000);
+        assert_eq!(event.recurrence.map(|r| r.to_string()).as_deref(), Some("FREQ=YEARLY"));
+        assert!(!event.all_day);
+        assert!(event.calendar_id.is_some());
+        let found: i64 = conn
+            .query_row("SELECT rowid FROM events_fts WHERE events_fts MATCH 'card'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(found, 1);
+
+        let backup = Connection::open(backup_path(&path, 0)).unwrap();
+        assert_eq!(schema_version(&backup).unwrap(), 0);
+        assert!(!columns(&backup, "events").contains(&"uid".to_string()));
+        drop(backup);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn migrates_a_database_from_an_intermediate_version() {
+        let dir = temp_dir("v5");
+        let path = dir.join("events.db");
+        {
+            let mut conn = Connection::open(&path).unwrap();
+            for (index, migration) in MIGRATIONS.iter().enumerate().take(5) {
+                let tx = conn.transaction().unwrap();
+                (migration.apply)(&tx).unwrap();
+                tx.pragma_update(None, "user_version", index as i64 + 1).unwrap();
+                tx.commit().unwrap();
+            }
+            conn.execute_batch(
+                "
+                INSERT INTO events (title, start_datetime, end_datetime, uid, tzid, all_day)
+                    VALUES ('Holiday', 1780000000, 1780086400, 'holiday@example.com', NULL, 1);
+                INSERT INTO event_exceptions (event_id, occurrence_start) VALUES (1, 1780000000);
+                ",
+            )
+            .unwrap();
+        }
+
+        let conn = open_db(&path).unwrap();
+        assert_latest_schema(&conn);
+        let event = get_event(&conn, 1).unwrap().unwrap();
+        assert_eq!(event.title, "Holiday");
+        assert_eq!(event.uid.as_deref(), Some("holiday@example.com"));
+        assert!(event.all_day);
+        assert_eq!(event.exception_dates.len(), 1);
+        assert!(event.reminders.is_empty() && event.tags.is_empty() && event.attendees.is_empty());
+        assert!(backup_path(&path, 5).exists());
+
+        // Opening it again changes nothing and leaves no further backup.
+        drop(conn);
+        open_db(&path).unwrap();
+        assert!(!backup_path(&path, latest_version()).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refuses_a_database_from_a_newer_build() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", latest_version() + 1).unwrap();
+        assert!(migrate(&mut conn, None).is_err());
+    }
+
+    #[test]
+    fn leaves_calendars_with_the_default_color_to_the_theme() {
+        let dir = temp_dir("colors");
+        let path = dir.join("events.db");
+        {
+            let mut conn = Connection::open(&path).unwrap();
+            for (index, migration) in MIGRATIONS.iter().enumerate().take(MIGRATIONS.len() - 1) {
+                let tx = conn.transaction().unwrap();
+                (migration.apply)(&tx).unwrap();
+                tx.pragma_update(None, "user_version", index as i64 + 1).unwrap();
+                tx.commit().unwrap();
+            }
+            conn.execute("INSERT INTO calendars (name, color) VALUES ('Work', 'green')", []).unwrap();
+        }
+
+        let conn = open_db(&path).unwrap();
+        let colors: Vec<_> = get_calendars(&conn).unwrap().into_iter().map(|c| (c.name, c.color)).collect();
+        assert_eq!(colors, [("Personal".to_string(), None), ("Work".to_string(), Some("green".to_string()))]);
+        drop(conn);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1 +1,4 @@
+pub mod cache;
 pub mod db;
+pub mod migrations;
+pub mod sync_state;
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, Row};
+
+/// A local calendar mirrored from a CalDAV collection.
+pub struct CalendarLink {
+    pub calendar_id: i64,
+    pub href: String,
+    pub ctag: Option<String>,
+}
+
+/// A local event backed by a remote calendar object resource. Overrides of
+/// recurring events share their series' href. A row whose event was deleted
+/// locally (`event_id` is `None`) is a pending remote delete.
+pub struct Resource {
+    pub id: i64,
+    pub calendar_id: i64,
+    pub href: String,
+    pub event_id: Option<i64>,
+    pub etag: Option<String>,
+    pub is_override: bool,
+    pub dirty: bool,
+}
+
+pub fn get_calendar_links(conn: &Connection) -> Result<Vec<CalendarLink>> {
+    let mut stmt = conn.prepare("SELECT calendar_id, href, ctag FROM caldav_calendars ORDER BY calendar_id")?;
+    let links = stmt
+        .query_map([], |row| {
+            Ok(CalendarLink {
+                calendar_id: row.get(0)?,
+                href: row.get(1)?,
+                ctag: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(links)
+}
+
+pub fn link_calendar(conn: &Connection, calendar_id: i64, href: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO caldav_calendars (calendar_id, href) VALUES (?1, ?2)",
+        params![calendar_id, href],
+    )?;
+    Ok(())
+}
+
+pub fn set_calendar_ctag(conn: &Connection, calendar_id: i64, ctag: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE caldav_calendars SET ctag = ?1 WHERE calendar_id = ?2",
+        params![ctag, calendar_id],
+    )?;
+    Ok(())
+}
+
+/// Stops syncing a calendar; its events stay as local-only events.
+pub fn unlink_calendar(conn: &Connection, calendar_id: i64) -> Result<()> {
+    conn.execute("DELETE FROM caldav_resources WHERE calendar_id = ?1", params![calendar_id])?;
+    conn.execute("DELETE FROM caldav_calendars WHERE calendar_id = ?1", params![calendar_id])?;
+    Ok(())
+}
+
+fn resource_from_row(row: &Row) -> rusqlite::Result<Resource> {
+    Ok(Resource {
+        id: row.get(0)?,
+        calendar_id: row.get(1)?,
+        href: row.get(2)?,
+        event_id: row.get(3)?,
+        etag: row.get(4)?,
+        is_override: row.get(5)?,
+        dirty: row.get(6)?,
+    })
+}
+
+pub fn get_resources(conn: &Connection, calendar_id: i64) -> Result<Vec<Resource>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, calendar_id, href, event_id, etag, is_override, dirty
+         FROM caldav_resources WHERE calendar_id = ?1 ORDER BY id",
+    )?;
+    let resources = stmt
+        .query_map([calendar_id], resource_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(resources)
+}
+
+pub fn insert_resource(
+    conn: &Connection,
+    calendar_id: i64,
+    href: &str,
+    event_id: i64,
+    etag: Option<&str>,
+    is_override: bool,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO caldav_resources (calendar_id, href, event_id, etag, is_override) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![calendar_id, href, event_id, etag, is_override],
+    )?;
+    Ok(())
+}
+
+/// Records that resource `id` matches the server at `etag`.
+pub fn mark_resource_synced(conn: &Connection, id: i64, etag: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE caldav_resources SET etag = ?1, dirty = 0 WHERE id = ?2",
+        params![etag, id],
+    )?;
+    Ok(())
+}
+
+pub fn delete_resource(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM caldav_resources WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+/// Events of `calendar_id` that have never been uploaded.
+pub fn unsynced_event_ids(conn: &Connection, calendar_id: i64) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM events
+         WHERE calendar_id = ?1
+           AND id NOT IN (SELECT event_id FROM caldav_resources WHERE event_id IS NOT NULL)
+         ORDER BY id",
+    )?;
+    let ids = stmt
+        .query_map([calendar_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<i64>>>()?;
+    Ok(ids)
+}
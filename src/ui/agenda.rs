@@ -0,0 +1,96 @@
+use crate::{
+    app::{App, InteractionMode, AGENDA_DAYS},
+    models::event::Event,
+    ui::{bordered, day_style, event_color, tag_chips, view_title},
+};
+use chrono::{Duration, NaiveDate, NaiveTime};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+pub fn draw_agenda_view(f: &mut Frame, app: &App, area: Rect) {
+    let last = app.selected_date + Duration::days(AGENDA_DAYS - 1);
+    let title = format!(
+        "Agenda {} - {}",
+        app.selected_date.format("%B %-d"),
+        last.format("%B %-d, %Y")
+    );
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+        .split(area);
+
+    let header_block = Block::default().title(view_title(app, title)).borders(Borders::NONE);
+    f.render_widget(header_block, chunks[0]);
+
+    let mut items = Vec::new();
+    let mut selected_row = None;
+    let mut current_date = None;
+    for (index, (date, event)) in app.agenda_entries().iter().enumerate() {
+        if current_date != Some(*date) {
+            current_date = Some(*date);
+            items.push(ListItem::new(Line::styled(
+                date.format("%A, %B %-d").to_string(),
+                day_style(app, *date, app.theme.header.add_modifier(Modifier::BOLD)),
+            )));
+        }
+        if index == app.agenda_selected {
+            selected_row = Some(items.len());
+        }
+        items.push(agenda_item(app, *date, event));
+    }
+    if items.is_empty() {
+        items.push(ListItem::new(Line::styled(
+            "No upcoming events",
+            app.theme.muted,
+        )));
+    }
+
+    let highlight = if let InteractionMode::Selection = app.mode {
+        app.theme.selection
+    } else {
+        Style::default().add_modifier(Modifier::REVERSED)
+    };
+    let list = List::new(items)
+        .block(bordered(app))
+        .highlight_style(highlight);
+    let mut state = ListState::default().with_selected(selected_row);
+    f.render_stateful_widget(list, chunks[1], &mut state);
+}
+
+fn agenda_item<'a>(app: &App, date: NaiveDate, event: &Event) -> ListItem<'a> {
+    let (first, last) = event.date_span(app.timezone);
+    let time_pattern = app.config.ui.time_pattern();
+    let start = app.to_local(event.start_datetime).format(time_pattern);
+    let end = app.to_local(event.end_datetime).format(time_pattern);
+    // Timed events spanning days show their start on the first day and end on the last.
+    let time_range = if event.all_day || (date != first && date != last) {
+        "all day".to_string()
+    } else if first == last {
+        format!("{}-{}", start, end)
+    } else if date == first {
+        format!("{} →", start)
+    } else {
+        format!("→ {}", end)
+    };
+    // Room for a start-end range plus a little padding.
+    let width = 2 * NaiveTime::MIN.format(time_pattern).to_string().len() + 3;
+
+    let mut spans = vec![
+        Span::raw("  "),
+        Span::styled("▌", Style::default().fg(event_color(app, event.calendar_id))),
+        Span::raw(format!("{:<width$} {}", time_range, event.title, width = width)),
+    ];
+    if let Some(location) = event.location.as_deref().filter(|l| !l.is_empty()) {
+        spans.push(Span::styled(format!(" @ {}", location), app.theme.muted));
+    }
+    if !event.tags.is_empty() {
+        spans.push(Span::styled(format!("  {}", tag_chips(&event.tags)), app.theme.tag));
+    }
+    ListItem::new(Line::from(spans))
+}
@@ -0,0 +1,36 @@
+use crate::{app::App, models::keys::Action, ui::{bordered, event_color, event_form::centered_rect}};
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, ListState},
+    Frame,
+};
+
+pub fn draw_calendars(f: &mut Frame, app: &App, area: Rect) {
+    let popup_area = centered_rect(40, 40, area);
+    let items: Vec<ListItem> = app
+        .calendars
+        .iter()
+        .map(|calendar| {
+            let checkbox = if calendar.visible { "[x] " } else { "[ ] " };
+            ListItem::new(Line::from(vec![
+                Span::raw(checkbox),
+                Span::styled("  ", Style::default().bg(event_color(app, calendar.id))),
+                Span::raw(format!(" {}", calendar.name)),
+            ]))
+        })
+        .collect();
+    let title = format!(
+        "Calendars (space/{}: show/hide, {}: close)",
+        app.keys.label(Action::Select),
+        app.keys.label(Action::Back)
+    );
+    let list = List::new(items)
+        .block(bordered(app).title(title))
+        .highlight_style(app.theme.selection);
+    let mut state = ListState::default().with_selected(Some(app.selected_calendar));
+
+    f.render_widget(Clear, popup_area);
+    f.render_stateful_widget(list, popup_area, &mut state);
+}
@@ -0,0 +1,257 @@
+use crate::{
+    app::App,
+    models::event::Event,
+    ui::{event_style, tag_chips},
+};
+use chrono::{DateTime, Duration, Utc};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span, Text},
+};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+/// Sub-column of an event among the events it overlaps with.
+#[derive(Clone, Copy)]
+pub struct Placement {
+    pub column: usize,
+    /// Columns of its group of transitively overlapping events.
+    pub columns: usize,
+}
+
+/// Lays overlapping events out side by side like desktop calendars do: each
+/// event takes the leftmost column that is free at its start, and all events
+/// of an overlapping group share the group's column count.
+pub fn pack(events: &[Event]) -> Vec<Placement> {
+    let mut order: Vec<usize> = (0..events.len()).collect();
+    order.sort_by_key(|&i| (events[i].start_datetime, Reverse(events[i].end_datetime)));
+
+    let mut placements = vec![Placement { column: 0, columns: 1 }; events.len()];
+    let mut group: Vec<usize> = Vec::new();
+    let mut column_ends: Vec<DateTime<Utc>> = Vec::new();
+    let mut group_end: Option<DateTime<Utc>> = None;
+    for i in order {
+        let event = &events[i];
+        if group_end.is_some_and(|end| event.start_datetime >= end) {
+            for &j in &group {
+                placements[j].columns = column_ends.len();
+            }
+            group.clear();
+            column_ends.clear();
+        }
+        let column = match column_ends.iter().position(|end| *end <= event.start_datetime) {
+            Some(column) => {
+                column_ends[column] = event.end_datetime;
+                column
+            }
+            None => {
+                column_ends.push(event.end_datetime);
+                column_ends.len() - 1
+            }
+        };
+        placements[i].column = column;
+        group.push(i);
+        group_end = Some(group_end.map_or(event.end_datetime, |end| end.max(event.end_datetime)));
+    }
+    for &j in &group {
+        placements[j].columns = column_ends.len();
+    }
+    placements
+}
+
+/// Whether `event` overlaps the `slot`-long grid slot starting at one of
+/// `slot_instants`. Zero-length events belong to the slot they start in.
+pub fn covers_slot(event: &Event, slot_instants: &[DateTime<Utc>], slot: Duration) -> bool {
+    slot_instants.iter().any(|&start| {
+        let end = start + slot;
+        event.start_datetime < end && (event.end_datetime > start || event.start_datetime >= start)
+    })
+}
+
+/// Widths of the columns a bordered table with `constraints` gets in `area`.
+pub fn table_column_widths(constraints: &[Constraint], area: Rect) -> Vec<u16> {
+    let inner = Rect::new(0, 0, area.width.saturating_sub(2), 1);
+    Layout::horizontal(constraints.iter().copied())
+        .flex(Flex::Start)
+        .spacing(1)
+        .split(inner)
+        .iter()
+        .map(|rect| rect.width)
+        .collect()
+}
+
+/// An event drawn in one grid cell.
+pub struct SlotEntry<'e> {
+    pub event: &'e Event,
+    pub placement: Placement,
+    /// Only the first cell of an event carries its title.
+    pub first: bool,
+}
+
+/// Two-line grid cell `width` columns wide with each event in its own
+/// sub-column. A `!` in the conflict style marks slots where events overlap.
+/// With `chips`, an event's tags go under its title.
+pub fn slot_text<'a>(app: &App, entries: &[SlotEntry], width: u16, background: Style, chips: bool) -> Text<'a> {
+    let Some(columns) = entries.first().map(|e| e.placement.columns) else {
+        return Text::default();
+    };
+    let mut title_spans = Vec::new();
+    let mut fill_spans = Vec::new();
+    let mut available = width as usize;
+    if columns > 1 {
+        let marker = if entries.len() > 1 {
+            Span::styled("!", background.patch(app.theme.conflict))
+        } else {
+            Span::styled(" ", background)
+        };
+        title_spans.push(marker);
+        fill_spans.push(Span::styled(" ", background));
+        available = available.saturating_sub(1);
+    }
+
+    for column in 0..columns {
+        let column_width = available / columns + usize::from(column < available % columns);
+        let Some(entry) = entries.iter().find(|e| e.placement.column == column) else {
+            title_spans.push(Span::styled(" ".repeat(column_width), background));
+            fill_spans.push(Span::styled(" ".repeat(column_width), background));
+            continue;
+        };
+        // Adjacent events of the same calendar color stay apart.
+        let gap = usize::from(columns > 1 && column_width > 1);
+        let event_width = column_width - gap;
+        let style = event_style(app, entry.event);
+        let (title, tags): (String, String) = if entry.first {
+            let tags = if chips { tag_chips(&entry.event.tags) } else { String::new() };
+            (
+                entry.event.title.chars().take(event_width).collect(),
+                tags.chars().take(event_width).collect(),
+            )
+        } else {
+            (String::new(), String::new())
+        };
+        title_spans.push(Span::styled(format!("{:<width$}", title, width = event_width), style));
+        fill_spans.push(Span::styled(format!("{:<width$}", tags, width = event_width), style.patch(app.theme.tag)));
+        if gap == 1 {
+            title_spans.push(Span::styled(" ", background));
+            fill_spans.push(Span::styled(" ", background));
+        }
+    }
+    Text::from(vec![Line::from(title_spans), Line::from(fill_spans)])
+}
+
+/// Entries for one slot of a day column, left to right. `placements` comes
+/// from `pack(events)`; `titled` collects events whose title was drawn in an
+/// earlier slot.
+pub fn slot_entries<'e>(
+    events: &'e [Event],
+    placements: &[Placement],
+    slot_instants: &[DateTime<Utc>],
+    slot: Duration,
+    titled: &mut HashSet<usize>,
+) -> Vec<SlotEntry<'e>> {
+    let mut entries: Vec<SlotEntry> = events
+        .iter()
+        .zip(placements)
+        .enumerate()
+        .filter(|(_, (event, _))| covers_slot(event, slot_instants, slot))
+        .map(|(i, (event, placement))| SlotEntry {
+            event,
+            placement: *placement,
+            first: titled.insert(i),
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.placement.column);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::config::Config, storage::db::open_memory_db};
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap()
+    }
+
+    fn event(title: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Event {
+        Event {
+            title: title.to_string(),
+            start_datetime: start,
+            end_datetime: end,
+            ..Event::default()
+        }
+    }
+
+    fn layout(events: &[Event]) -> Vec<(usize, usize)> {
+        pack(events).iter().map(|p| (p.column, p.columns)).collect()
+    }
+
+    #[test]
+    fn events_that_do_not_overlap_share_the_first_column() {
+        let events = [
+            event("B", at(10, 0), at(11, 0)),
+            event("A", at(9, 0), at(10, 0)),
+            event("C", at(14, 0), at(14, 0)),
+        ];
+        assert_eq!(layout(&events), [(0, 1), (0, 1), (0, 1)]);
+    }
+
+    #[test]
+    fn a_later_event_reuses_a_column_freed_in_its_group() {
+        // A and C do not overlap, but both overlap B, so all three form one group.
+        let events = [
+            event("A", at(9, 0), at(11, 0)),
+            event("B", at(10, 0), at(12, 0)),
+            event("C", at(11, 0), at(13, 0)),
+        ];
+        assert_eq!(layout(&events), [(0, 2), (1, 2), (0, 2)]);
+    }
+
+    #[test]
+    fn every_event_of_a_group_gets_the_group_width() {
+        let events = [
+            event("A", at(9, 0), at(10, 0)),
+            event("B", at(9, 0), at(10, 0)),
+            event("C", at(9, 0), at(10, 0)),
+            event("D", at(9, 30), at(11, 0)),
+            // Overlaps D alone, yet keeps the four columns of its group.
+            event("E", at(10, 30), at(11, 0)),
+            event("F", at(11, 0), at(12, 0)),
+        ];
+        assert_eq!(layout(&events), [(0, 4), (1, 4), (2, 4), (3, 4), (0, 4), (0, 1)]);
+    }
+
+    #[test]
+    fn titles_each_event_in_its_first_slot_only() {
+        let events = [event("Long", at(9, 0), at(11, 0)), event("Short", at(10, 0), at(10, 30))];
+        let placements = pack(&events);
+        let mut titled = HashSet::new();
+        let mut slot = |hour| {
+            slot_entries(&events, &placements, &[at(hour, 0)], Duration::hours(1), &mut titled)
+                .iter()
+                .map(|e| (e.event.title.clone(), e.placement.column, e.first))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(slot(9), [("Long".to_string(), 0, true)]);
+        assert_eq!(slot(10), [("Long".to_string(), 0, false), ("Short".to_string(), 1, true)]);
+        assert!(slot(11).is_empty());
+    }
+
+    #[test]
+    fn marks_slots_where_events_overlap() {
+        let app = App::new(Config::default(), open_memory_db());
+        let events = [event("Long", at(9, 0), at(11, 0)), event("Short", at(10, 0), at(10, 30))];
+        let placements = pack(&events);
+        let mut titled = HashSet::new();
+        let mut marker = |hour| {
+            let entries = slot_entries(&events, &placements, &[at(hour, 0)], Duration::hours(1), &mut titled);
+            let text = slot_text(&app, &entries, 21, Style::default(), false);
+            let first = &text.lines[0].spans[0];
+            (first.content.to_string(), first.style == app.theme.conflict)
+        };
+        assert_eq!(marker(9), (" ".to_string(), false));
+        assert_eq!(marker(10), ("!".to_string(), true));
+    }
+}
@@ -1,11 +1,21 @@
-use crate::{app::App, storage::db::get_events_in_range};
-use chrono::{Datelike, Timelike};
+use crate::{
+    app::App,
+    models::{event::Event, timezone::local_instants},
+    ui::{
+        bordered,
+        columns::{pack, slot_entries, slot_text, table_column_widths},
+        day_style, event_style, view_title,
+    },
+};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Utc};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::Style,
+    text::{Line, Text},
     widgets::{Block, Borders, Cell, Row, Table},
     Frame,
 };
+use std::collections::HashSet;
 
 pub fn draw_day_view(f: &mut Frame, app: &App, area: Rect) {
     let year = app.selected_date.year();
@@ -25,67 +35,83 @@ pub fn draw_day_view(f: &mut Frame, app: &App, area: Rect) {
         .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
         .split(area);
 
-    let header_block = Block::default().title(title).borders(Borders::NONE);
+    let header_block = Block::default().title(view_title(app, title)).borders(Borders::NONE);
     f.render_widget(header_block, chunks[0]);
 
-    let table = day_table(app);
+    let table = day_table(app, chunks[1]);
     f.render_widget(table, chunks[1]);
 }
 
-fn day_table<'a>(app: &App) -> Table<'a> {
-    let header_cells = ["Time", "Event"]
-        .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
+fn day_table<'a>(app: &App, area: Rect) -> Table<'a> {
+    let header_cells = vec![
+        Cell::from("Time"),
+        Cell::from("Event").style(day_style(app, app.selected_date, Style::default())),
+    ];
     let header = Row::new(header_cells)
-        .style(Style::default().bg(Color::Blue))
+        .style(app.theme.header)
         .height(1)
         .bottom_margin(1);
 
-    let start_timestamp = app
-        .selected_date
-        .and_hms_opt(0, 0, 0)
-        .map(|dt| dt.and_utc().timestamp())
-        .unwrap_or_default();
-    let end_timestamp = app
-        .selected_date
-        .and_hms_opt(23, 59, 59)
-        .map(|dt| dt.and_utc().timestamp())
-        .unwrap_or_default();
+    let (start_timestamp, end_timestamp) = app.day_range(app.selected_date, app.selected_date);
 
-    let events = get_events_in_range(&app.conn, start_timestamp, end_timestamp).unwrap_or_default();
+    let events = app.events_in_range(start_timestamp, end_timestamp);
+    let (banner_events, events): (Vec<Event>, Vec<Event>) =
+        events.into_iter().partition(|e| e.is_multi_day(app.timezone));
 
-    let mut rows = vec![];
+    let (banner_cell, banner_height) = all_day_cell(app, &banner_events, app.selected_date);
+    let mut rows = vec![Row::new(vec![Cell::from("All day"), banner_cell])
+        .height(banner_height)
+        .bottom_margin(1)];
 
-    for hour in 0..24 {
-        for minute in [0, 30] {
-            let time_cell = Cell::from(format!("{:02}:{:02}", hour, minute));
-            let mut event_text = String::new();
-            let mut row_style = Style::default();
+    let constraints = vec![time_column(app), Constraint::Percentage(90)];
+    let event_width = table_column_widths(&constraints, area)[1];
+    let placements = pack(&events);
+    let mut titled = HashSet::new();
 
-            for event in &events {
-                let event_start_time = event.start_datetime.time();
-                let event_end_time = event.end_datetime.time();
-                if let Some(current_time) = chrono::NaiveTime::from_hms_opt(hour, minute, 0) {
-                    if current_time >= event_start_time && current_time < event_end_time {
-                        event_text = event.title.clone();
-                        row_style = row_style.bg(Color::Cyan);
-                    }
-                }
-            }
-            let event_cell = Cell::from(event_text);
-            let mut row = Row::new(vec![time_cell, event_cell]).height(2);
-            if hour == app.selected_time.hour() && minute == app.selected_time.minute() {
-                row = row.style(Style::default().bg(Color::Yellow));
-            } else {
-                row = row.style(row_style);
-            }
-            rows.push(row);
-        }
+    for time in app.slot_times(0, 24) {
+        let slot_instants = local_instants(app.timezone, app.selected_date.and_time(time));
+        let time_cell = slot_time_cell(app, time, &slot_instants);
+        let background = if time == app.selected_time {
+            app.theme.selection
+        } else {
+            Style::default()
+        };
+        let entries = slot_entries(&events, &placements, &slot_instants, app.slot, &mut titled);
+        let event_cell = Cell::from(slot_text(app, &entries, event_width, background, true));
+        rows.push(Row::new(vec![time_cell, event_cell]).height(2).style(background));
     }
 
-    let constraints = vec![Constraint::Length(6), Constraint::Percentage(90)];
     Table::new(rows, constraints)
         .header(header)
-        .block(Block::default().borders(Borders::ALL))
+        .block(bordered(app))
         .column_spacing(1)
 }
+
+/// Time label for a grid slot. Slots skipped by a DST transition are dimmed
+/// and slots that occur twice are marked with `*`.
+pub fn slot_time_cell<'a>(app: &App, time: NaiveTime, slot_instants: &[DateTime<Utc>]) -> Cell<'a> {
+    let label = time.format(app.config.ui.time_pattern()).to_string();
+    match slot_instants.len() {
+        0 => Cell::from(label).style(app.theme.muted),
+        1 => Cell::from(label),
+        _ => Cell::from(format!("{}*", label)),
+    }
+}
+
+/// Width of the grid's time column: a label in the configured time format
+/// and the DST marker.
+pub fn time_column(app: &App) -> Constraint {
+    Constraint::Length(NaiveTime::MIN.format(app.config.ui.time_pattern()).to_string().len() as u16 + 1)
+}
+
+/// Banner cell listing the all-day and multi-day events covering `date`,
+/// with the number of lines it needs.
+pub fn all_day_cell<'a>(app: &App, events: &[Event], date: NaiveDate) -> (Cell<'a>, u16) {
+    let lines: Vec<Line> = events
+        .iter()
+        .filter(|e| e.covers_date(app.timezone, date))
+        .map(|e| Line::styled(e.title.clone(), event_style(app, e)))
+        .collect();
+    let height = lines.len().max(1) as u16;
+    (Cell::from(Text::from(lines)), height)
+}
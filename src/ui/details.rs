@@ -0,0 +1,229 @@
+use crate::{
+    app::App,
+    models::{attendee::Status, event::Event, keys::Action},
+    ui::{bordered, event_color, event_form::centered_rect, tag_chips},
+};
+use chrono::{DateTime, Duration, Utc};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// The view's part of `area` and the details panel beside it, while the
+/// panel is open in a view that has room for it.
+pub fn split(app: &App, area: Rect) -> (Rect, Option<Rect>) {
+    if !app.show_details || !app.has_details_panel() {
+        return (area, None);
+    }
+    let width = (area.width / 3).clamp(30, 50).min(area.width / 2);
+    let [view, panel] = Layout::horizontal([Constraint::Min(0), Constraint::Length(width)]).areas(area);
+    (view, Some(panel))
+}
+
+pub fn draw_details_panel(f: &mut Frame, app: &App, area: Rect) {
+    let lines = match app.focused_event() {
+        Some(event) => details_lines(app, &event),
+        None => vec![Line::styled(
+            format!(
+                "No event focused; {}/{} focus one",
+                app.keys.label(Action::NextEvent),
+                app.keys.label(Action::PreviousEvent)
+            ),
+            app.theme.muted,
+        )],
+    };
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }).block(bordered(app).title("Details")),
+        area,
+    );
+}
+
+/// Details of the focused event over the view. The popup still shows when the
+/// event went away while it was open, so the key closing it isn't lost.
+pub fn draw_details_popup(f: &mut Frame, app: &App, area: Rect) {
+    let lines = match app.focused_event() {
+        Some(event) => details_lines(app, &event),
+        None => vec![Line::styled("No event selected", app.theme.muted)],
+    };
+    let popup_area = centered_rect(60, 70, area);
+    f.render_widget(Clear, popup_area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(bordered(app).title("Details (any key to close)")),
+        popup_area,
+    );
+}
+
+fn details_lines<'a>(app: &App, event: &Event) -> Vec<Line<'a>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let time_pattern = app.config.ui.time_pattern();
+    let mut lines = vec![
+        Line::styled(event.title.clone(), bold),
+        Line::raw(time_range(app, event)),
+    ];
+    if let Some(recurrence) = &event.recurrence {
+        lines.push(Line::styled(format!("Repeats {}", recurrence), app.theme.muted));
+    }
+    if let Some(location) = event.location.as_deref().filter(|l| !l.is_empty()) {
+        lines.push(Line::raw(format!("@ {}", location)));
+    }
+    if let Some(calendar) = app.calendar(event.calendar_id) {
+        lines.push(Line::from(vec![
+            Span::styled("▌", Style::default().fg(event_color(app, event.calendar_id))),
+            Span::raw(calendar.name.clone()),
+        ]));
+    }
+    if !event.tags.is_empty() {
+        lines.push(Line::styled(tag_chips(&event.tags), app.theme.tag));
+    }
+
+    if let Some(description) = event.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        lines.push(Line::raw(""));
+        lines.extend(description.lines().map(|line| Line::raw(line.to_string())));
+    }
+
+    if !event.attendees.is_empty() {
+        let accepted = event.attendees.iter().filter(|a| a.status == Status::Accepted).count();
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            format!("Attendees ({}, {} accepted)", event.attendees.len(), accepted),
+            bold,
+        ));
+        for attendee in &event.attendees {
+            let mut spans = vec![Span::raw(format!(" {} {}", attendee.status.symbol(), attendee.display_name()))];
+            if attendee.name.is_some() {
+                spans.push(Span::styled(format!(" <{}>", attendee.email), app.theme.muted));
+            }
+            spans.push(Span::styled(
+                format!("  {}, {}", attendee.role.name(), attendee.status.name()),
+                app.theme.muted,
+            ));
+            lines.push(Line::from(spans));
+        }
+    }
+
+    let text = [Some(event.title.as_str()), event.location.as_deref(), event.description.as_deref()];
+    let links = find_links(&text.into_iter().flatten().collect::<Vec<_>>().join("\n"));
+    if !links.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("Links", bold));
+        let link_style = Style::default().add_modifier(Modifier::UNDERLINED);
+        lines.extend(links.into_iter().map(|link| Line::styled(link, link_style)));
+    }
+
+    let stamp = |datetime: DateTime<Utc>| {
+        let local = app.to_local(datetime);
+        format!("{} {}", local.format("%Y-%m-%d"), local.format(time_pattern))
+    };
+    lines.push(Line::raw(""));
+    lines.push(Line::styled(format!("Created {}", stamp(event.created_at)), app.theme.muted));
+    lines.push(Line::styled(format!("Updated {}", stamp(event.updated_at)), app.theme.muted));
+    lines
+}
+
+/// Start and end with the duration, e.g. "Mon 2026-10-19 15:00-16:30 (1 h 30 min)".
+fn time_range(app: &App, event: &Event) -> String {
+    let (first, last) = event.date_span(app.timezone);
+    if event.all_day {
+        let days = (last - first).num_days() + 1;
+        let plural = if days == 1 { "" } else { "s" };
+        return if first == last {
+            format!("{}, all day", first.format("%a %Y-%m-%d"))
+        } else {
+            format!(
+                "{} - {}, all day ({} day{})",
+                first.format("%a %Y-%m-%d"),
+                last.format("%a %Y-%m-%d"),
+                days,
+                plural
+            )
+        };
+    }
+    let time_pattern = app.config.ui.time_pattern();
+    let start = app.to_local(event.start_datetime);
+    let end = app.to_local(event.end_datetime);
+    let end = if start.date() == end.date() {
+        end.format(time_pattern).to_string()
+    } else {
+        format!("{} {}", end.format("%a %Y-%m-%d"), end.format(time_pattern))
+    };
+    format!(
+        "{} {}-{} ({})",
+        start.format("%a %Y-%m-%d"),
+        start.format(time_pattern),
+        end,
+        format_duration(event.end_datetime - event.start_datetime)
+    )
+}
+
+/// Duration in days, hours and minutes, e.g. "1 day 2 h" or "45 min".
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(format!("{} day{}", days, if days == 1 { "" } else { "s" }));
+    }
+    if hours > 0 {
+        parts.push(format!("{} h", hours));
+    }
+    if minutes > 0 || parts.is_empty() {
+        parts.push(format!("{} min", minutes));
+    }
+    parts.join(" ")
+}
+
+/// Web and mail links in `text`, in order of appearance, without duplicates.
+/// Punctuation that usually ends a sentence is not part of a link.
+fn find_links(text: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    for word in text.split(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '(' | '[')) {
+        let lower = word.to_ascii_lowercase();
+        let Some(prefix) = ["http://", "https://", "mailto:", "www."].into_iter().find(|p| lower.starts_with(p)) else {
+            continue;
+        };
+        let link = word.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '\'']);
+        if link.len() > prefix.len() && !links.iter().any(|l| l == link) {
+            links.push(link.to_string());
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_trailing_punctuation_out_of_links() {
+        let text = "Agenda at https://example.com/agenda. Questions (mailto:team@example.com)? See www.example.org!";
+        assert_eq!(
+            find_links(text),
+            ["https://example.com/agenda", "mailto:team@example.com", "www.example.org"]
+        );
+        assert_eq!(
+            find_links("Notes: \"https://example.com/a?b=1\", then [http://x.io/p]."),
+            ["https://example.com/a?b=1", "http://x.io/p"]
+        );
+    }
+
+    #[test]
+    fn lists_each_link_once_in_order() {
+        let text = "Join https://meet.example.com/abc\nor dial in; slides at https://example.com/s.\n\
+                    Join <https://meet.example.com/abc> again, HTTPS://EXAMPLE.COM/UP stays as written";
+        assert_eq!(
+            find_links(text),
+            ["https://meet.example.com/abc", "https://example.com/s", "HTTPS://EXAMPLE.COM/UP"]
+        );
+    }
+
+    #[test]
+    fn ignores_words_that_only_start_like_links() {
+        assert!(find_links("www. and http:// alone, or wwwhat and mailto").is_empty());
+    }
+}
@@ -1,16 +1,29 @@
-use crate::app::App;
+use crate::{
+    app::{App, FormField},
+    models::time_input::input_hint,
+    ui::{bordered, event_color},
+};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    widgets::{Block, Borders, Clear},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
 pub fn draw_event_form(f: &mut Frame, app: &mut App, area: Rect) {
-    let popup_area = centered_rect(60, 50, area);
-    let block = Block::default().title("Create Event").borders(Borders::ALL);
+    let popup_area = centered_rect(60, 90, area);
+    let block = bordered(app).title("Create Event");
     f.render_widget(Clear, popup_area); // this clears the area behind the popup
     f.render_widget(block.clone(), popup_area);
 
+    let calendar_id = app.event_form_state.as_ref().and_then(|s| s.calendar_id);
+    let calendar_name = app.calendar(calendar_id).map(|c| c.name.clone()).unwrap_or_default();
+    let calendar_color = event_color(app, calendar_id);
+    let time_pattern = app.config.ui.time_pattern();
+    let (border, error_style, selection) = (app.theme.border, app.theme.error, app.theme.selection);
+    let field_block = || Block::default().borders(Borders::ALL).border_style(border);
+
     if let Some(form_state) = &mut app.event_form_state {
         let form_chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -20,24 +33,101 @@ pub fn draw_event_form(f: &mut Frame, app: &mut App, area: Rect) {
                     Constraint::Length(3),
                     Constraint::Length(3),
                     Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(1),
+                    Constraint::Length(1),
                     Constraint::Min(0),
                 ]
                 .as_ref(),
             )
             .split(popup_area);
 
-        form_state.title.set_block(Block::default().borders(Borders::ALL).title("Title"));
-        form_state.description.set_block(Block::default().borders(Borders::ALL).title("Description"));
-        form_state.location.set_block(Block::default().borders(Borders::ALL).title("Location"));
+        form_state.title.set_block(field_block().title("Title"));
+        let (start_error, end_error) = form_state.time_errors(time_pattern);
+        let (start_label, end_label) = if form_state.all_day {
+            ("First day", "Last day")
+        } else {
+            ("Start", "End")
+        };
+        let hint = input_hint(form_state.all_day, time_pattern);
+        // Invalid times are flagged as they are typed.
+        let time_block = |label: &str, error: Option<String>| {
+            let block = field_block();
+            match error {
+                Some(error) => block
+                    .title(format!("{}: {}", label, error))
+                    .border_style(error_style),
+                None => block.title(format!("{} ({})", label, hint)),
+            }
+        };
+        form_state.start.set_block(time_block(start_label, start_error));
+        form_state.end.set_block(time_block(end_label, end_error));
+        form_state.description.set_block(field_block().title("Description"));
+        form_state.location.set_block(field_block().title("Location"));
+        // Tab accepts the completion shown in the title.
+        let tags_title = match form_state.tag_suggestion() {
+            Some(rest) if form_state.focused_field == FormField::Tags => format!("Tags (Tab: …{})", rest),
+            _ => "Tags (e.g. work, travel)".to_string(),
+        };
+        form_state.tags.set_block(field_block().title(tags_title));
+        let attendees_block = match form_state.parsed_attendees() {
+            Err(error) if !form_state.attendees.is_empty() => field_block()
+                .title(format!("Attendees: {}", error))
+                .border_style(error_style),
+            _ => field_block().title("Attendees (e.g. Ana <ana@example.com> accepted, bob@example.com optional)"),
+        };
+        form_state.attendees.set_block(attendees_block);
+        form_state
+            .recurrence
+            .set_block(field_block().title("Repeat (RRULE, e.g. FREQ=WEEKLY;BYDAY=MO)"));
+        form_state
+            .reminders
+            .set_block(field_block().title("Reminders (e.g. 10m, 1h, 1d)"));
 
+        let time_chunks =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).split(form_chunks[1]);
         f.render_widget(&form_state.title, form_chunks[0]);
-        f.render_widget(&form_state.description, form_chunks[1]);
-        f.render_widget(&form_state.location, form_chunks[2]);
+        f.render_widget(&form_state.start, time_chunks[0]);
+        f.render_widget(&form_state.end, time_chunks[1]);
+        f.render_widget(&form_state.description, form_chunks[2]);
+        f.render_widget(&form_state.location, form_chunks[3]);
+        f.render_widget(&form_state.tags, form_chunks[4]);
+        f.render_widget(&form_state.attendees, form_chunks[5]);
+        f.render_widget(&form_state.recurrence, form_chunks[6]);
+        f.render_widget(&form_state.reminders, form_chunks[7]);
+
+        let checkbox = if form_state.all_day { "[x] All day" } else { "[ ] All day" };
+        let checkbox_style = if form_state.focused_field == FormField::AllDay {
+            selection
+        } else {
+            Style::default()
+        };
+        f.render_widget(Paragraph::new(checkbox).style(checkbox_style), form_chunks[8]);
+
+        let calendar_style = if form_state.focused_field == FormField::Calendar {
+            selection
+        } else {
+            Style::default()
+        };
+        let calendar = Line::from(vec![
+            Span::styled(format!("Calendar: < {} > ", calendar_name), calendar_style),
+            Span::styled("  ", Style::default().bg(calendar_color)),
+        ]);
+        f.render_widget(Paragraph::new(calendar), form_chunks[9]);
+
+        if let Some(error) = &form_state.error {
+            let error = Paragraph::new(error.as_str()).style(error_style);
+            f.render_widget(error, form_chunks[10]);
+        }
     }
 }
 
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
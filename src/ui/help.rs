@@ -0,0 +1,43 @@
+use crate::{
+    app::App,
+    models::keys::Action,
+    ui::{bordered, event_form::centered_rect},
+};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::{Cell, Clear, Row, Table},
+    Frame,
+};
+
+/// Keys of the event form and text popups, which cannot be rebound.
+const FIXED_KEYS: &[(&str, &str)] = &[
+    ("tab", "Next field in the event form, or complete the tag being typed"),
+    ("up/down", "Adjust start and end in the event form"),
+    ("space", "Toggle all day in the event form"),
+    ("enter/esc", "Save or cancel forms, search and quick add"),
+];
+
+pub fn draw_help(f: &mut Frame, app: &App, area: Rect) {
+    let popup_area = centered_rect(70, 80, area);
+    let key_style = Style::default().add_modifier(Modifier::BOLD);
+    let mut rows: Vec<Row> = Action::ALL
+        .iter()
+        .map(|action| {
+            Row::new(vec![
+                Cell::from(app.keys.label(*action)).style(key_style),
+                Cell::from(action.description()),
+            ])
+        })
+        .collect();
+    rows.push(Row::new(vec![Cell::from(""), Cell::from("")]));
+    rows.extend(FIXED_KEYS.iter().map(|(keys, description)| {
+        Row::new(vec![Cell::from(*keys).style(key_style), Cell::from(*description)])
+    }));
+
+    let table = Table::new(rows, [Constraint::Length(14), Constraint::Min(0)])
+        .header(Row::new(vec!["Key", "Action"]).style(app.theme.header))
+        .block(bordered(app).title("Key bindings (any key to close)"));
+    f.render_widget(Clear, popup_area);
+    f.render_widget(table, popup_area);
+}
@@ -1,22 +1,186 @@
+pub mod agenda;
+pub mod calendars;
+pub mod columns;
 pub mod day;
+pub mod details;
 pub mod event_form;
+pub mod help;
 pub mod month;
+pub mod quick_add;
+pub mod recurrence_scope;
+pub mod search;
+pub mod tag_filter;
 pub mod week;
 pub mod year;
 
 use crate::app::{App, AppState, InteractionMode};
-use ratatui::Frame;
+use crate::models::event::Event;
+use crate::models::keys::Action;
+use chrono::{Datelike, NaiveDate, Weekday};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
 
 pub fn draw(f: &mut Frame, app: &mut App) {
     let size = f.area();
+    let (view_area, details_area) = details::split(app, size);
     match app.state {
-        AppState::Year => year::draw_year_view(f, app, size),
-        AppState::Month => month::draw_month_view(f, app, size),
-        AppState::Week => week::draw_week_view(f, app, size),
-        AppState::Day => day::draw_day_view(f, app, size),
+        AppState::Year => year::draw_year_view(f, app, view_area),
+        AppState::Month => month::draw_month_view(f, app, view_area),
+        AppState::Week => week::draw_week_view(f, app, view_area),
+        AppState::Day => day::draw_day_view(f, app, view_area),
+        AppState::Agenda => agenda::draw_agenda_view(f, app, view_area),
+    }
+    if let Some(area) = details_area {
+        details::draw_details_panel(f, app, area);
     }
 
     if let InteractionMode::EventForm = app.mode {
         event_form::draw_event_form(f, app, size);
     }
+    if let InteractionMode::RecurrenceScope = app.mode {
+        recurrence_scope::draw_recurrence_scope(f, app, size);
+    }
+    if let InteractionMode::Calendars = app.mode {
+        calendars::draw_calendars(f, app, size);
+    }
+    if let InteractionMode::Search = app.mode {
+        search::draw_search(f, app, size);
+    }
+    if let InteractionMode::QuickAdd = app.mode {
+        quick_add::draw_quick_add(f, app, size);
+    }
+    if let InteractionMode::TagFilter = app.mode {
+        tag_filter::draw_tag_filter(f, app, size);
+    }
+    if app.show_details && details_area.is_none() {
+        details::draw_details_popup(f, app, size);
+    }
+    if app.show_help {
+        help::draw_help(f, app, size);
+    }
+    let banner_area = Rect::new(size.x, size.bottom().saturating_sub(1), size.width, 1.min(size.height));
+    if let (InteractionMode::Move, Some(move_state)) = (&app.mode, &app.move_state) {
+        let event = &move_state.event;
+        let start = event.start_datetime + move_state.offset;
+        let when = if event.all_day {
+            start.naive_utc().format("%a %b %-d").to_string()
+        } else {
+            let start = app.to_local(start);
+            format!("{} {}", start.format("%a %b %-d"), start.format(app.config.ui.time_pattern()))
+        };
+        f.render_widget(Clear, banner_area);
+        f.render_widget(
+            Paragraph::new(format!(
+                " Move '{}' to {}  {}/{} day  {}/{} {}  {} save  {} cancel ",
+                event.title,
+                when,
+                app.keys.label(Action::Left),
+                app.keys.label(Action::Right),
+                app.keys.label(Action::Up),
+                app.keys.label(Action::Down),
+                if event.all_day { "week".to_string() } else { format!("{} min", app.slot.num_minutes()) },
+                app.keys.label(Action::Select),
+                app.keys.label(Action::Back)
+            ))
+            .style(app.theme.status),
+            banner_area,
+        );
+    }
+    if let Some(message) = app.banner.borrow().as_deref() {
+        f.render_widget(Clear, banner_area);
+        f.render_widget(
+            Paragraph::new(format!(" {} ", message)).style(app.theme.banner),
+            banner_area,
+        );
+    }
+}
+
+/// Bordered block in the theme's border style.
+pub fn bordered<'a>(app: &App) -> Block<'a> {
+    Block::default().borders(Borders::ALL).border_style(app.theme.border)
+}
+
+/// `title` of a view, followed by the tag filter while one is active.
+pub fn view_title(app: &App, title: String) -> String {
+    if app.tag_filter.is_empty() {
+        title
+    } else {
+        format!("{}  [tags: {}]", title, app.tag_filter)
+    }
+}
+
+/// Tags of an event as "#tag" chips, e.g. "#focus #travel".
+pub fn tag_chips(tags: &[String]) -> String {
+    tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ")
+}
+
+/// Block style of an event in its calendar color, reversed when it is focused.
+pub fn event_style(app: &App, event: &Event) -> Style {
+    let style = app.theme.event.bg(event_color(app, event.calendar_id));
+    if app.is_focused(event) {
+        style.add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    } else {
+        style
+    }
+}
+
+/// Style of a weekday name, in the weekend style on Saturday and Sunday.
+pub fn weekday_style(app: &App, weekday: Weekday) -> Style {
+    if weekday.num_days_from_monday() >= 5 {
+        app.theme.weekend
+    } else {
+        Style::default()
+    }
+}
+
+/// Style of a day cell or column header: the weekend and today slots
+/// patched over `base`.
+pub fn day_style(app: &App, date: NaiveDate, base: Style) -> Style {
+    let mut style = base.patch(weekday_style(app, date.weekday()));
+    if date == app.today() {
+        style = style.patch(app.theme.today);
+    }
+    style
+}
+
+/// Color of the calendar an event belongs to, or the theme's event color
+/// when the calendar has none.
+pub fn event_color(app: &App, calendar_id: Option<i64>) -> Color {
+    app.calendar(calendar_id)
+        .and_then(|calendar| calendar.color.as_deref()?.parse().ok())
+        .or(app.theme.event.bg)
+        .unwrap_or(Color::Cyan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{calendar::Calendar, config::Config},
+        storage::db::{create_calendar, get_calendars, open_memory_db},
+    };
+
+    #[test]
+    fn colors_events_by_theme_unless_their_calendar_has_a_color() {
+        let conn = open_memory_db();
+        let personal = get_calendars(&conn).unwrap()[0].clone();
+        assert_eq!(personal.color, None);
+        let work = Calendar {
+            id: None,
+            name: "Work".to_string(),
+            color: Some("green".to_string()),
+            visible: true,
+        };
+        let work = create_calendar(&conn, &work).unwrap();
+        let mut config = Config::default();
+        config.theme.preset = "light".to_string();
+        let app = App::new(config, conn);
+
+        assert_eq!(event_color(&app, personal.id), Color::LightCyan);
+        assert_eq!(event_color(&app, Some(work)), Color::Green);
+    }
 }
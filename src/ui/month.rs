@@ -1,11 +1,17 @@
-use crate::{app::App, storage::db::get_events_in_range};
+use crate::{
+    app::App,
+    models::event::Event,
+    ui::{bordered, day_style, event_color, event_style, view_title, weekday_style},
+};
 use chrono::{Datelike, Month, NaiveDate};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::Style,
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Cell, Row, Table},
     Frame,
 };
+use std::collections::HashMap;
 
 pub fn draw_month_view(f: &mut Frame, app: &App, area: Rect) {
     let year = app.selected_date.year();
@@ -17,7 +23,7 @@ pub fn draw_month_view(f: &mut Frame, app: &App, area: Rect) {
     let title = format!("{} {}", month_name, year);
 
     let first_day_of_month =
-        NaiveDate::from_ymd_opt(year, month, 1).unwrap_or_else(|| app.selected_date);
+        NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(app.selected_date);
     let last_day_of_month = if month == 12 {
         NaiveDate::from_ymd_opt(year + 1, 1, 1)
             .and_then(|d| d.pred_opt())
@@ -28,19 +34,18 @@ pub fn draw_month_view(f: &mut Frame, app: &App, area: Rect) {
             .unwrap_or(first_day_of_month)
     };
 
-    let start_timestamp = first_day_of_month
-        .and_hms_opt(0, 0, 0)
-        .map(|dt| dt.and_utc().timestamp())
-        .unwrap_or_default();
-    let end_timestamp = last_day_of_month
-        .and_hms_opt(23, 59, 59)
-        .map(|dt| dt.and_utc().timestamp())
-        .unwrap_or_default();
-
-    let events = get_events_in_range(&app.conn, start_timestamp, end_timestamp).unwrap_or_default();
-    let mut event_days = std::collections::HashSet::new();
+    let (start_timestamp, end_timestamp) = app.day_range(first_day_of_month, last_day_of_month);
+
+    let events = app.events_in_range(start_timestamp, end_timestamp);
+    let (spanning_events, events): (Vec<Event>, Vec<Event>) =
+        events.into_iter().partition(|e| e.is_multi_day(app.timezone));
+    // Single-day events by their local date, in order of start.
+    let mut day_events: HashMap<NaiveDate, Vec<Event>> = HashMap::new();
     for event in events {
-        event_days.insert(event.start_datetime.day());
+        day_events.entry(app.to_local(event.start_datetime).date()).or_default().push(event);
+    }
+    for events in day_events.values_mut() {
+        events.sort_by_key(|e| e.start_datetime);
     }
 
     let chunks = Layout::default()
@@ -48,19 +53,93 @@ pub fn draw_month_view(f: &mut Frame, app: &App, area: Rect) {
         .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
         .split(area);
 
-    let header_block = Block::default().title(title).borders(Borders::NONE);
+    let header_block = Block::default().title(view_title(app, title)).borders(Borders::NONE);
     f.render_widget(header_block, chunks[0]);
 
-    let table = month_table(app, &event_days);
+    // Seven percentage columns inside the borders; bars are padded to this width.
+    let cell_width = (chunks[1].width.saturating_sub(2) as usize * 14 / 100).max(1);
+    // Weeks share the height left below the borders and the header, keeping
+    // room for the day number and one event.
+    let weeks = (first_day_of_month.weekday().days_since(app.week_start) + last_day_of_month.day()).div_ceil(7);
+    let cell_height = (chunks[1].height.saturating_sub(4) / weeks as u16).max(2);
+    let table = month_table(app, &day_events, &spanning_events, cell_width, cell_height);
     f.render_widget(table, chunks[1]);
 }
 
-fn month_table<'a>(app: &App, event_days: &std::collections::HashSet<u32>) -> Table<'a> {
-    let header_cells = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+/// Line drawn in a day cell for an all-day or multi-day event: the title where a
+/// bar starts (on the event's first day or at the start of a week row) and a
+/// plain continuation otherwise.
+fn spanning_bar<'a>(app: &App, event: &Event, date: NaiveDate, cell_width: usize) -> Line<'a> {
+    let (first, _) = event.date_span(app.timezone);
+    let label = if date == first || date.weekday() == app.week_start {
+        event.title.clone()
+    } else {
+        String::new()
+    };
+    let label: String = label.chars().take(cell_width).collect();
+    Line::from(Span::styled(
+        format!("{:<width$}", label, width = cell_width),
+        event_style(app, event),
+    ))
+}
+
+/// Line drawn in a day cell for a timed event: its start in the calendar
+/// color and its title, or both in the event style while focused.
+fn timed_line<'a>(app: &App, event: &Event, cell_width: usize) -> Line<'a> {
+    let time = app.to_local(event.start_datetime).format(app.config.ui.time_pattern()).to_string();
+    let title: String = event.title.chars().take(cell_width.saturating_sub(time.chars().count() + 1)).collect();
+    if app.is_focused(event) {
+        let text: String = format!("{} {}", time, title).chars().take(cell_width).collect();
+        return Line::from(Span::styled(text, event_style(app, event)));
+    }
+    Line::from(vec![
+        Span::styled(time, Style::default().fg(event_color(app, event.calendar_id))),
+        Span::raw(" "),
+        Span::raw(title),
+    ])
+}
+
+/// Events listed in a day cell with room for `capacity` lines: spanning ones
+/// first, then timed ones, and the number left out. The focused event takes
+/// the last line shown rather than being left out.
+fn cell_events<'e>(
+    app: &App,
+    spanning_events: &'e [Event],
+    day_events: Option<&'e Vec<Event>>,
+    date: NaiveDate,
+    capacity: usize,
+) -> (Vec<&'e Event>, usize) {
+    let mut events: Vec<&Event> = spanning_events
         .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
+        .filter(|e| e.covers_date(app.timezone, date))
+        .chain(day_events.into_iter().flatten())
+        .collect();
+    if events.len() <= capacity {
+        return (events, 0);
+    }
+    // One line goes to the "+N more" marker.
+    let shown = capacity.saturating_sub(1);
+    if let Some(index) = events.iter().position(|e| app.is_focused(e)).filter(|&i| i >= shown && shown > 0) {
+        events.swap(index, shown - 1);
+    }
+    let hidden = events.len() - shown;
+    events.truncate(shown);
+    (events, hidden)
+}
+
+fn month_table<'a>(
+    app: &App,
+    day_events: &HashMap<NaiveDate, Vec<Event>>,
+    spanning_events: &[Event],
+    cell_width: usize,
+    cell_height: u16,
+) -> Table<'a> {
+    let header_cells = app
+        .weekdays()
+        .into_iter()
+        .map(|weekday| Cell::from(weekday.to_string()).style(weekday_style(app, weekday)));
     let header = Row::new(header_cells)
-        .style(Style::default().bg(Color::Blue))
+        .style(app.theme.header)
         .height(1)
         .bottom_margin(1);
 
@@ -68,8 +147,8 @@ fn month_table<'a>(app: &App, event_days: &std::collections::HashSet<u32>) -> Ta
     let month = app.selected_date.month();
 
     let first_day =
-        NaiveDate::from_ymd_opt(year, month, 1).unwrap_or_else(|| app.selected_date);
-    let weekday_of_first = first_day.weekday().num_days_from_monday();
+        NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(app.selected_date);
+    let weekday_of_first = first_day.weekday().days_since(app.week_start);
 
     let mut rows = vec![];
     let mut days: Vec<Cell> = (0..weekday_of_first).map(|_| Cell::from("")).collect();
@@ -83,18 +162,28 @@ fn month_table<'a>(app: &App, event_days: &std::collections::HashSet<u32>) -> Ta
     .num_days();
 
     for day in 1..=days_in_month {
-        let day_str = if event_days.contains(&(day as u32)) {
-            format!("{}\n•", day)
-        } else {
-            day.to_string()
-        };
-        let mut cell = Cell::from(day_str);
+        let date = first_day + chrono::Duration::days(day - 1);
+        let mut lines = vec![Line::from(day.to_string())];
+        let capacity = cell_height.saturating_sub(1) as usize;
+        let (events, hidden) = cell_events(app, spanning_events, day_events.get(&date), date, capacity);
+        for event in events {
+            lines.push(if event.is_multi_day(app.timezone) {
+                spanning_bar(app, event, date, cell_width)
+            } else {
+                timed_line(app, event, cell_width)
+            });
+        }
+        if hidden > 0 {
+            lines.push(Line::styled(format!("+{} more", hidden), app.theme.muted));
+        }
+        let mut style = day_style(app, date, Style::default());
         if day as u32 == app.selected_date.day() {
-            cell = cell.style(Style::default().bg(Color::Yellow).fg(Color::Black));
+            style = style.patch(app.theme.selection);
         }
+        let cell = Cell::from(Text::from(lines)).style(style);
         days.push(cell);
         if days.len() == 7 {
-            let row = Row::new(days.drain(..)).height(4);
+            let row = Row::new(days.drain(..)).height(cell_height);
             rows.push(row);
         }
     }
@@ -103,12 +192,12 @@ fn month_table<'a>(app: &App, event_days: &std::collections::HashSet<u32>) -> Ta
         for _ in 0..(7 - remaining_len) {
             days.push(Cell::from(""));
         }
-        rows.push(Row::new(days.drain(..)).height(4));
+        rows.push(Row::new(days.drain(..)).height(cell_height));
     }
 
     let constraints = vec![Constraint::Percentage(14); 7];
     Table::new(rows, constraints)
         .header(header)
-        .block(Block::default().borders(Borders::ALL))
+        .block(bordered(app))
         .column_spacing(1)
 }
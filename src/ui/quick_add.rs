@@ -0,0 +1,45 @@
+use crate::{
+    app::App,
+    models::quick_add::describe,
+    ui::{bordered, event_form::centered_rect},
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Clear, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn draw_quick_add(f: &mut Frame, app: &App, area: Rect) {
+    let Some(quick_add) = &app.quick_add_state else {
+        return;
+    };
+    let popup_area = centered_rect(60, 30, area);
+    f.render_widget(Clear, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .split(popup_area);
+
+    let input = Paragraph::new(format!("{}_", quick_add.input))
+        .block(bordered(app).title("Quick add (Enter: save, Esc: cancel)"));
+    f.render_widget(input, chunks[0]);
+
+    let preview = match &quick_add.preview {
+        _ if quick_add.input.trim().is_empty() => vec![
+            Line::styled("e.g. Lunch with Ana tomorrow 12:30-13:30 at Cafe Nero", app.theme.muted),
+            Line::styled("     standup every weekday 9am for 15m", app.theme.muted),
+        ],
+        Ok(event) => vec![
+            Line::styled(event.title.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            Line::raw(describe(event, app.timezone, app.config.ui.time_pattern())),
+        ],
+        Err(error) => vec![Line::styled(error.clone(), app.theme.error)],
+    };
+    let preview = Paragraph::new(preview)
+        .wrap(Wrap { trim: true })
+        .block(bordered(app).title("Preview"));
+    f.render_widget(preview, chunks[1]);
+}
@@ -0,0 +1,32 @@
+use crate::{
+    app::{App, ScopeAction},
+    models::recurrence::RecurrenceScope,
+    ui::{bordered, event_form::centered_rect},
+};
+use ratatui::{
+    layout::Rect,
+    widgets::{Clear, List, ListItem, ListState},
+    Frame,
+};
+
+pub fn draw_recurrence_scope(f: &mut Frame, app: &App, area: Rect) {
+    if let Some(prompt) = &app.scope_prompt_state {
+        let title = match prompt.action {
+            ScopeAction::Edit => "Edit recurring event",
+            ScopeAction::Delete => "Delete recurring event",
+            ScopeAction::Move(_) => "Move recurring event",
+        };
+        let popup_area = centered_rect(40, 20, area);
+        let items: Vec<ListItem> = RecurrenceScope::ALL
+            .iter()
+            .map(|scope| ListItem::new(scope.label()))
+            .collect();
+        let list = List::new(items)
+            .block(bordered(app).title(title))
+            .highlight_style(app.theme.selection);
+        let mut state = ListState::default().with_selected(Some(prompt.selected));
+
+        f.render_widget(Clear, popup_area);
+        f.render_stateful_widget(list, popup_area, &mut state);
+    }
+}
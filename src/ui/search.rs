@@ -0,0 +1,54 @@
+use crate::{
+    app::App,
+    ui::{bordered, event_form::centered_rect},
+};
+use ratatui::{
+    layout::{Constraint, Direction, Rect},
+    widgets::{Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+pub fn draw_search(f: &mut Frame, app: &App, area: Rect) {
+    let Some(search) = &app.search_state else {
+        return;
+    };
+    let popup_area = centered_rect(60, 60, area);
+    f.render_widget(Clear, popup_area);
+
+    let chunks = ratatui::layout::Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .split(popup_area);
+
+    let input = Paragraph::new(format!("/{}", search.query))
+        .block(bordered(app).title("Search (Enter: jump, Esc: cancel)"));
+    f.render_widget(input, chunks[0]);
+
+    let items: Vec<ListItem> = search
+        .results
+        .iter()
+        .map(|event| {
+            let when = if event.all_day {
+                event.start_datetime.format("%Y-%m-%d  all day").to_string()
+            } else {
+                let start = app.to_local(event.start_datetime);
+                format!("{}  {}", start.format("%Y-%m-%d"), start.format(app.config.ui.time_pattern()))
+            };
+            let mut line = format!("{}  {}", when, event.title);
+            if let Some(location) = event.location.as_deref().filter(|l| !l.is_empty()) {
+                line.push_str(&format!(" @ {}", location));
+            }
+            ListItem::new(line)
+        })
+        .collect();
+    let title = if search.query.trim().is_empty() || !search.results.is_empty() {
+        "Results".to_string()
+    } else {
+        "No matches".to_string()
+    };
+    let list = List::new(items)
+        .block(bordered(app).title(title))
+        .highlight_style(app.theme.selection);
+    let mut state = ListState::default().with_selected((!search.results.is_empty()).then_some(search.selected));
+    f.render_stateful_widget(list, chunks[1], &mut state);
+}
@@ -0,0 +1,43 @@
+use crate::{
+    app::App,
+    ui::{bordered, event_form::centered_rect, tag_chips},
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::Line,
+    widgets::{Clear, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn draw_tag_filter(f: &mut Frame, app: &App, area: Rect) {
+    let Some(filter) = &app.tag_filter_state else {
+        return;
+    };
+    let popup_area = centered_rect(60, 30, area);
+    f.render_widget(Clear, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .split(popup_area);
+
+    let input = Paragraph::new(format!("{}_", filter.input))
+        .block(bordered(app).title("Filter by tags (Enter: apply, Esc: cancel)"));
+    f.render_widget(input, chunks[0]);
+
+    let mut lines = match &filter.error {
+        Some(error) => vec![Line::styled(error.clone(), app.theme.error)],
+        None => vec![
+            Line::styled("e.g. work and not 1:1, -travel (focus or review)", app.theme.muted),
+            Line::styled("Empty to show all events", app.theme.muted),
+        ],
+    };
+    if !filter.known_tags.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(tag_chips(&filter.known_tags), app.theme.tag));
+    }
+    let help = Paragraph::new(lines)
+        .wrap(Wrap { trim: true })
+        .block(bordered(app).title("Tags"));
+    f.render_widget(help, chunks[1]);
+}
@@ -1,57 +1,68 @@
-use crate::{app::App, storage::db::get_events_in_range};
-use chrono::{Datelike, Timelike, Weekday};
+use crate::{
+    app::App,
+    models::{event::Event, timezone::local_instants},
+    ui::{
+        columns::{pack, slot_entries, slot_text, table_column_widths},
+        day::{all_day_cell, slot_time_cell, time_column},
+        bordered, day_style, view_title,
+    },
+};
+use chrono::{Datelike, Weekday};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::{Modifier, Style},
     widgets::{Block, Borders, Cell, Row, Table},
     Frame,
 };
+use std::collections::HashSet;
 
 pub fn draw_week_view(f: &mut Frame, app: &App, area: Rect) {
-    let year = app.selected_date.year();
-    let week = app.selected_date.iso_week().week();
-    let title = format!("Year {} - Week {}", year, week);
+    // Weeks are numbered like ISO weeks: by the ISO week of their Thursday.
+    let first_day = app.week_first_day(app.selected_date);
+    let thursday = first_day + chrono::Duration::days(Weekday::Thu.days_since(app.week_start) as i64);
+    let week = thursday.iso_week();
+    let title = format!("Year {} - Week {}", week.year(), week.week());
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
         .split(area);
 
-    let header_block = Block::default().title(title).borders(Borders::NONE);
+    let header_block = Block::default().title(view_title(app, title)).borders(Borders::NONE);
     f.render_widget(header_block, chunks[0]);
 
-    let table = week_table(app);
+    let table = week_table(app, chunks[1]);
     f.render_widget(table, chunks[1]);
 }
 
-fn week_table<'a>(app: &App) -> Table<'a> {
-    let header_cells = ["Time", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
-        .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
+fn week_table<'a>(app: &App, area: Rect) -> Table<'a> {
+    let first_day_of_week = app.week_first_day(app.selected_date);
+    let last_day_of_week = first_day_of_week + chrono::Duration::days(6);
+
+    let mut header_cells = vec![Cell::from("Time")];
+    for day_offset in 0..7 {
+        let date = first_day_of_week + chrono::Duration::days(day_offset);
+        header_cells.push(Cell::from(date.format("%a").to_string()).style(day_style(app, date, Style::default())));
+    }
     let header = Row::new(header_cells)
-        .style(Style::default().bg(Color::Blue))
+        .style(app.theme.header)
         .height(1)
         .bottom_margin(1);
 
-    let year = app.selected_date.year();
-    let week = app.selected_date.iso_week().week();
-    let first_day_of_week = chrono::NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
-        .unwrap_or(app.selected_date);
-    let last_day_of_week = chrono::NaiveDate::from_isoywd_opt(year, week, Weekday::Sun)
-        .unwrap_or(app.selected_date);
+    let (start_timestamp, end_timestamp) = app.day_range(first_day_of_week, last_day_of_week);
 
-    let start_timestamp = first_day_of_week
-        .and_hms_opt(0, 0, 0)
-        .map(|dt| dt.and_utc().timestamp())
-        .unwrap_or_default();
-    let end_timestamp = last_day_of_week
-        .and_hms_opt(23, 59, 59)
-        .map(|dt| dt.and_utc().timestamp())
-        .unwrap_or_default();
+    let events = app.events_in_range(start_timestamp, end_timestamp);
+    let (banner_events, events): (Vec<Event>, Vec<Event>) =
+        events.into_iter().partition(|e| e.is_multi_day(app.timezone));
 
-    let events = get_events_in_range(&app.conn, start_timestamp, end_timestamp).unwrap_or_default();
-
-    let mut rows = vec![];
+    let mut banner_cells = vec![Cell::from("All day")];
+    let mut banner_height = 1;
+    for day_offset in 0..7 {
+        let (cell, height) = all_day_cell(app, &banner_events, first_day_of_week + chrono::Duration::days(day_offset));
+        banner_cells.push(cell);
+        banner_height = banner_height.max(height);
+    }
+    let mut rows = vec![Row::new(banner_cells).height(banner_height).bottom_margin(1)];
     let start_hour = app
         .config
         .calendar
@@ -69,43 +80,8 @@ fn week_table<'a>(app: &App) -> Table<'a> {
         .and_then(|h| h.parse::<u32>().ok())
         .unwrap_or(18);
 
-    for hour in start_hour..end_hour {
-        for minute in [0, 30] {
-            let time_cell = Cell::from(format!("{:02}:{:02}", hour, minute));
-            let mut cells = vec![time_cell];
-            for day_offset in 0..7 {
-                let current_day = first_day_of_week + chrono::Duration::days(day_offset);
-                if let Some(current_time) = chrono::NaiveTime::from_hms_opt(hour, minute, 0) {
-                    let mut event_text = String::new();
-                    let mut cell_style = Style::default();
-
-                    for event in &events {
-                        let event_start_time = event.start_datetime.time();
-                        let event_end_time = event.end_datetime.time();
-                        if event.start_datetime.date_naive() == current_day
-                            && current_time >= event_start_time
-                            && current_time < event_end_time
-                        {
-                            event_text.push_str(&event.title);
-                            cell_style = cell_style.bg(Color::Cyan);
-                        }
-                    }
-                    let mut cell = Cell::from(event_text).style(cell_style);
-                    if current_day == app.selected_date
-                        && hour == app.selected_time.hour()
-                        && minute == app.selected_time.minute()
-                    {
-                        cell = cell.style(Style::default().bg(Color::Yellow));
-                    }
-                    cells.push(cell);
-                }
-            }
-            rows.push(Row::new(cells).height(2));
-        }
-    }
-
     let constraints = vec![
-        Constraint::Length(6),
+        time_column(app),
         Constraint::Percentage(13),
         Constraint::Percentage(13),
         Constraint::Percentage(13),
@@ -114,8 +90,39 @@ fn week_table<'a>(app: &App) -> Table<'a> {
         Constraint::Percentage(13),
         Constraint::Percentage(13),
     ];
+    let widths = table_column_widths(&constraints, area);
+    // Timed events never cross midnight; those that do are in the banner.
+    let days: Vec<(chrono::NaiveDate, Vec<Event>)> = (0..7)
+        .map(|day_offset| {
+            let day = first_day_of_week + chrono::Duration::days(day_offset);
+            (day, events.iter().filter(|e| e.covers_date(app.timezone, day)).cloned().collect())
+        })
+        .collect();
+    let placements: Vec<_> = days.iter().map(|(_, events)| pack(events)).collect();
+    let mut titled = vec![HashSet::new(); 7];
+
+    for time in app.slot_times(start_hour, end_hour) {
+        // The label marks the slot as the day view does for the selected date.
+        let selected_instants = local_instants(app.timezone, app.selected_date.and_time(time));
+        let mut cells = vec![slot_time_cell(app, time, &selected_instants)];
+        for (index, (current_day, day_events)) in days.iter().enumerate() {
+            let slot_instants = local_instants(app.timezone, current_day.and_time(time));
+            let background = if *current_day == app.selected_date && time == app.selected_time {
+                app.theme.selection
+            } else if slot_instants.is_empty() {
+                // Slots skipped by DST are filled in the muted color.
+                app.theme.muted.add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            let entries = slot_entries(day_events, &placements[index], &slot_instants, app.slot, &mut titled[index]);
+            let width = widths.get(index + 1).copied().unwrap_or_default();
+            cells.push(Cell::from(slot_text(app, &entries, width, background, false)).style(background));
+        }
+        rows.push(Row::new(cells).height(2));
+    }
     Table::new(rows, constraints)
         .header(header)
-        .block(Block::default().borders(Borders::ALL))
+        .block(bordered(app))
         .column_spacing(1)
 }
@@ -1,9 +1,12 @@
-use crate::app::App;
+use crate::{
+    app::App,
+    ui::{bordered, day_style, view_title, weekday_style},
+};
 use chrono::{Datelike, Month, NaiveDate};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    widgets::{Block, Borders, Cell, Row, Table},
+    style::{Modifier, Style},
+    widgets::{Cell, Row, Table},
     Frame,
 };
 
@@ -11,7 +14,7 @@ pub fn draw_year_view(f: &mut Frame, app: &App, area: Rect) {
     let year = app.selected_date.year();
     let title = format!("Year {}", year);
 
-    let block = Block::default().title(title).borders(Borders::ALL);
+    let block = bordered(app).title(view_title(app, title));
     f.render_widget(block, area);
 
     let inner_area = area.inner(ratatui::layout::Margin {
@@ -24,6 +27,13 @@ pub fn draw_year_view(f: &mut Frame, app: &App, area: Rect) {
         .constraints(vec![Constraint::Ratio(1, 4); 4])
         .split(inner_area);
 
+    let focused_date = app.focused_event().map(|event| {
+        if event.all_day {
+            event.start_datetime.date_naive()
+        } else {
+            app.to_local(event.start_datetime).date()
+        }
+    });
     for (i, chunk) in chunks.iter().enumerate() {
         let month_chunks = Layout::default()
             .direction(Direction::Horizontal)
@@ -32,23 +42,24 @@ pub fn draw_year_view(f: &mut Frame, app: &App, area: Rect) {
 
         for (j, month_chunk) in month_chunks.iter().enumerate() {
             let month_index = (i * 3 + j + 1) as u32;
-            let month_table = mini_month_table(year, month_index);
+            let month_table = mini_month_table(app, year, month_index, focused_date);
             f.render_widget(month_table, *month_chunk);
         }
     }
 }
 
-fn mini_month_table<'a>(year: i32, month: u32) -> Table<'a> {
+/// Calendar of one month; the day of the focused event is shown reversed.
+fn mini_month_table<'a>(app: &App, year: i32, month: u32, focused_date: Option<NaiveDate>) -> Table<'a> {
     let month_name = Month::try_from(month as u8)
         .unwrap_or(Month::January)
         .name();
-    let header_cells = ["M", "T", "W", "T", "F", "S", "S"]
-        .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
-    let header = Row::new(header_cells).height(1);
+    let header_cells = app.weekdays().into_iter().map(|weekday| {
+        Cell::from(weekday.to_string()[..1].to_string()).style(weekday_style(app, weekday))
+    });
+    let header = Row::new(header_cells).style(app.theme.header).height(1);
 
     let first_day = NaiveDate::from_ymd_opt(year, month, 1).unwrap_or_default();
-    let weekday_of_first = first_day.weekday().num_days_from_monday();
+    let weekday_of_first = first_day.weekday().days_since(app.week_start);
 
     let mut rows = vec![];
     let mut days: Vec<Cell> = (0..weekday_of_first).map(|_| Cell::from("")).collect();
@@ -62,7 +73,12 @@ fn mini_month_table<'a>(year: i32, month: u32) -> Table<'a> {
     .num_days();
 
     for day in 1..=days_in_month {
-        days.push(Cell::from(day.to_string()));
+        let date = first_day + chrono::Duration::days(day - 1);
+        let mut style = day_style(app, date, Style::default());
+        if focused_date == Some(date) {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        days.push(Cell::from(day.to_string()).style(style));
         if days.len() == 7 {
             let row = Row::new(days.drain(..));
             rows.push(row);
@@ -78,9 +94,5 @@ fn mini_month_table<'a>(year: i32, month: u32) -> Table<'a> {
 
     Table::new(rows, vec![Constraint::Length(2); 7])
         .header(header)
-        .block(
-            Block::default()
-                .title(month_name)
-                .borders(Borders::ALL),
-        )
+        .block(bordered(app).title(month_name))
 }